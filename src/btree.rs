@@ -0,0 +1,452 @@
+//! A counter backed by a `BTreeMap`, which keeps its items in sorted order.
+
+use num_traits::{One, Zero};
+
+use alloc::collections::btree_map;
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::iter;
+use core::ops::{Add, AddAssign, Deref, DerefMut, Index, Sub, SubAssign};
+
+/// A counter whose items only need to be `Ord`, and are iterated in sorted order.
+///
+/// It offers the core counting API of [`Counter`](struct.Counter.html).
+///
+/// ```rust
+/// # use counter::BTreeCounter;
+/// let counter = "abracadabra".chars().collect::<BTreeCounter<_>>();
+/// let table = counter.iter().map(|(&c, &n)| (c, n)).collect::<Vec<_>>();
+/// assert_eq!(table, vec![('a', 5), ('b', 2), ('c', 1), ('d', 1), ('r', 2)]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct BTreeCounter<T: Ord, N = usize> {
+    map: BTreeMap<T, N>,
+    // necessary for `Index::index` since we cannot declare generic `static` variables.
+    zero: N,
+}
+
+impl<T, N> BTreeCounter<T, N>
+where
+    T: Ord,
+    N: Zero,
+{
+    /// Create a new, empty `BTreeCounter`
+    pub fn new() -> BTreeCounter<T, N> {
+        BTreeCounter {
+            map: BTreeMap::new(),
+            zero: N::zero(),
+        }
+    }
+}
+
+impl<T, N> BTreeCounter<T, N>
+where
+    T: Ord,
+    N: PartialOrd + AddAssign + Zero + One,
+{
+    /// Create a new `BTreeCounter` initialized with the given iterable
+    pub fn init<I>(iterable: I) -> BTreeCounter<T, N>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut counter = BTreeCounter::new();
+        counter.update(iterable);
+        counter
+    }
+
+    /// Add the counts of the elements from the given iterable to this counter
+    pub fn update<I>(&mut self, iterable: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        for item in iterable.into_iter() {
+            let entry = self.map.entry(item).or_insert_with(N::zero);
+            *entry += N::one();
+        }
+    }
+}
+
+impl<T, N> BTreeCounter<T, N>
+where
+    T: Ord,
+    N: PartialOrd + SubAssign + Zero + One,
+{
+    /// Remove the counts of the elements from the given iterable to this counter
+    ///
+    /// Non-positive counts are automatically removed
+    ///
+    /// ```rust
+    /// # use counter::BTreeCounter;
+    /// let mut counter = "abbccc".chars().collect::<BTreeCounter<_>>();
+    /// counter.subtract("abba".chars());
+    /// assert_eq!(counter.into_map().into_iter().collect::<Vec<_>>(), vec![('c', 3)]);
+    /// ```
+    pub fn subtract<I>(&mut self, iterable: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        for item in iterable.into_iter() {
+            let mut remove = false;
+            if let Some(entry) = self.map.get_mut(&item) {
+                if *entry > N::zero() {
+                    *entry -= N::one();
+                }
+                remove = *entry == N::zero();
+            }
+            if remove {
+                self.map.remove(&item);
+            }
+        }
+    }
+}
+
+impl<T, N> BTreeCounter<T, N>
+where
+    T: Ord,
+{
+    /// Consumes this counter and returns a BTreeMap mapping the items to the counts.
+    pub fn into_map(self) -> BTreeMap<T, N> {
+        self.map
+    }
+
+    /// Sum all the counts.
+    pub fn total(&self) -> N
+    where
+        N: Clone + Zero,
+    {
+        self.map
+            .values()
+            .fold(N::zero(), |total, count| total + count.clone())
+    }
+}
+
+impl<T, N> BTreeCounter<T, N>
+where
+    T: Ord + Clone,
+    N: Clone + Ord,
+{
+    /// Create a vector of `(elem, frequency)` pairs, sorted most to least common.
+    ///
+    /// Since the items are already sorted, ties are broken by the natural ordering of the items.
+    ///
+    /// ```rust
+    /// # use counter::BTreeCounter;
+    /// let mc = "abracadabra".chars().collect::<BTreeCounter<_>>().most_common();
+    /// let expect = vec![('a', 5), ('b', 2), ('r', 2), ('c', 1), ('d', 1)];
+    /// assert_eq!(mc, expect);
+    /// ```
+    pub fn most_common(&self) -> Vec<(T, N)> {
+        let mut items = self.map
+            .iter()
+            .map(|(key, count)| (key.clone(), count.clone()))
+            .collect::<Vec<_>>();
+        // the sort is stable, so equal counts stay in key order
+        items.sort_by(|(_, a_count), (_, b_count)| b_count.cmp(a_count));
+        items
+    }
+}
+
+impl<T, N> Default for BTreeCounter<T, N>
+where
+    T: Ord,
+    N: Zero,
+{
+    fn default() -> BTreeCounter<T, N> {
+        BTreeCounter::new()
+    }
+}
+
+impl<T, N> PartialEq for BTreeCounter<T, N>
+where
+    T: Ord,
+    N: PartialEq,
+{
+    /// Two counters are equal if every item has the same count in both, where an item which
+    /// is missing from one counter has a count of zero there.
+    fn eq(&self, other: &BTreeCounter<T, N>) -> bool {
+        self.map
+            .iter()
+            .all(|(item, count)| other.map.get(item).unwrap_or(&other.zero) == count)
+            && other
+                .map
+                .iter()
+                .all(|(item, count)| self.map.contains_key(item) || *count == self.zero)
+    }
+}
+
+impl<T, N> Eq for BTreeCounter<T, N>
+where
+    T: Ord,
+    N: Eq,
+{
+}
+
+impl<T, N> AddAssign for BTreeCounter<T, N>
+where
+    T: Ord,
+    N: Zero + AddAssign,
+{
+    /// Add another counter to this counter
+    ///
+    /// `c += d;` -> `c[x] += d[x]` for all `x`
+    fn add_assign(&mut self, rhs: Self) {
+        for (key, value) in rhs.map.into_iter() {
+            let entry = self.map.entry(key).or_insert_with(N::zero);
+            *entry += value;
+        }
+    }
+}
+
+impl<T, N> Add for BTreeCounter<T, N>
+where
+    T: Ord,
+    N: Zero + AddAssign,
+{
+    type Output = BTreeCounter<T, N>;
+
+    /// Add two counters together.
+    ///
+    /// `out = c + d;` -> `out[x] == c[x] + d[x]` for all `x`
+    ///
+    /// ```rust
+    /// # use counter::BTreeCounter;
+    /// let c = "aaab".chars().collect::<BTreeCounter<_>>();
+    /// let d = "abb".chars().collect::<BTreeCounter<_>>();
+    ///
+    /// let e = c + d;
+    ///
+    /// assert_eq!(e.into_map().into_iter().collect::<Vec<_>>(), vec![('a', 4), ('b', 3)]);
+    /// ```
+    fn add(mut self, rhs: BTreeCounter<T, N>) -> Self::Output {
+        self += rhs;
+        self
+    }
+}
+
+impl<T, N> SubAssign for BTreeCounter<T, N>
+where
+    T: Ord,
+    N: PartialOrd + SubAssign + Zero,
+{
+    /// Subtract (keeping only positive values).
+    ///
+    /// `c -= d;` -> `c[x] -= d[x]` for all `x`,
+    /// keeping only items with a value greater than N::zero().
+    fn sub_assign(&mut self, rhs: Self) {
+        for (key, value) in rhs.map.into_iter() {
+            let mut remove = false;
+            if let Some(entry) = self.map.get_mut(&key) {
+                if *entry > value {
+                    *entry -= value;
+                } else {
+                    remove = true;
+                }
+            }
+            if remove {
+                self.map.remove(&key);
+            }
+        }
+    }
+}
+
+impl<T, N> Sub for BTreeCounter<T, N>
+where
+    T: Ord,
+    N: PartialOrd + SubAssign + Zero,
+{
+    type Output = BTreeCounter<T, N>;
+
+    /// Subtract (keeping only positive values).
+    ///
+    /// `out = c - d;` -> `out[x] == c[x] - d[x]` for all `x`,
+    /// keeping only items with a value greater than N::zero().
+    ///
+    /// ```rust
+    /// # use counter::BTreeCounter;
+    /// let c = "aaab".chars().collect::<BTreeCounter<_>>();
+    /// let d = "abb".chars().collect::<BTreeCounter<_>>();
+    ///
+    /// let e = c - d;
+    ///
+    /// assert_eq!(e.into_map().into_iter().collect::<Vec<_>>(), vec![('a', 2)]);
+    /// ```
+    fn sub(mut self, rhs: BTreeCounter<T, N>) -> Self::Output {
+        self -= rhs;
+        self
+    }
+}
+
+impl<T, N> Deref for BTreeCounter<T, N>
+where
+    T: Ord,
+{
+    type Target = BTreeMap<T, N>;
+    fn deref(&self) -> &BTreeMap<T, N> {
+        &self.map
+    }
+}
+
+impl<T, N> DerefMut for BTreeCounter<T, N>
+where
+    T: Ord,
+{
+    fn deref_mut(&mut self) -> &mut BTreeMap<T, N> {
+        &mut self.map
+    }
+}
+
+impl<'q, Q, T, N> Index<&'q Q> for BTreeCounter<T, N>
+where
+    T: Ord + Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    type Output = N;
+
+    /// Index in immutable contexts
+    ///
+    /// Returns a reference to a `zero` value for missing keys.
+    ///
+    /// ```rust
+    /// # use counter::BTreeCounter;
+    /// let counter = BTreeCounter::<_>::init("aabbcc".chars());
+    /// assert_eq!(counter[&'a'], 2);
+    /// assert_eq!(counter[&'d'], 0);
+    /// ```
+    fn index(&self, key: &'q Q) -> &N {
+        self.map.get(key).unwrap_or(&self.zero)
+    }
+}
+
+impl<T, N> IntoIterator for BTreeCounter<T, N>
+where
+    T: Ord,
+{
+    type Item = (T, N);
+    type IntoIter = btree_map::IntoIter<T, N>;
+
+    /// Consumes the counter, producing an iterator over `(item, count)` pairs in item order.
+    fn into_iter(self) -> Self::IntoIter {
+        self.map.into_iter()
+    }
+}
+
+impl<'a, T, N> IntoIterator for &'a BTreeCounter<T, N>
+where
+    T: Ord,
+{
+    type Item = (&'a T, &'a N);
+    type IntoIter = btree_map::Iter<'a, T, N>;
+
+    /// Iterate over `(&item, &count)` pairs in item order.
+    fn into_iter(self) -> Self::IntoIter {
+        self.map.iter()
+    }
+}
+
+impl<T, N> iter::FromIterator<T> for BTreeCounter<T, N>
+where
+    T: Ord,
+    N: PartialOrd + AddAssign + Zero + One,
+{
+    /// Produce a BTreeCounter from an iterator of items. This is called automatically
+    /// by `iter.collect()`.
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        BTreeCounter::init(iter)
+    }
+}
+
+impl<T, N> iter::FromIterator<(T, N)> for BTreeCounter<T, N>
+where
+    T: Ord,
+    N: AddAssign + Zero,
+{
+    /// `from_iter` creates a counter from `(item, count)` tuples.
+    ///
+    /// The counts of duplicate items are summed.
+    fn from_iter<I: IntoIterator<Item = (T, N)>>(iter: I) -> Self {
+        let mut cnt = BTreeCounter::new();
+        cnt.extend(iter);
+        cnt
+    }
+}
+
+impl<T, N> Extend<T> for BTreeCounter<T, N>
+where
+    T: Ord,
+    N: PartialOrd + AddAssign + Zero + One,
+{
+    /// Extend a counter with the counts of the elements of an iterator.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.update(iter);
+    }
+}
+
+impl<T, N> Extend<(T, N)> for BTreeCounter<T, N>
+where
+    T: Ord,
+    N: AddAssign + Zero,
+{
+    /// Extend a counter with `(item, count)` tuples.
+    ///
+    /// The counts of duplicate items are summed.
+    fn extend<I: IntoIterator<Item = (T, N)>>(&mut self, iter: I) {
+        for (item, item_count) in iter.into_iter() {
+            let entry = self.map.entry(item).or_insert_with(N::zero);
+            *entry += item_count;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eq_ignores_zero_counts() {
+        let mut counter = BTreeCounter::<_>::init("aab".chars());
+        counter.insert('c', 0);
+        assert_eq!(counter, BTreeCounter::init("aba".chars()));
+        assert_eq!(BTreeCounter::init("aba".chars()), counter);
+        assert_ne!(counter, BTreeCounter::init("ab".chars()));
+        assert_ne!(counter, BTreeCounter::init("aabc".chars()));
+    }
+
+    #[test]
+    fn test_sorted_iteration() {
+        let counter = BTreeCounter::<_>::init("cabbac".chars());
+        let items = counter.into_iter().collect::<Vec<_>>();
+        assert_eq!(items, vec![('a', 2), ('b', 2), ('c', 2)]);
+    }
+
+    #[test]
+    fn test_unhashable_items() {
+        #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+        struct Version(u32, u32);
+
+        let counter: BTreeCounter<_> =
+            vec![Version(1, 0), Version(0, 9), Version(1, 0)].into_iter().collect();
+        assert_eq!(
+            counter.most_common(),
+            vec![(Version(1, 0), 2), (Version(0, 9), 1)]
+        );
+        assert_eq!(counter[&Version(2, 0)], 0);
+    }
+
+    #[test]
+    fn test_add_sub() {
+        let d = BTreeCounter::<_>::init("abbccc".chars());
+        let e = BTreeCounter::<_>::init("bccddd".chars());
+
+        assert_eq!(d.clone() + e.clone(), BTreeCounter::init("abbbcccccddd".chars()));
+        assert_eq!(d - e, BTreeCounter::init("abc".chars()));
+    }
+
+    #[test]
+    fn test_subtract() {
+        let mut counter = BTreeCounter::<_>::init("abbccc".chars());
+        counter.subtract("bbccddd".chars());
+        assert_eq!(counter, BTreeCounter::init("ac".chars()));
+        assert_eq!(counter.total(), 2);
+    }
+}
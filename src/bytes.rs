@@ -0,0 +1,310 @@
+use core::cmp::Reverse;
+use core::hash::BuildHasher;
+use core::iter;
+use core::ops::Index;
+
+#[cfg(feature = "std")]
+use std::io::{self, Read};
+
+use alloc::vec::Vec;
+
+use Counter;
+
+/// A counter of bytes, backed by an array of 256 counts rather than a hash map.
+///
+/// Counting bytes this way avoids hashing each one, which makes it much faster than a
+/// `Counter<u8>` for tasks like measuring the byte frequencies of a file.
+///
+/// ```rust
+/// # use counter::ByteCounter;
+/// let mut counter = ByteCounter::new();
+/// counter.update(b"hello world");
+/// assert_eq!(counter[b'l'], 3);
+/// assert_eq!(counter.total(), 11);
+/// assert_eq!(counter.most_common()[0], (b'l', 3));
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ByteCounter {
+    counts: [usize; 256],
+}
+
+impl ByteCounter {
+    /// Create a new, empty `ByteCounter`
+    pub fn new() -> ByteCounter {
+        ByteCounter { counts: [0; 256] }
+    }
+
+    /// Create a new `ByteCounter` initialized with the given bytes
+    pub fn init(bytes: &[u8]) -> ByteCounter {
+        let mut counter = ByteCounter::new();
+        counter.update(bytes);
+        counter
+    }
+
+    /// Add the counts of the given bytes to this counter
+    ///
+    /// Long inputs are counted 64 bytes at a time, eight bytes per load, spread across four
+    /// separate tables so that runs of the same byte don't have to wait for each other's
+    /// counts to be stored.
+    pub fn update(&mut self, bytes: &[u8]) {
+        const CHUNK: usize = 64;
+        // Small enough that the `u32` tables can't overflow.
+        const BLOCK: usize = 1 << 24;
+
+        if bytes.len() < 4 * CHUNK {
+            self.update_simple(bytes);
+            return;
+        }
+        for block in bytes.chunks(BLOCK) {
+            let mut tables = [[0u32; 256]; 4];
+            let mut chunks = block.chunks_exact(CHUNK);
+            for chunk in &mut chunks {
+                for word in chunk.chunks_exact(8) {
+                    let word = u64::from_le_bytes([
+                        word[0], word[1], word[2], word[3], word[4], word[5], word[6], word[7],
+                    ]);
+                    tables[0][(word & 0xff) as usize] += 1;
+                    tables[1][(word >> 8 & 0xff) as usize] += 1;
+                    tables[2][(word >> 16 & 0xff) as usize] += 1;
+                    tables[3][(word >> 24 & 0xff) as usize] += 1;
+                    tables[0][(word >> 32 & 0xff) as usize] += 1;
+                    tables[1][(word >> 40 & 0xff) as usize] += 1;
+                    tables[2][(word >> 48 & 0xff) as usize] += 1;
+                    tables[3][(word >> 56) as usize] += 1;
+                }
+            }
+            for (byte, count) in self.counts.iter_mut().enumerate() {
+                *count += tables.iter().map(|table| table[byte] as usize).sum::<usize>();
+            }
+            self.update_simple(chunks.remainder());
+        }
+    }
+
+    fn update_simple(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.counts[byte as usize] += 1;
+        }
+    }
+
+    /// Add the counts of every byte read from `reader` to this counter, returning the number
+    /// of bytes read
+    ///
+    /// This method requires the `std` feature.
+    ///
+    /// ```rust
+    /// # use counter::ByteCounter;
+    /// let mut counter = ByteCounter::new();
+    /// let read = counter.update_from_reader(&b"abbccc"[..]).unwrap();
+    /// assert_eq!(read, 6);
+    /// assert_eq!(counter[b'c'], 3);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn update_from_reader<R: Read>(&mut self, mut reader: R) -> io::Result<usize> {
+        let mut buffer = [0; 8192];
+        let mut read = 0;
+        loop {
+            match reader.read(&mut buffer) {
+                Ok(0) => return Ok(read),
+                Ok(n) => {
+                    self.update(&buffer[..n]);
+                    read += n;
+                }
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Returns the count of the given byte.
+    pub fn count_of(&self, byte: u8) -> usize {
+        self.counts[byte as usize]
+    }
+
+    /// Set the count of the given byte
+    pub fn set_count(&mut self, byte: u8, count: usize) {
+        self.counts[byte as usize] = count;
+    }
+
+    /// The counts of every byte, indexed by the byte
+    pub fn counts(&self) -> &[usize; 256] {
+        &self.counts
+    }
+
+    /// Sum all the counts
+    pub fn total(&self) -> usize {
+        self.counts.iter().sum()
+    }
+
+    /// The number of distinct bytes which have been counted
+    pub fn len(&self) -> usize {
+        self.counts.iter().filter(|&&count| count > 0).count()
+    }
+
+    /// Returns `true` if no bytes have been counted.
+    pub fn is_empty(&self) -> bool {
+        self.counts.iter().all(|&count| count == 0)
+    }
+
+    /// Reset every count to zero
+    pub fn clear(&mut self) {
+        self.counts = [0; 256];
+    }
+
+    /// An iterator over the bytes which have been counted and their counts, in byte order
+    pub fn iter(&self) -> impl Iterator<Item = (u8, usize)> + '_ {
+        (0..=255u8)
+            .zip(self.counts.iter().cloned())
+            .filter(|&(_, count)| count > 0)
+    }
+
+    /// Create a vector of `(byte, count)` pairs, sorted from the most common byte to the least;
+    /// bytes with the same count are sorted in byte order.
+    pub fn most_common(&self) -> Vec<(u8, usize)> {
+        let mut items = self.iter().collect::<Vec<_>>();
+        items.sort_by_key(|&(_, count)| Reverse(count));
+        items
+    }
+
+    /// Convert this counter into an ordinary `Counter`, which will use the given hasher
+    pub fn into_counter_with_hasher<S>(self, hash_builder: S) -> Counter<u8, usize, S>
+    where
+        S: BuildHasher,
+    {
+        let mut counter = Counter::with_capacity_and_hasher(self.len(), hash_builder);
+        counter.map.extend(self.iter());
+        counter
+    }
+}
+
+impl Default for ByteCounter {
+    fn default() -> ByteCounter {
+        ByteCounter::new()
+    }
+}
+
+impl Index<u8> for ByteCounter {
+    type Output = usize;
+
+    fn index(&self, byte: u8) -> &usize {
+        &self.counts[byte as usize]
+    }
+}
+
+impl iter::FromIterator<u8> for ByteCounter {
+    fn from_iter<I: IntoIterator<Item = u8>>(iter: I) -> ByteCounter {
+        let mut counter = ByteCounter::new();
+        counter.extend(iter);
+        counter
+    }
+}
+
+impl Extend<u8> for ByteCounter {
+    fn extend<I: IntoIterator<Item = u8>>(&mut self, iter: I) {
+        for byte in iter {
+            self.counts[byte as usize] += 1;
+        }
+    }
+}
+
+impl<S> From<ByteCounter> for Counter<u8, usize, S>
+where
+    S: BuildHasher + Default,
+{
+    /// ```rust
+    /// # use counter::{ByteCounter, Counter};
+    /// let counter: Counter<u8> = ByteCounter::init(b"abb").into();
+    /// assert_eq!(counter, Counter::init(b"abb".iter().cloned()));
+    /// ```
+    fn from(counter: ByteCounter) -> Counter<u8, usize, S> {
+        counter.into_counter_with_hasher(S::default())
+    }
+}
+
+impl<'a, S> From<&'a Counter<u8, usize, S>> for ByteCounter
+where
+    S: BuildHasher,
+{
+    /// ```rust
+    /// # use counter::{ByteCounter, Counter};
+    /// let counter = Counter::<_>::init(b"abb".iter().cloned());
+    /// assert_eq!(ByteCounter::from(&counter), ByteCounter::init(b"abb"));
+    /// ```
+    fn from(counter: &'a Counter<u8, usize, S>) -> ByteCounter {
+        let mut bytes = ByteCounter::new();
+        for (&byte, &count) in counter.map.iter() {
+            bytes.counts[byte as usize] = count;
+        }
+        bytes
+    }
+}
+
+impl<S> From<Counter<u8, usize, S>> for ByteCounter
+where
+    S: BuildHasher,
+{
+    fn from(counter: Counter<u8, usize, S>) -> ByteCounter {
+        ByteCounter::from(&counter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_byte_counter() {
+        let mut counter = ByteCounter::init(b"abracadabra");
+        assert_eq!(counter.len(), 5);
+        assert_eq!(counter.count_of(b'a'), 5);
+        assert_eq!(counter[b'z'], 0);
+        assert_eq!(
+            counter.most_common(),
+            vec![(b'a', 5), (b'b', 2), (b'r', 2), (b'c', 1), (b'd', 1)]
+        );
+
+        counter.extend(b"zz".iter().cloned());
+        counter.set_count(b'a', 0);
+        assert_eq!(counter.iter().next(), Some((b'b', 2)));
+        assert_eq!(counter.total(), 8);
+
+        counter.clear();
+        assert!(counter.is_empty());
+    }
+
+    #[test]
+    fn test_update_chunks() {
+        let data = (0..10_000u32)
+            .map(|n| (n.wrapping_mul(2_654_435_761) >> 24) as u8)
+            .collect::<Vec<_>>();
+        for &len in &[0, 1, 63, 255, 256, 257, 1000, 10_000] {
+            let mut expected = [0; 256];
+            for &byte in &data[..len] {
+                expected[byte as usize] += 1;
+            }
+            let mut counter = ByteCounter::init(&data[..len]);
+            assert_eq!(&counter.counts()[..], &expected[..]);
+            counter.update(&data[..len]);
+            assert_eq!(counter.total(), 2 * len);
+        }
+    }
+
+    #[test]
+    fn test_byte_counter_conversions() {
+        let bytes = ByteCounter::init(&[0, 255, 255, 7]);
+        let counter: Counter<u8> = bytes.clone().into();
+        let expected = hashmap! { 0 => 1, 255 => 2, 7 => 1 };
+        assert!(counter.map == expected);
+        assert_eq!(ByteCounter::from(counter), bytes);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_update_from_reader() {
+        let data = (0..20_000).map(|n| (n % 7) as u8).collect::<Vec<_>>();
+        let mut counter = ByteCounter::new();
+        assert_eq!(counter.update_from_reader(&data[..]).unwrap(), 20_000);
+        assert_eq!(counter.total(), 20_000);
+        assert_eq!(counter[0], 2858);
+        assert_eq!(counter[6], 2857);
+    }
+}
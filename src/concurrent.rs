@@ -0,0 +1,433 @@
+//! Counters which can be updated from many threads at once.
+//!
+//! This module requires the `std` feature.
+
+use num_traits::{One, Zero};
+
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash};
+use std::ops::AddAssign;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::thread;
+
+use Counter;
+
+/// A counter whose items are sharded across several mutex-protected maps, so that
+/// many threads can count through a shared reference with little contention.
+///
+/// Use [`snapshot`](#method.snapshot) or [`into_counter`](#method.into_counter) to get an
+/// ordinary [`Counter`](../struct.Counter.html) back.
+///
+/// ```rust
+/// # use counter::concurrent::ConcurrentCounter;
+/// use std::sync::Arc;
+/// use std::thread;
+///
+/// let counter = Arc::new(ConcurrentCounter::<_>::new());
+/// let handles = (0..4)
+///     .map(|_| {
+///         let counter = Arc::clone(&counter);
+///         thread::spawn(move || counter.update("abbccc".chars()))
+///     })
+///     .collect::<Vec<_>>();
+/// for handle in handles {
+///     handle.join().unwrap();
+/// }
+///
+/// let snapshot = counter.snapshot();
+/// assert_eq!(snapshot[&'a'], 4);
+/// assert_eq!(snapshot[&'c'], 12);
+/// ```
+///
+/// A panic in one thread while it holds a shard doesn't prevent other threads from counting:
+/// every update leaves the counts in a valid state, so poisoned locks are simply recovered.
+#[derive(Debug)]
+pub struct ConcurrentCounter<T: Hash + Eq, N = usize, S = RandomState> {
+    shards: Vec<Mutex<HashMap<T, N, S>>>,
+    hash_builder: S,
+}
+
+impl<T, N> ConcurrentCounter<T, N>
+where
+    T: Hash + Eq,
+{
+    /// Create a new, empty `ConcurrentCounter`, with a number of shards suited to the number
+    /// of available CPUs
+    pub fn new() -> ConcurrentCounter<T, N> {
+        let shards = thread::available_parallelism()
+            .map(|n| n.get() * 4)
+            .unwrap_or(16);
+        ConcurrentCounter::with_shards(shards)
+    }
+
+    /// Create a new, empty `ConcurrentCounter` with the given number of shards
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shards` is zero.
+    pub fn with_shards(shards: usize) -> ConcurrentCounter<T, N> {
+        ConcurrentCounter::with_shards_and_hasher(shards, RandomState::new())
+    }
+}
+
+impl<T, N, S> ConcurrentCounter<T, N, S>
+where
+    T: Hash + Eq,
+    S: BuildHasher + Clone,
+{
+    /// Create a new, empty `ConcurrentCounter` with the given number of shards, which will
+    /// use the given hasher to hash the items
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shards` is zero.
+    pub fn with_shards_and_hasher(shards: usize, hash_builder: S) -> ConcurrentCounter<T, N, S> {
+        assert!(shards > 0, "a ConcurrentCounter needs at least one shard");
+        ConcurrentCounter {
+            shards: (0..shards)
+                .map(|_| Mutex::new(HashMap::with_hasher(hash_builder.clone())))
+                .collect(),
+            hash_builder,
+        }
+    }
+}
+
+impl<T, N, S> ConcurrentCounter<T, N, S>
+where
+    T: Hash + Eq,
+    S: BuildHasher,
+{
+    fn shard<Q>(&self, item: &Q) -> MutexGuard<'_, HashMap<T, N, S>>
+    where
+        Q: Hash + ?Sized,
+    {
+        // The shard maps use the same hasher, and hashbrown picks buckets from the low bits, so
+        // take the shard from the high bits to keep each shard's items spread over its buckets.
+        let index = ((self.hash_builder.hash_one(item) >> 32) % self.shards.len() as u64) as usize;
+        self.shards[index]
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Add `count` to the count of the given item, returning its new count
+    pub fn add_count(&self, item: T, count: N) -> N
+    where
+        N: Clone + AddAssign + Zero,
+    {
+        let mut shard = self.shard(&item);
+        let entry = shard.entry(item).or_insert_with(N::zero);
+        *entry += count;
+        entry.clone()
+    }
+
+    /// Add one to the count of the given item, returning its new count
+    ///
+    /// ```rust
+    /// # use counter::concurrent::ConcurrentCounter;
+    /// let counter = ConcurrentCounter::<_>::new();
+    /// assert_eq!(counter.increment("a"), 1);
+    /// assert_eq!(counter.increment("a"), 2);
+    /// ```
+    pub fn increment(&self, item: T) -> N
+    where
+        N: Clone + AddAssign + Zero + One,
+    {
+        self.add_count(item, N::one())
+    }
+
+    /// Add the counts of the elements from the given iterable to this counter
+    pub fn update<I>(&self, iterable: I)
+    where
+        I: IntoIterator<Item = T>,
+        N: AddAssign + Zero + One,
+    {
+        for item in iterable.into_iter() {
+            let mut shard = self.shard(&item);
+            let entry = shard.entry(item).or_insert_with(N::zero);
+            *entry += N::one();
+        }
+    }
+
+    /// Returns the current count of the given item, or zero if it is not present.
+    pub fn count_of<Q>(&self, item: &Q) -> N
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        N: Clone + Zero,
+    {
+        self.shard(item).get(item).cloned().unwrap_or_else(N::zero)
+    }
+
+    /// Copy the current counts into an ordinary `Counter`.
+    ///
+    /// The shards are locked one at a time, so counts which are updated concurrently
+    /// may or may not be reflected in the snapshot.
+    pub fn snapshot(&self) -> Counter<T, N, S>
+    where
+        T: Clone,
+        N: Clone + Zero,
+        S: Clone,
+    {
+        let mut counter = Counter::with_hasher(self.hash_builder.clone());
+        for shard in self.shards.iter() {
+            let shard = shard.lock().unwrap_or_else(PoisonError::into_inner);
+            counter
+                .map
+                .extend(shard.iter().map(|(item, count)| (item.clone(), count.clone())));
+        }
+        counter
+    }
+
+    /// Consumes this counter and returns an ordinary `Counter` with all of its counts.
+    pub fn into_counter(self) -> Counter<T, N, S>
+    where
+        N: Zero,
+    {
+        let mut counter = Counter::with_hasher(self.hash_builder);
+        for shard in self.shards.into_iter() {
+            let shard = shard.into_inner().unwrap_or_else(PoisonError::into_inner);
+            counter.map.extend(shard);
+        }
+        counter
+    }
+}
+
+impl<T, N> Default for ConcurrentCounter<T, N>
+where
+    T: Hash + Eq,
+{
+    fn default() -> ConcurrentCounter<T, N> {
+        ConcurrentCounter::new()
+    }
+}
+
+/// A counter over a fixed set of items, whose counts are atomic integers.
+///
+/// Counting an item which is already known only needs a shared reference and takes no
+/// locks. New items can only be added through a mutable reference, so this suits counters
+/// whose set of items is known up front or rarely changes.
+///
+/// ```rust
+/// # use counter::concurrent::AtomicCounter;
+/// use std::sync::Arc;
+/// use std::thread;
+///
+/// let counter = Arc::new(AtomicCounter::<_>::new(vec!["get", "post"]));
+/// let handles = (0..4)
+///     .map(|_| {
+///         let counter = Arc::clone(&counter);
+///         thread::spawn(move || {
+///             counter.increment("get");
+///             assert_eq!(counter.increment("delete"), None);
+///         })
+///     })
+///     .collect::<Vec<_>>();
+/// for handle in handles {
+///     handle.join().unwrap();
+/// }
+///
+/// let snapshot = counter.snapshot();
+/// assert_eq!(snapshot[&"get"], 4);
+/// assert!(!snapshot.contains_key(&"post"));
+/// ```
+#[derive(Debug)]
+pub struct AtomicCounter<T: Hash + Eq, S = RandomState> {
+    map: HashMap<T, AtomicUsize, S>,
+}
+
+impl<T> AtomicCounter<T>
+where
+    T: Hash + Eq,
+{
+    /// Create a new `AtomicCounter` which can count the given items, all starting at zero
+    pub fn new<I>(items: I) -> AtomicCounter<T>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        AtomicCounter::with_hasher(items, RandomState::new())
+    }
+}
+
+impl<T, S> AtomicCounter<T, S>
+where
+    T: Hash + Eq,
+    S: BuildHasher,
+{
+    /// Create a new `AtomicCounter` which can count the given items, all starting at zero,
+    /// and which will use the given hasher to hash the items
+    pub fn with_hasher<I>(items: I, hash_builder: S) -> AtomicCounter<T, S>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut map = HashMap::with_hasher(hash_builder);
+        map.extend(items.into_iter().map(|item| (item, AtomicUsize::new(0))));
+        AtomicCounter { map }
+    }
+
+    /// Allow the given item to be counted, returning `false` if it was already known
+    pub fn insert_item(&mut self, item: T) -> bool {
+        if self.map.contains_key(&item) {
+            return false;
+        }
+        self.map.insert(item, AtomicUsize::new(0));
+        true
+    }
+
+    /// Add `count` to the count of the given item, returning its new count,
+    /// or `None` if the item is not known to this counter
+    ///
+    /// Like the other integer operations, this wraps around on overflow.
+    pub fn add_count<Q>(&self, item: &Q, count: usize) -> Option<usize>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.get(item).map(|atomic| {
+            atomic
+                .fetch_add(count, Ordering::Relaxed)
+                .wrapping_add(count)
+        })
+    }
+
+    /// Add one to the count of the given item, returning its new count,
+    /// or `None` if the item is not known to this counter
+    pub fn increment<Q>(&self, item: &Q) -> Option<usize>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.add_count(item, 1)
+    }
+
+    /// Returns the current count of the given item, or zero if it is not known.
+    pub fn count_of<Q>(&self, item: &Q) -> usize
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map
+            .get(item)
+            .map_or(0, |atomic| atomic.load(Ordering::Relaxed))
+    }
+
+    /// Copy the current counts into an ordinary `Counter`, leaving out items with a count of zero.
+    ///
+    /// Each count is read individually, so counts which are updated concurrently
+    /// may or may not be reflected in the snapshot.
+    pub fn snapshot(&self) -> Counter<T, usize, S>
+    where
+        T: Clone,
+        S: Clone,
+    {
+        let mut counter = Counter::with_hasher(self.map.hasher().clone());
+        counter
+            .map
+            .extend(self.map.iter().filter_map(
+                |(item, atomic)| match atomic.load(Ordering::Relaxed) {
+                    0 => None,
+                    count => Some((item.clone(), count)),
+                },
+            ));
+        counter
+    }
+
+    /// Consumes this counter and returns an ordinary `Counter`, leaving out items with a
+    /// count of zero.
+    pub fn into_counter(self) -> Counter<T, usize, S>
+    where
+        S: Clone,
+    {
+        let mut counter = Counter::with_hasher(self.map.hasher().clone());
+        counter.map.extend(self.map.into_iter().filter_map(|(item, atomic)| {
+            match atomic.into_inner() {
+                0 => None,
+                count => Some((item, count)),
+            }
+        }));
+        counter
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_concurrent_update() {
+        let counter = Arc::new(ConcurrentCounter::<u32>::with_shards(4));
+        let handles = (0..8)
+            .map(|_| {
+                let counter = Arc::clone(&counter);
+                thread::spawn(move || {
+                    for n in 0..1000 {
+                        counter.increment(n % 10);
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let snapshot = counter.snapshot();
+        assert_eq!(snapshot.len(), 10);
+        assert!(snapshot.values().all(|&count| count == 800));
+        assert_eq!(counter.count_of(&3), 800);
+        assert_eq!(counter.count_of(&10), 0);
+    }
+
+    #[test]
+    fn test_into_counter() {
+        let counter = ConcurrentCounter::<_>::with_shards(3);
+        counter.update("abbccc".chars());
+        assert_eq!(counter.add_count('d', 4), 4);
+        assert_eq!(
+            counter.into_counter(),
+            Counter::init("abbcccdddd".chars())
+        );
+    }
+
+    #[test]
+    fn test_atomic_counter() {
+        let mut counter = AtomicCounter::<_>::new(vec!['a', 'b']);
+        assert_eq!(counter.add_count(&'a', 3), Some(3));
+        assert_eq!(counter.increment(&'a'), Some(4));
+        assert_eq!(counter.increment(&'c'), None);
+        assert_eq!(counter.count_of(&'c'), 0);
+
+        assert!(counter.insert_item('c'));
+        assert!(!counter.insert_item('a'));
+        assert_eq!(counter.increment(&'c'), Some(1));
+        assert_eq!(counter.count_of(&'a'), 4);
+
+        let expected = hashmap! { 'a' => 4, 'c' => 1 };
+        assert!(counter.snapshot().map == expected);
+        assert!(counter.into_counter().map == expected);
+    }
+
+    #[test]
+    fn test_atomic_counter_threads() {
+        let counter = Arc::new(AtomicCounter::<_>::new(0..10));
+        let handles = (0..8)
+            .map(|_| {
+                let counter = Arc::clone(&counter);
+                thread::spawn(move || {
+                    for n in 0..1000 {
+                        counter.increment(&(n % 10));
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let snapshot = counter.snapshot();
+        assert_eq!(snapshot.len(), 10);
+        assert!(snapshot.values().all(|&count| count == 800));
+    }
+}
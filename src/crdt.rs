@@ -0,0 +1,231 @@
+//! Counters which are kept on several replicas and merged, as conflict-free replicated data
+//! types.
+//!
+//! Each replica only changes the counts under its own id, and merging takes the greatest count
+//! of each key. That merge is commutative, associative and idempotent, so the replicas end up
+//! with the same counts, whatever order they receive each other's states in and however many
+//! times.
+
+use core::fmt;
+use core::hash::{BuildHasher, Hash};
+use core::ops::{AddAssign, Sub};
+
+use num_traits::Zero;
+
+use Counter;
+use RandomState;
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Clone + Hash + Eq,
+    N: Clone + Ord + Zero,
+    S: BuildHasher,
+{
+    /// Merge the state of another replica of a grow-only counter (G-Counter) into this one,
+    /// keeping the greatest count of each key
+    ///
+    /// This is the same as the union `self |= other`. For it to converge, the keys should be
+    /// replica ids, or `(replica, item)` pairs, and each replica should only ever increase
+    /// the counts of its own keys. [`marginal_right`](#method.marginal_right) then sums the
+    /// counts of each item over the replicas.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mut a = Counter::<(&str, char)>::new();
+    /// a.add_count(("a", 'x'), 2);
+    /// let mut b = Counter::<(&str, char)>::new();
+    /// b.add_count(("b", 'x'), 1);
+    /// b.add_count(("b", 'y'), 4);
+    ///
+    /// let mut merged = a.clone();
+    /// merged.merge_crdt(&b);
+    /// b.merge_crdt(&a);
+    /// assert_eq!(merged, b);
+    /// assert_eq!(merged.marginal_right()[&'x'], 3);
+    /// ```
+    pub fn merge_crdt(&mut self, other: &Counter<T, N, S>) {
+        *self |= other;
+    }
+}
+
+/// A counter which can be both incremented and decremented on several replicas (a PN-Counter).
+///
+/// The increments and decrements of each key are kept apart, as two grow-only counts, so
+/// that [`merge`](#method.merge) can take the greatest of each. The keys should be replica
+/// ids, or `(replica, item)` pairs, and each replica should only change its own keys.
+///
+/// ```rust
+/// # use counter::crdt::PnCounter;
+/// let mut a = PnCounter::<_, u32>::new();
+/// a.increment("a", 5);
+/// let mut b = a.clone();
+/// a.decrement("a", 2);
+/// b.increment("b", 1);
+///
+/// a.merge(&b);
+/// b.merge(&a);
+/// assert_eq!(a, b);
+/// assert_eq!(a.value(), 4);
+/// ```
+#[derive(Clone)]
+pub struct PnCounter<T: Hash + Eq, N = usize, S = RandomState> {
+    increments: Counter<T, N, S>,
+    decrements: Counter<T, N, S>,
+}
+
+impl<T, N> PnCounter<T, N>
+where
+    T: Hash + Eq,
+    N: Zero,
+{
+    /// Create a new `PnCounter` whose value is zero
+    pub fn new() -> PnCounter<T, N> {
+        PnCounter {
+            increments: Counter::new(),
+            decrements: Counter::new(),
+        }
+    }
+}
+
+impl<T, N> Default for PnCounter<T, N>
+where
+    T: Hash + Eq,
+    N: Zero,
+{
+    fn default() -> PnCounter<T, N> {
+        PnCounter::new()
+    }
+}
+
+impl<T, N, S> fmt::Debug for PnCounter<T, N, S>
+where
+    T: Hash + Eq + fmt::Debug,
+    N: PartialOrd + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PnCounter")
+            .field("increments", &self.increments)
+            .field("decrements", &self.decrements)
+            .finish()
+    }
+}
+
+impl<T, N, S> PartialEq for PnCounter<T, N, S>
+where
+    T: Hash + Eq,
+    N: PartialEq,
+    S: BuildHasher,
+{
+    fn eq(&self, other: &PnCounter<T, N, S>) -> bool {
+        self.increments == other.increments && self.decrements == other.decrements
+    }
+}
+
+impl<T, N, S> Eq for PnCounter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Eq,
+    S: BuildHasher,
+{
+}
+
+impl<T, N, S> PnCounter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Clone + AddAssign + Zero,
+    S: BuildHasher,
+{
+    /// Add `by` to the increments of `key`
+    pub fn increment(&mut self, key: T, by: N) {
+        *self.increments.map.entry(key).or_insert_with(N::zero) += by;
+    }
+
+    /// Add `by` to the decrements of `key`
+    pub fn decrement(&mut self, key: T, by: N) {
+        *self.decrements.map.entry(key).or_insert_with(N::zero) += by;
+    }
+
+    /// The total increments of each key
+    pub fn increments(&self) -> &Counter<T, N, S> {
+        &self.increments
+    }
+
+    /// The total decrements of each key
+    pub fn decrements(&self) -> &Counter<T, N, S> {
+        &self.decrements
+    }
+
+    /// The total of the increments minus the total of the decrements
+    ///
+    /// With an unsigned count type, this panics or wraps if the decrements outweigh the
+    /// increments, so use a signed type if that can happen.
+    pub fn value(&self) -> N
+    where
+        N: Sub<Output = N>,
+    {
+        self.increments.total() - self.decrements.total()
+    }
+}
+
+impl<T, N, S> PnCounter<T, N, S>
+where
+    T: Clone + Hash + Eq,
+    N: Clone + Ord + Zero,
+    S: BuildHasher,
+{
+    /// Merge the state of another replica into this one, keeping the greatest increments and
+    /// decrements of each key
+    pub fn merge(&mut self, other: &PnCounter<T, N, S>) {
+        self.increments.merge_crdt(&other.increments);
+        self.decrements.merge_crdt(&other.decrements);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_crdt() {
+        let replicas = [
+            Counter::<_>::from_pairs(vec![("a", 3), ("b", 1)]),
+            Counter::<_>::from_pairs(vec![("a", 2), ("b", 4)]),
+            Counter::<_>::from_pairs(vec![("c", 1)]),
+        ];
+        let orders = [[0, 1, 2], [2, 1, 0], [1, 0, 2]];
+        for order in orders.iter() {
+            let mut merged = Counter::new();
+            for &i in order.iter() {
+                merged.merge_crdt(&replicas[i]);
+                merged.merge_crdt(&replicas[i]);
+            }
+            assert!(merged.map == hashmap! { "a" => 3, "b" => 4, "c" => 1 });
+        }
+    }
+
+    #[test]
+    fn test_pn_counter() {
+        let mut a = PnCounter::<_, i32>::new();
+        let mut b = PnCounter::new();
+        a.increment("a", 3);
+        b.increment("b", 2);
+        b.decrement("b", 5);
+        assert_eq!(b.value(), -3);
+
+        let mut merged_ab = a.clone();
+        merged_ab.merge(&b);
+        let mut merged_ba = b.clone();
+        merged_ba.merge(&a);
+        merged_ba.merge(&a);
+        assert_eq!(merged_ab, merged_ba);
+        assert_eq!(merged_ab.value(), 0);
+        assert_eq!(merged_ab.increments()[&"a"], 3);
+        assert_eq!(merged_ab.decrements()[&"b"], 5);
+
+        // a stale state doesn't undo newer changes
+        a.decrement("a", 1);
+        let stale = merged_ab.clone();
+        a.merge(&stale);
+        assert_eq!(a.value(), -1);
+    }
+}
@@ -0,0 +1,172 @@
+//! Reading and writing frequency tables as CSV, enabled by the `csv` feature.
+//!
+//! Each row is an `item,count` pair. Items and counts are converted with their serde
+//! implementations, so anything the `csv` crate can put in a cell will work.
+
+use csv::{ReaderBuilder, WriterBuilder};
+use num_traits::Zero;
+use serde::de::DeserializeOwned;
+use serde::ser::Serialize;
+
+use std::cmp::Reverse;
+use std::hash::{BuildHasher, Hash};
+use std::io::{Read, Write};
+use std::ops::AddAssign;
+
+use Counter;
+
+/// The order in which [`Counter::to_csv_writer`](struct.Counter.html#method.to_csv_writer)
+/// writes its rows
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CsvOrder {
+    /// From the most common item to the least
+    MostCommon,
+    /// From the least common item to the most
+    LeastCommon,
+    /// In the counter's iteration order
+    Unsorted,
+}
+
+/// Options for reading and writing a `Counter` as CSV
+///
+/// The defaults are a comma delimiter, an `item,count` header row, and rows sorted from the
+/// most common item to the least.
+#[derive(Clone, Debug)]
+pub struct CsvOptions {
+    /// The byte which separates the item from the count
+    pub delimiter: u8,
+    /// Whether the first row is a header, which is written as `item,count` and skipped
+    /// when reading
+    pub has_headers: bool,
+    /// The order in which rows are written
+    pub order: CsvOrder,
+}
+
+impl Default for CsvOptions {
+    fn default() -> CsvOptions {
+        CsvOptions {
+            delimiter: b',',
+            has_headers: true,
+            order: CsvOrder::MostCommon,
+        }
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    S: BuildHasher,
+{
+    /// Write this counter to `writer` as CSV, one `item,count` row per item
+    ///
+    /// This method requires the `csv` feature.
+    ///
+    /// ```rust
+    /// # use counter::{Counter, CsvOptions};
+    /// let counter = "abbccc".chars().collect::<Counter<_>>();
+    /// let mut csv = Vec::new();
+    /// counter.to_csv_writer(&mut csv, &CsvOptions::default()).unwrap();
+    /// assert_eq!(String::from_utf8(csv).unwrap(), "item,count\nc,3\nb,2\na,1\n");
+    /// ```
+    pub fn to_csv_writer<W>(&self, writer: W, options: &CsvOptions) -> Result<(), csv::Error>
+    where
+        W: Write,
+        T: Serialize,
+        N: Serialize + Ord,
+    {
+        let mut rows = self.map.iter().collect::<Vec<_>>();
+        match options.order {
+            CsvOrder::MostCommon => rows.sort_by_key(|&(_, count)| Reverse(count)),
+            CsvOrder::LeastCommon => rows.sort_by_key(|&(_, count)| count),
+            CsvOrder::Unsorted => {}
+        }
+
+        let mut writer = WriterBuilder::new()
+            .delimiter(options.delimiter)
+            .from_writer(writer);
+        if options.has_headers {
+            writer.write_record(["item", "count"])?;
+        }
+        for row in rows {
+            writer.serialize(row)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Read a counter from `item,count` rows of CSV, summing the counts of duplicate items
+    ///
+    /// This method requires the `csv` feature.
+    ///
+    /// ```rust
+    /// # use counter::{Counter, CsvOptions};
+    /// let options = CsvOptions { delimiter: b'\t', has_headers: false, ..CsvOptions::default() };
+    /// let counter: Counter<String> =
+    ///     Counter::from_csv_reader("a\t1\nb\t2\na\t3\n".as_bytes(), &options).unwrap();
+    /// assert_eq!(counter["a"], 4);
+    /// assert_eq!(counter["b"], 2);
+    /// ```
+    pub fn from_csv_reader<R>(
+        reader: R,
+        options: &CsvOptions,
+    ) -> Result<Counter<T, N, S>, csv::Error>
+    where
+        R: Read,
+        T: DeserializeOwned,
+        N: DeserializeOwned + AddAssign + Zero,
+        S: Default,
+    {
+        let mut reader = ReaderBuilder::new()
+            .delimiter(options.delimiter)
+            .has_headers(options.has_headers)
+            .from_reader(reader);
+        let mut counter = Counter::with_hasher(S::default());
+        for row in reader.deserialize() {
+            let (item, count): (T, N) = row?;
+            let entry = counter.map.entry(item).or_insert_with(N::zero);
+            *entry += count;
+        }
+        Ok(counter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_round_trip() {
+        let counter = Counter::<u32>::init(vec![1, 2, 2, 3, 3, 3]);
+        for &order in &[CsvOrder::MostCommon, CsvOrder::LeastCommon, CsvOrder::Unsorted] {
+            let options = CsvOptions {
+                delimiter: b';',
+                order,
+                ..CsvOptions::default()
+            };
+            let mut csv = Vec::new();
+            counter.to_csv_writer(&mut csv, &options).unwrap();
+            let read = Counter::<u32>::from_csv_reader(&csv[..], &options).unwrap();
+            assert_eq!(read, counter);
+        }
+    }
+
+    #[test]
+    fn test_csv_least_common() {
+        let counter = "abbccc".chars().collect::<Counter<_>>();
+        let options = CsvOptions {
+            has_headers: false,
+            order: CsvOrder::LeastCommon,
+            ..CsvOptions::default()
+        };
+        let mut csv = Vec::new();
+        counter.to_csv_writer(&mut csv, &options).unwrap();
+        assert_eq!(String::from_utf8(csv).unwrap(), "a,1\nb,2\nc,3\n");
+    }
+
+    #[test]
+    fn test_csv_bad_count() {
+        let csv = "item,count\na,x\n".as_bytes();
+        let result = Counter::<String>::from_csv_reader(csv, &CsvOptions::default());
+        assert!(result.is_err());
+    }
+}
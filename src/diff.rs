@@ -0,0 +1,212 @@
+use alloc::vec::Vec;
+use core::hash::{BuildHasher, Hash};
+
+use num_traits::Zero;
+
+use Counter;
+
+/// The differences between two counters.
+///
+/// This `struct` is created by the [`diff`](struct.Counter.html#method.diff) method on
+/// [`Counter`](struct.Counter.html). As with `==` on counters, an item whose count is zero is
+/// treated as missing. The entries of each field are in an arbitrary order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CounterDiff<'a, T: 'a, N: 'a> {
+    /// The items which are only in the first counter, with their counts
+    pub only_in_self: Vec<(&'a T, &'a N)>,
+    /// The items which are only in the second counter, with their counts
+    pub only_in_other: Vec<(&'a T, &'a N)>,
+    /// The items which are in both counters with different counts, with the count in the
+    /// first counter and then the count in the second
+    pub changed: Vec<(&'a T, &'a N, &'a N)>,
+}
+
+impl<'a, T, N> CounterDiff<'a, T, N> {
+    /// Returns `true` if the counters have the same items with the same counts.
+    pub fn is_empty(&self) -> bool {
+        self.only_in_self.is_empty() && self.only_in_other.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// The changes which turn one counter into another, which can be sent to where a copy of the
+/// first counter is kept, instead of the whole second counter.
+///
+/// This `struct` is created by the [`delta_since`](struct.Counter.html#method.delta_since)
+/// method on [`Counter`](struct.Counter.html), and applied with
+/// [`apply_delta`](struct.Counter.html#method.apply_delta). It records the new counts rather
+/// than the differences, so applying the same delta twice has the same effect as applying it
+/// once.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CounterDelta<T, N> {
+    /// The items which were added or whose counts changed, with their new counts
+    pub updated: Vec<(T, N)>,
+    /// The items which were removed, or whose counts became zero
+    pub removed: Vec<T>,
+}
+
+impl<T, N> CounterDelta<T, N> {
+    /// Returns `true` if there are no changes.
+    pub fn is_empty(&self) -> bool {
+        self.updated.is_empty() && self.removed.is_empty()
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: PartialEq + Zero,
+    S: BuildHasher,
+{
+    /// Compare this counter with `other`, listing the items which only one of them has and the
+    /// items whose counts changed
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let yesterday = "the cat sat".split(' ').collect::<Counter<_>>();
+    /// let today = "the cat the dog".split(' ').collect::<Counter<_>>();
+    /// let diff = yesterday.diff(&today);
+    /// assert_eq!(diff.only_in_self, vec![(&"sat", &1)]);
+    /// assert_eq!(diff.only_in_other, vec![(&"dog", &1)]);
+    /// assert_eq!(diff.changed, vec![(&"the", &1, &2)]);
+    /// ```
+    pub fn diff<'a>(&'a self, other: &'a Counter<T, N, S>) -> CounterDiff<'a, T, N> {
+        let mut only_in_self = Vec::new();
+        let mut only_in_other = Vec::new();
+        let mut changed = Vec::new();
+        for (item, count) in self.map.iter() {
+            let other_count = other.map.get(item).filter(|count| !count.is_zero());
+            match (count.is_zero(), other_count) {
+                (false, Some(other_count)) if other_count != count => {
+                    changed.push((item, count, other_count))
+                }
+                (false, None) => only_in_self.push((item, count)),
+                (true, Some(other_count)) => only_in_other.push((item, other_count)),
+                _ => {}
+            }
+        }
+        only_in_other.extend(
+            other
+                .map
+                .iter()
+                .filter(|&(item, count)| !count.is_zero() && !self.map.contains_key(item)),
+        );
+        CounterDiff {
+            only_in_self,
+            only_in_other,
+            changed,
+        }
+    }
+
+    /// The changes which turn `baseline` into this counter
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let baseline = "aab".chars().collect::<Counter<_>>();
+    /// let mut current = baseline.clone();
+    /// current.update("ac".chars());
+    /// current.remove(&'b');
+    ///
+    /// let delta = current.delta_since(&baseline);
+    /// assert_eq!(delta.removed, vec!['b']);
+    ///
+    /// let mut replica = baseline.clone();
+    /// replica.apply_delta(delta);
+    /// assert_eq!(replica, current);
+    /// ```
+    pub fn delta_since(&self, baseline: &Counter<T, N, S>) -> CounterDelta<T, N>
+    where
+        T: Clone,
+        N: Clone,
+    {
+        let updated = self
+            .map
+            .iter()
+            .filter(|&(item, count)| !count.is_zero() && *baseline.get_or_zero(item) != *count)
+            .map(|(item, count)| (item.clone(), count.clone()))
+            .collect();
+        let removed = baseline
+            .map
+            .iter()
+            .filter(|&(item, count)| !count.is_zero() && self.get_or_zero(item).is_zero())
+            .map(|(item, _)| item.clone())
+            .collect();
+        CounterDelta { updated, removed }
+    }
+
+    fn get_or_zero(&self, item: &T) -> &N {
+        self.map.get(item).unwrap_or(&self.zero)
+    }
+
+    /// Apply the changes from [`delta_since`](#method.delta_since), so that a copy of its
+    /// baseline becomes a copy of the counter it was created from
+    pub fn apply_delta(&mut self, delta: CounterDelta<T, N>) {
+        for item in delta.removed {
+            self.map.remove(&item);
+        }
+        self.map.extend(delta.updated);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff() {
+        let a = Counter::<_>::from_pairs(vec![('a', 1), ('b', 2), ('c', 3), ('d', 4)]);
+        let b = Counter::<_>::from_pairs(vec![('b', 2), ('c', 5), ('d', 1), ('e', 6)]);
+        let mut diff = a.diff(&b);
+        diff.changed.sort();
+        assert_eq!(diff.only_in_self, vec![(&'a', &1)]);
+        assert_eq!(diff.only_in_other, vec![(&'e', &6)]);
+        assert_eq!(diff.changed, vec![(&'c', &3, &5), (&'d', &4, &1)]);
+        assert!(!diff.is_empty());
+
+        assert!(a.diff(&a.clone()).is_empty());
+        assert_eq!(b.diff(&Counter::new()).only_in_self.len(), 4);
+    }
+
+    #[test]
+    fn test_delta() {
+        let baseline = Counter::<_>::from_pairs(vec![('a', 1), ('b', 2), ('c', 3)]);
+        let current = Counter::<_>::from_pairs(vec![('a', 1), ('b', 5), ('d', 4)]);
+        let mut delta = current.delta_since(&baseline);
+        delta.updated.sort();
+        assert_eq!(delta.updated, vec![('b', 5), ('d', 4)]);
+        assert_eq!(delta.removed, vec!['c']);
+
+        let mut replica = baseline.clone();
+        replica.apply_delta(delta.clone());
+        assert_eq!(replica, current);
+        replica.apply_delta(delta);
+        assert_eq!(replica, current);
+
+        assert!(current.delta_since(&current).is_empty());
+    }
+
+    #[test]
+    fn test_zero_counts_are_missing() {
+        let mut a = Counter::<_>::from_pairs(vec![('a', 1), ('b', 2)]);
+        a.insert('z', 0);
+        let mut b = Counter::<_>::from_pairs(vec![('a', 1), ('b', 2)]);
+        assert_eq!(a, b);
+        assert!(a.diff(&b).is_empty());
+        assert!(b.diff(&a).is_empty());
+        assert!(a.delta_since(&b).is_empty());
+        assert!(b.delta_since(&a).is_empty());
+
+        b.insert('z', 3);
+        b.insert('b', 0);
+        let diff = a.diff(&b);
+        assert_eq!(diff.only_in_self, vec![(&'b', &2)]);
+        assert_eq!(diff.only_in_other, vec![(&'z', &3)]);
+        assert!(diff.changed.is_empty());
+
+        let delta = b.delta_since(&a);
+        assert_eq!(delta.updated, vec![('z', 3)]);
+        assert_eq!(delta.removed, vec!['b']);
+        let mut replica = a.clone();
+        replica.apply_delta(delta);
+        assert_eq!(replica, b);
+    }
+}
@@ -0,0 +1,281 @@
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::hash::{BuildHasher, Hash};
+
+use num_traits::ToPrimitive;
+
+#[cfg(feature = "rand")]
+use rand::Rng;
+
+use {Counter, HashMap, RandomState};
+
+/// The probability distribution of the items of a counter.
+///
+/// The probabilities are computed once, when the distribution is created, so that looking them
+/// up doesn't have to divide by the total each time. Items whose counts aren't positive are
+/// left out.
+///
+/// ```rust
+/// # use counter::Counter;
+/// let counter = "aaaabbbc".chars().collect::<Counter<_>>();
+/// let distribution = counter.distribution();
+/// assert_eq!(distribution.total(), 8.0);
+/// assert_eq!(distribution.prob(&'a'), 0.5);
+/// assert_eq!(distribution.prob(&'z'), 0.0);
+/// assert_eq!(distribution.top_p(0.8), &['a', 'b']);
+/// ```
+#[derive(Clone, Debug)]
+pub struct FrequencyDistribution<T: Hash + Eq, S = RandomState> {
+    total: f64,
+    // the items and their probabilities, from the most probable to the least
+    items: Vec<T>,
+    probabilities: Vec<f64>,
+    index: HashMap<T, usize, S>,
+    #[cfg(feature = "rand")]
+    alias: AliasTable,
+}
+
+impl<T, S> FrequencyDistribution<T, S>
+where
+    T: Clone + Hash + Eq,
+    S: BuildHasher + Clone,
+{
+    /// Create the distribution of the items of `counter`
+    pub fn new<N>(counter: &Counter<T, N, S>) -> FrequencyDistribution<T, S>
+    where
+        N: ToPrimitive,
+    {
+        let mut entries = counter
+            .map
+            .iter()
+            .filter_map(|(item, count)| count.to_f64().map(|count| (item, count)))
+            .filter(|&(_, count)| count > 0.0)
+            .collect::<Vec<_>>();
+        entries.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap_or(Ordering::Equal));
+        let total = entries.iter().map(|&(_, count)| count).sum::<f64>();
+
+        let mut index =
+            HashMap::with_capacity_and_hasher(entries.len(), counter.map.hasher().clone());
+        let mut items = Vec::with_capacity(entries.len());
+        let mut probabilities = Vec::with_capacity(entries.len());
+        for (i, (item, count)) in entries.into_iter().enumerate() {
+            index.insert(item.clone(), i);
+            items.push(item.clone());
+            probabilities.push(count / total);
+        }
+
+        FrequencyDistribution {
+            total,
+            #[cfg(feature = "rand")]
+            alias: AliasTable::new(&probabilities),
+            items,
+            probabilities,
+            index,
+        }
+    }
+}
+
+impl<T, S> FrequencyDistribution<T, S>
+where
+    T: Hash + Eq,
+    S: BuildHasher,
+{
+    /// The total of the counts the distribution was created from
+    pub fn total(&self) -> f64 {
+        self.total
+    }
+
+    /// The number of items with a nonzero probability
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns `true` if no item has a nonzero probability.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// The probability of the given item, which is zero if it wasn't counted
+    pub fn prob<Q>(&self, item: &Q) -> f64
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.index.get(item).map_or(0.0, |&i| self.probabilities[i])
+    }
+
+    /// An iterator over the items and their probabilities, from the most probable to the least
+    pub fn iter(&self) -> impl Iterator<Item = (&T, f64)> + '_ {
+        self.items.iter().zip(self.probabilities.iter().cloned())
+    }
+
+    /// The Shannon entropy of the distribution, in bits
+    ///
+    /// This method requires the `std` feature.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = "abcd".chars().collect::<Counter<_>>();
+    /// assert_eq!(counter.distribution().entropy(), 2.0);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn entropy(&self) -> f64 {
+        -self.probabilities.iter().map(|&p| p * p.log2()).sum::<f64>()
+    }
+
+    /// The smallest set of the most probable items whose probabilities add up to at least `p`,
+    /// from the most probable to the least, as used by nucleus sampling
+    ///
+    /// Items with the same probability are in an arbitrary order, so which of them is cut off
+    /// isn't specified.
+    pub fn top_p(&self, p: f64) -> &[T] {
+        let mut cumulative = 0.0;
+        let len = self
+            .probabilities
+            .iter()
+            .position(|&probability| {
+                cumulative += probability;
+                cumulative >= p
+            })
+            .map_or(self.items.len(), |i| i + 1);
+        &self.items[..len]
+    }
+
+    /// Pick a random item, with the probability of each item, or `None` if the distribution
+    /// is empty
+    ///
+    /// This takes constant time, using an alias table which is built when the distribution is
+    /// created. This method requires the `rand` feature.
+    ///
+    /// ```rust
+    /// # extern crate counter;
+    /// # extern crate rand;
+    /// # use counter::Counter;
+    /// # fn main() {
+    /// let counter = "aaab".chars().collect::<Counter<_>>();
+    /// let distribution = counter.distribution();
+    /// let item = distribution.sample(&mut rand::thread_rng()).unwrap();
+    /// assert!(*item == 'a' || *item == 'b');
+    /// # }
+    /// ```
+    #[cfg(feature = "rand")]
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<&T> {
+        self.alias.sample(rng).map(|i| &self.items[i])
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Clone + Hash + Eq,
+    N: ToPrimitive,
+    S: BuildHasher + Clone,
+{
+    /// Create the probability distribution of the items of this counter
+    pub fn distribution(&self) -> FrequencyDistribution<T, S> {
+        FrequencyDistribution::new(self)
+    }
+}
+
+/// Walker's alias method, as described by Vose, for picking an index in constant time
+#[cfg(feature = "rand")]
+#[derive(Clone, Debug)]
+pub(crate) struct AliasTable {
+    // the probability of keeping each index rather than taking its alias
+    keep: Vec<f64>,
+    alias: Vec<usize>,
+}
+
+#[cfg(feature = "rand")]
+impl AliasTable {
+    /// Build the table for the given weights, which needn't add up to one
+    pub(crate) fn new(weights: &[f64]) -> AliasTable {
+        let len = weights.len();
+        let total = weights.iter().sum::<f64>();
+        let mut keep = weights
+            .iter()
+            .map(|&weight| weight * len as f64 / total)
+            .collect::<Vec<_>>();
+        let mut alias = (0..len).collect::<Vec<_>>();
+
+        let (mut small, mut large): (Vec<_>, Vec<_>) = (0..len).partition(|&i| keep[i] < 1.0);
+        while let (Some(&less), Some(&more)) = (small.last(), large.last()) {
+            small.pop();
+            alias[less] = more;
+            keep[more] -= 1.0 - keep[less];
+            if keep[more] < 1.0 {
+                large.pop();
+                small.push(more);
+            }
+        }
+        // whatever is left over only differs from one by rounding error
+        for i in small.into_iter().chain(large) {
+            keep[i] = 1.0;
+        }
+        AliasTable { keep, alias }
+    }
+
+    pub(crate) fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<usize> {
+        if self.keep.is_empty() {
+            return None;
+        }
+        let i = rng.gen_range(0..self.keep.len());
+        if rng.gen::<f64>() < self.keep[i] {
+            Some(i)
+        } else {
+            Some(self.alias[i])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distribution() {
+        let counter = Counter::<_, i32>::from_pairs(vec![('a', 6), ('b', 3), ('c', 1), ('d', -2)]);
+        let distribution = counter.distribution();
+        assert_eq!(distribution.len(), 3);
+        assert_eq!(distribution.total(), 10.0);
+        assert_eq!(distribution.prob(&'b'), 0.3);
+        assert_eq!(distribution.prob(&'d'), 0.0);
+        assert_eq!(
+            distribution.iter().collect::<Vec<_>>(),
+            vec![(&'a', 0.6), (&'b', 0.3), (&'c', 0.1)]
+        );
+
+        assert_eq!(distribution.top_p(0.0), &['a']);
+        assert_eq!(distribution.top_p(0.6), &['a']);
+        assert_eq!(distribution.top_p(0.61), &['a', 'b']);
+        assert_eq!(distribution.top_p(2.0), &['a', 'b', 'c']);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_entropy() {
+        let counter = Counter::<_>::init("aabc".chars());
+        assert_eq!(counter.distribution().entropy(), 1.5);
+        assert_eq!(Counter::<char>::new().distribution().entropy(), 0.0);
+    }
+
+    #[test]
+    #[cfg(feature = "rand")]
+    fn test_sample() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let counter = Counter::<_>::from_pairs(vec![('a', 1), ('b', 2), ('c', 7)]);
+        let distribution = counter.distribution();
+        let mut rng = StdRng::seed_from_u64(7);
+        let samples = (0..10_000)
+            .map(|_| *distribution.sample(&mut rng).unwrap())
+            .collect::<Counter<_>>();
+        assert!((800..1200).contains(&samples[&'a']));
+        assert!((1800..2200).contains(&samples[&'b']));
+        assert!((6700..7300).contains(&samples[&'c']));
+
+        let empty = Counter::<char>::new().distribution();
+        assert_eq!(empty.sample(&mut rng), None);
+    }
+}
@@ -0,0 +1,34 @@
+use core::fmt;
+
+/// The error returned when adding to the count of an item would overflow the count type
+///
+/// It holds the item whose count overflowed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct OverflowError<T> {
+    item: T,
+}
+
+impl<T> OverflowError<T> {
+    pub(crate) fn new(item: T) -> OverflowError<T> {
+        OverflowError { item }
+    }
+
+    /// The item whose count overflowed
+    pub fn item(&self) -> &T {
+        &self.item
+    }
+
+    /// Consumes the error and returns the item whose count overflowed.
+    pub fn into_item(self) -> T {
+        self.item
+    }
+}
+
+impl<T: fmt::Debug> fmt::Display for OverflowError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the count of {:?} overflowed", self.item)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: fmt::Debug> std::error::Error for OverflowError<T> {}
@@ -0,0 +1,5 @@
+//! Rendering counters in the formats of other tools.
+//!
+//! This module requires the `std` feature.
+
+pub mod prometheus;
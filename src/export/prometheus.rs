@@ -0,0 +1,127 @@
+//! The Prometheus text exposition format.
+//!
+//! [`write`](fn.write.html) renders a counter as one metric, with a label whose values are
+//! the counter's items:
+//!
+//! ```rust
+//! # use counter::Counter;
+//! use counter::export::prometheus;
+//!
+//! let requests = vec!["/", "/about", "/"].into_iter().collect::<Counter<_>>();
+//! let mut text = Vec::new();
+//! prometheus::write(&mut text, "http_requests_total", "path", &requests).unwrap();
+//! assert_eq!(
+//!     String::from_utf8(text).unwrap(),
+//!     "# TYPE http_requests_total counter\n\
+//!      http_requests_total{path=\"/\"} 2\n\
+//!      http_requests_total{path=\"/about\"} 1\n"
+//! );
+//! ```
+
+use std::fmt::Display;
+use std::hash::{BuildHasher, Hash};
+use std::io::{self, Write};
+
+use Counter;
+
+/// Turn `name` into a valid metric or label name, by replacing every character other than
+/// an ASCII letter, digit or underscore with an underscore, and prefixing an underscore if it
+/// is empty or starts with a digit.
+///
+/// ```rust
+/// # use counter::export::prometheus::sanitize_name;
+/// assert_eq!(sanitize_name("http.requests-total"), "http_requests_total");
+/// assert_eq!(sanitize_name("2xx"), "_2xx");
+/// ```
+pub fn sanitize_name(name: &str) -> String {
+    let mut sanitized = String::with_capacity(name.len() + 1);
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        sanitized.push('_');
+    }
+    sanitized.extend(name.chars().map(|c| {
+        if c.is_ascii_alphanumeric() || c == '_' {
+            c
+        } else {
+            '_'
+        }
+    }));
+    sanitized
+}
+
+/// Escape a label value, so that backslashes, double quotes and newlines survive inside
+/// its quotes.
+pub fn escape_label_value(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Write `counter` to `writer` as a Prometheus counter called `name`, with one sample per
+/// item, labeled by `label`
+///
+/// The metric and label names are sanitized with [`sanitize_name`](fn.sanitize_name.html),
+/// and the items are formatted with `Display` and escaped. Samples are written in order of
+/// their label values, so the output is the same however the counter's map is ordered.
+pub fn write<W, T, N, S>(
+    mut writer: W,
+    name: &str,
+    label: &str,
+    counter: &Counter<T, N, S>,
+) -> io::Result<()>
+where
+    W: Write,
+    T: Hash + Eq + Display,
+    N: Display,
+    S: BuildHasher,
+{
+    let name = sanitize_name(name);
+    let label = sanitize_name(label);
+    let mut samples = counter
+        .map
+        .iter()
+        .map(|(item, count)| (escape_label_value(&item.to_string()), count))
+        .collect::<Vec<_>>();
+    samples.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    writeln!(writer, "# TYPE {} counter", name)?;
+    for (value, count) in samples {
+        writeln!(writer, "{}{{{}=\"{}\"}} {}", name, label, value, count)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_name() {
+        assert_eq!(sanitize_name("valid_name"), "valid_name");
+        assert_eq!(sanitize_name("a b:c"), "a_b_c");
+        assert_eq!(sanitize_name(""), "_");
+        assert_eq!(sanitize_name("é"), "_");
+    }
+
+    #[test]
+    fn test_write_escapes_values() {
+        let counter = vec!["say \"hi\"", "back\\slash", "new\nline"]
+            .into_iter()
+            .collect::<Counter<_>>();
+        let mut text = Vec::new();
+        write(&mut text, "my metric", "9label", &counter).unwrap();
+        let expected = concat!(
+            "# TYPE my_metric counter\n",
+            "my_metric{_9label=\"back\\\\slash\"} 1\n",
+            "my_metric{_9label=\"new\\nline\"} 1\n",
+            "my_metric{_9label=\"say \\\"hi\\\"\"} 1\n",
+        );
+        assert_eq!(String::from_utf8(text).unwrap(), expected);
+    }
+}
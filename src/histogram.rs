@@ -0,0 +1,141 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cmp::Reverse;
+use core::fmt;
+use core::hash::{BuildHasher, Hash};
+
+use num_traits::ToPrimitive;
+
+use Counter;
+
+const EIGHTHS: [char; 8] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉', '█'];
+
+/// A text histogram of the most common items of a counter, which is rendered by its
+/// `Display` implementation.
+///
+/// This `struct` is created by the [`histogram`](struct.Counter.html#method.histogram)
+/// method on [`Counter`](struct.Counter.html).
+pub struct Histogram<'a, N: 'a> {
+    rows: Vec<(String, &'a N)>,
+    width: usize,
+    unicode: bool,
+}
+
+impl<'a, N> Histogram<'a, N> {
+    /// Draw the bars with Unicode block characters, which can show eighths of a column,
+    /// rather than `#`
+    pub fn unicode(mut self) -> Histogram<'a, N> {
+        self.unicode = true;
+        self
+    }
+}
+
+impl<'a, N> fmt::Display for Histogram<'a, N>
+where
+    N: ToPrimitive + fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let item_width = self
+            .rows
+            .iter()
+            .map(|(item, _)| item.chars().count())
+            .max()
+            .unwrap_or(0);
+        let counts = self
+            .rows
+            .iter()
+            .map(|(_, count)| format!("{}", count))
+            .collect::<Vec<_>>();
+        let count_width = counts.iter().map(String::len).max().unwrap_or(0);
+        let max = self
+            .rows
+            .iter()
+            .filter_map(|(_, count)| count.to_f64())
+            .fold(0.0, f64::max);
+
+        for ((item, count), rendered) in self.rows.iter().zip(counts.iter()) {
+            let fraction = match count.to_f64() {
+                Some(count) if max > 0.0 => count / max,
+                _ => 0.0,
+            };
+            let padding = item_width - item.chars().count();
+            write!(f, "{}{:padding$} {:>count_width$} ", item, "", rendered)?;
+            if self.unicode {
+                let eighths = (fraction * (self.width * 8) as f64 + 0.5) as usize;
+                for _ in 0..eighths / 8 {
+                    f.write_str("█")?;
+                }
+                if let Some(partial) = (eighths % 8).checked_sub(1) {
+                    write!(f, "{}", EIGHTHS[partial])?;
+                }
+            } else {
+                let columns = (fraction * self.width as f64 + 0.5) as usize;
+                for _ in 0..columns {
+                    f.write_str("#")?;
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq + fmt::Display,
+    N: Ord,
+    S: BuildHasher,
+{
+    /// Render the `top` most common items as a text histogram: each item, its count, and a bar
+    /// whose length is proportional to the count, with the longest bar `width` columns long.
+    ///
+    /// Items with the same count are sorted by how they are formatted.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = "abracadabra".chars().collect::<Counter<_>>();
+    /// assert_eq!(
+    ///     counter.histogram(3, 10).to_string(),
+    ///     "a 5 ##########\nb 2 ####\nr 2 ####\n"
+    /// );
+    /// assert_eq!(counter.histogram(1, 4).unicode().to_string(), "a 5 ████\n");
+    /// ```
+    pub fn histogram(&self, top: usize, width: usize) -> Histogram<'_, N> {
+        let mut rows = self
+            .map
+            .iter()
+            .map(|(item, count)| (format!("{}", item), count))
+            .collect::<Vec<_>>();
+        rows.sort_by(|(a_item, a_count), (b_item, b_count)| {
+            (Reverse(a_count), a_item).cmp(&(Reverse(b_count), b_item))
+        });
+        rows.truncate(top);
+        Histogram {
+            rows,
+            width,
+            unicode: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::string::ToString;
+
+    #[test]
+    fn test_histogram_alignment() {
+        let counter = Counter::<_>::init(vec!["apple", "fig", "fig", "fig"]);
+        let expected = "fig   3 ######\napple 1 ##\n";
+        assert_eq!(counter.histogram(10, 6).to_string(), expected);
+        assert_eq!(counter.histogram(0, 6).to_string(), "");
+    }
+
+    #[test]
+    fn test_histogram_unicode() {
+        let mut counter = Counter::<_>::init(vec!['a'; 8]);
+        counter.add_count('b', 3);
+        assert_eq!(counter.histogram(2, 2).unicode().to_string(), "a 8 ██\nb 3 ▊\n");
+    }
+}
@@ -0,0 +1,451 @@
+//! A counter backed by an `IndexMap`, which remembers the order in which items were first seen.
+//!
+//! This module is enabled by the `indexmap` feature.
+
+use num_traits::{One, Zero};
+
+use indexmap::map::{self as index_map, IndexMap};
+
+use std::borrow::Borrow;
+use std::hash::Hash;
+use std::iter;
+use std::ops::{Add, AddAssign, Deref, DerefMut, Index, Sub, SubAssign};
+
+/// A counter which iterates its items in the order in which they were first seen.
+///
+/// It offers the core counting API of [`Counter`](struct.Counter.html).
+///
+/// ```rust
+/// # use counter::IndexCounter;
+/// let counter = "abracadabra".chars().collect::<IndexCounter<_>>();
+/// let table = counter.iter().map(|(&c, &n)| (c, n)).collect::<Vec<_>>();
+/// assert_eq!(table, vec![('a', 5), ('b', 2), ('r', 2), ('c', 1), ('d', 1)]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct IndexCounter<T: Hash + Eq, N = usize> {
+    map: IndexMap<T, N>,
+    // necessary for `Index::index` since we cannot declare generic `static` variables.
+    zero: N,
+}
+
+impl<T, N> IndexCounter<T, N>
+where
+    T: Hash + Eq,
+    N: Zero,
+{
+    /// Create a new, empty `IndexCounter`
+    pub fn new() -> IndexCounter<T, N> {
+        IndexCounter {
+            map: IndexMap::new(),
+            zero: N::zero(),
+        }
+    }
+}
+
+impl<T, N> IndexCounter<T, N>
+where
+    T: Hash + Eq,
+    N: PartialOrd + AddAssign + Zero + One,
+{
+    /// Create a new `IndexCounter` initialized with the given iterable
+    pub fn init<I>(iterable: I) -> IndexCounter<T, N>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut counter = IndexCounter::new();
+        counter.update(iterable);
+        counter
+    }
+
+    /// Add the counts of the elements from the given iterable to this counter
+    pub fn update<I>(&mut self, iterable: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        for item in iterable.into_iter() {
+            let entry = self.map.entry(item).or_insert_with(N::zero);
+            *entry += N::one();
+        }
+    }
+}
+
+impl<T, N> IndexCounter<T, N>
+where
+    T: Hash + Eq,
+    N: PartialOrd + SubAssign + Zero + One,
+{
+    /// Remove the counts of the elements from the given iterable to this counter
+    ///
+    /// Non-positive counts are automatically removed, keeping the order of the remaining items
+    ///
+    /// ```rust
+    /// # use counter::IndexCounter;
+    /// let mut counter = "abbccc".chars().collect::<IndexCounter<_>>();
+    /// counter.subtract("abba".chars());
+    /// assert_eq!(counter.into_map().into_iter().collect::<Vec<_>>(), vec![('c', 3)]);
+    /// ```
+    pub fn subtract<I>(&mut self, iterable: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        for item in iterable.into_iter() {
+            let mut remove = false;
+            if let Some(entry) = self.map.get_mut(&item) {
+                if *entry > N::zero() {
+                    *entry -= N::one();
+                }
+                remove = *entry == N::zero();
+            }
+            if remove {
+                self.map.shift_remove(&item);
+            }
+        }
+    }
+}
+
+impl<T, N> IndexCounter<T, N>
+where
+    T: Hash + Eq,
+{
+    /// Consumes this counter and returns a IndexMap mapping the items to the counts.
+    pub fn into_map(self) -> IndexMap<T, N> {
+        self.map
+    }
+
+    /// Sum all the counts.
+    pub fn total(&self) -> N
+    where
+        N: Clone + Zero,
+    {
+        self.map
+            .values()
+            .fold(N::zero(), |total, count| total + count.clone())
+    }
+}
+
+impl<T, N> IndexCounter<T, N>
+where
+    T: Hash + Eq + Clone,
+    N: Clone + Ord,
+{
+    /// Create a vector of `(elem, frequency)` pairs, sorted most to least common.
+    ///
+    /// Ties are broken by the order in which the items were first seen,
+    /// so the output is reproducible across runs.
+    ///
+    /// ```rust
+    /// # use counter::IndexCounter;
+    /// let mc = "dcbaabcd".chars().collect::<IndexCounter<_>>().most_common();
+    /// let expect = vec![('d', 2), ('c', 2), ('b', 2), ('a', 2)];
+    /// assert_eq!(mc, expect);
+    /// ```
+    pub fn most_common(&self) -> Vec<(T, N)> {
+        let mut items = self.map
+            .iter()
+            .map(|(key, count)| (key.clone(), count.clone()))
+            .collect::<Vec<_>>();
+        // the sort is stable, so equal counts stay in insertion order
+        items.sort_by(|(_, a_count), (_, b_count)| b_count.cmp(a_count));
+        items
+    }
+}
+
+impl<T, N> Default for IndexCounter<T, N>
+where
+    T: Hash + Eq,
+    N: Zero,
+{
+    fn default() -> IndexCounter<T, N> {
+        IndexCounter::new()
+    }
+}
+
+impl<T, N> PartialEq for IndexCounter<T, N>
+where
+    T: Hash + Eq,
+    N: PartialEq,
+{
+    /// Two counters are equal if every item has the same count in both, where an item which
+    /// is missing from one counter has a count of zero there.
+    fn eq(&self, other: &IndexCounter<T, N>) -> bool {
+        self.map
+            .iter()
+            .all(|(item, count)| other.map.get(item).unwrap_or(&other.zero) == count)
+            && other
+                .map
+                .iter()
+                .all(|(item, count)| self.map.contains_key(item) || *count == self.zero)
+    }
+}
+
+impl<T, N> Eq for IndexCounter<T, N>
+where
+    T: Hash + Eq,
+    N: Eq,
+{
+}
+
+impl<T, N> AddAssign for IndexCounter<T, N>
+where
+    T: Hash + Eq,
+    N: Zero + AddAssign,
+{
+    /// Add another counter to this counter
+    ///
+    /// `c += d;` -> `c[x] += d[x]` for all `x`
+    fn add_assign(&mut self, rhs: Self) {
+        for (key, value) in rhs.map.into_iter() {
+            let entry = self.map.entry(key).or_insert_with(N::zero);
+            *entry += value;
+        }
+    }
+}
+
+impl<T, N> Add for IndexCounter<T, N>
+where
+    T: Hash + Eq,
+    N: Zero + AddAssign,
+{
+    type Output = IndexCounter<T, N>;
+
+    /// Add two counters together.
+    ///
+    /// `out = c + d;` -> `out[x] == c[x] + d[x]` for all `x`
+    ///
+    /// ```rust
+    /// # use counter::IndexCounter;
+    /// let c = "aaab".chars().collect::<IndexCounter<_>>();
+    /// let d = "abb".chars().collect::<IndexCounter<_>>();
+    ///
+    /// let e = c + d;
+    ///
+    /// assert_eq!(e.into_map().into_iter().collect::<Vec<_>>(), vec![('a', 4), ('b', 3)]);
+    /// ```
+    fn add(mut self, rhs: IndexCounter<T, N>) -> Self::Output {
+        self += rhs;
+        self
+    }
+}
+
+impl<T, N> SubAssign for IndexCounter<T, N>
+where
+    T: Hash + Eq,
+    N: PartialOrd + SubAssign + Zero,
+{
+    /// Subtract (keeping only positive values).
+    ///
+    /// `c -= d;` -> `c[x] -= d[x]` for all `x`,
+    /// keeping only items with a value greater than N::zero().
+    fn sub_assign(&mut self, rhs: Self) {
+        for (key, value) in rhs.map.into_iter() {
+            let mut remove = false;
+            if let Some(entry) = self.map.get_mut(&key) {
+                if *entry > value {
+                    *entry -= value;
+                } else {
+                    remove = true;
+                }
+            }
+            if remove {
+                self.map.shift_remove(&key);
+            }
+        }
+    }
+}
+
+impl<T, N> Sub for IndexCounter<T, N>
+where
+    T: Hash + Eq,
+    N: PartialOrd + SubAssign + Zero,
+{
+    type Output = IndexCounter<T, N>;
+
+    /// Subtract (keeping only positive values).
+    ///
+    /// `out = c - d;` -> `out[x] == c[x] - d[x]` for all `x`,
+    /// keeping only items with a value greater than N::zero().
+    ///
+    /// ```rust
+    /// # use counter::IndexCounter;
+    /// let c = "aaab".chars().collect::<IndexCounter<_>>();
+    /// let d = "abb".chars().collect::<IndexCounter<_>>();
+    ///
+    /// let e = c - d;
+    ///
+    /// assert_eq!(e.into_map().into_iter().collect::<Vec<_>>(), vec![('a', 2)]);
+    /// ```
+    fn sub(mut self, rhs: IndexCounter<T, N>) -> Self::Output {
+        self -= rhs;
+        self
+    }
+}
+
+impl<T, N> Deref for IndexCounter<T, N>
+where
+    T: Hash + Eq,
+{
+    type Target = IndexMap<T, N>;
+    fn deref(&self) -> &IndexMap<T, N> {
+        &self.map
+    }
+}
+
+impl<T, N> DerefMut for IndexCounter<T, N>
+where
+    T: Hash + Eq,
+{
+    fn deref_mut(&mut self) -> &mut IndexMap<T, N> {
+        &mut self.map
+    }
+}
+
+impl<'q, Q, T, N> Index<&'q Q> for IndexCounter<T, N>
+where
+    T: Hash + Eq + Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+{
+    type Output = N;
+
+    /// Index in immutable contexts
+    ///
+    /// Returns a reference to a `zero` value for missing keys.
+    ///
+    /// ```rust
+    /// # use counter::IndexCounter;
+    /// let counter = IndexCounter::<_>::init("aabbcc".chars());
+    /// assert_eq!(counter[&'a'], 2);
+    /// assert_eq!(counter[&'d'], 0);
+    /// ```
+    fn index(&self, key: &'q Q) -> &N {
+        self.map.get(key).unwrap_or(&self.zero)
+    }
+}
+
+impl<T, N> IntoIterator for IndexCounter<T, N>
+where
+    T: Hash + Eq,
+{
+    type Item = (T, N);
+    type IntoIter = index_map::IntoIter<T, N>;
+
+    /// Consumes the counter, producing an iterator over `(item, count)` pairs in insertion order.
+    fn into_iter(self) -> Self::IntoIter {
+        self.map.into_iter()
+    }
+}
+
+impl<'a, T, N> IntoIterator for &'a IndexCounter<T, N>
+where
+    T: Hash + Eq,
+{
+    type Item = (&'a T, &'a N);
+    type IntoIter = index_map::Iter<'a, T, N>;
+
+    /// Iterate over `(&item, &count)` pairs in insertion order.
+    fn into_iter(self) -> Self::IntoIter {
+        self.map.iter()
+    }
+}
+
+impl<T, N> iter::FromIterator<T> for IndexCounter<T, N>
+where
+    T: Hash + Eq,
+    N: PartialOrd + AddAssign + Zero + One,
+{
+    /// Produce a IndexCounter from an iterator of items. This is called automatically
+    /// by `iter.collect()`.
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        IndexCounter::init(iter)
+    }
+}
+
+impl<T, N> iter::FromIterator<(T, N)> for IndexCounter<T, N>
+where
+    T: Hash + Eq,
+    N: AddAssign + Zero,
+{
+    /// `from_iter` creates a counter from `(item, count)` tuples.
+    ///
+    /// The counts of duplicate items are summed.
+    fn from_iter<I: IntoIterator<Item = (T, N)>>(iter: I) -> Self {
+        let mut cnt = IndexCounter::new();
+        cnt.extend(iter);
+        cnt
+    }
+}
+
+impl<T, N> Extend<T> for IndexCounter<T, N>
+where
+    T: Hash + Eq,
+    N: PartialOrd + AddAssign + Zero + One,
+{
+    /// Extend a counter with the counts of the elements of an iterator.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.update(iter);
+    }
+}
+
+impl<T, N> Extend<(T, N)> for IndexCounter<T, N>
+where
+    T: Hash + Eq,
+    N: AddAssign + Zero,
+{
+    /// Extend a counter with `(item, count)` tuples.
+    ///
+    /// The counts of duplicate items are summed.
+    fn extend<I: IntoIterator<Item = (T, N)>>(&mut self, iter: I) {
+        for (item, item_count) in iter.into_iter() {
+            let entry = self.map.entry(item).or_insert_with(N::zero);
+            *entry += item_count;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eq_ignores_zero_counts() {
+        let mut counter = IndexCounter::<_>::init("aab".chars());
+        counter.insert('c', 0);
+        assert_eq!(counter, IndexCounter::init("aba".chars()));
+        assert_eq!(IndexCounter::init("aba".chars()), counter);
+        assert_ne!(counter, IndexCounter::init("ab".chars()));
+        assert_ne!(counter, IndexCounter::init("aabc".chars()));
+    }
+
+    #[test]
+    fn test_insertion_order() {
+        let counter = IndexCounter::<_>::init("cabbac".chars());
+        let items = counter.into_iter().collect::<Vec<_>>();
+        assert_eq!(items, vec![('c', 2), ('a', 2), ('b', 2)]);
+    }
+
+    #[test]
+    fn test_most_common_ties() {
+        let counter = IndexCounter::<_>::init("zyxxyzw".chars());
+        assert_eq!(
+            counter.most_common(),
+            vec![('z', 2), ('y', 2), ('x', 2), ('w', 1)]
+        );
+    }
+
+    #[test]
+    fn test_subtract_keeps_order() {
+        let mut counter = IndexCounter::<_>::init("abbcccd".chars());
+        counter.subtract("bb".chars());
+        assert_eq!(counter.keys().cloned().collect::<Vec<_>>(), vec!['a', 'c', 'd']);
+        assert_eq!(counter[&'c'], 3);
+        assert_eq!(counter.total(), 5);
+    }
+
+    #[test]
+    fn test_add_sub() {
+        let d = IndexCounter::<_>::init("abbccc".chars());
+        let e = IndexCounter::<_>::init("bccddd".chars());
+
+        assert_eq!(d.clone() + e.clone(), IndexCounter::init("abbbcccccddd".chars()));
+        assert_eq!(d - e, IndexCounter::init("abc".chars()));
+    }
+}
@@ -0,0 +1,254 @@
+//! Counting the k-mers of DNA sequences.
+//!
+//! [`Counter::kmers`](../struct.Counter.html#method.kmers) counts each k-mer as a slice of the
+//! sequence. For genome-scale inputs,
+//! [`Counter::packed_kmers`](../struct.Counter.html#method.packed_kmers) and
+//! [`Counter::canonical_kmers`](../struct.Counter.html#method.canonical_kmers) instead pack
+//! each k-mer of up to 32 bases into a `u64`, two bits per base, which [`pack`](fn.pack.html)
+//! and [`unpack`](fn.unpack.html) convert to and from.
+
+use alloc::vec::Vec;
+use core::ops::AddAssign;
+
+use num_traits::{One, Zero};
+
+use Counter;
+
+/// The longest k-mer which fits in a `u64`
+pub const MAX_PACKED_K: usize = 32;
+
+fn encode(base: u8) -> Option<u64> {
+    match base {
+        b'A' | b'a' => Some(0),
+        b'C' | b'c' => Some(1),
+        b'G' | b'g' => Some(2),
+        b'T' | b't' => Some(3),
+        _ => None,
+    }
+}
+
+fn check_packed_k(k: usize) {
+    assert!(
+        k > 0 && k <= MAX_PACKED_K,
+        "packed k-mers must have a length between 1 and 32"
+    );
+}
+
+fn mask(k: usize) -> u64 {
+    if k == MAX_PACKED_K {
+        !0
+    } else {
+        (1 << (2 * k)) - 1
+    }
+}
+
+/// Pack a k-mer into a `u64`, two bits per base, or return `None` if it contains anything
+/// other than `A`, `C`, `G` or `T`, in either case
+///
+/// ```rust
+/// # use counter::kmer;
+/// assert_eq!(kmer::pack(b"ACGT"), Some(0b00_01_10_11));
+/// assert_eq!(kmer::pack(b"ANGT"), None);
+/// ```
+///
+/// # Panics
+///
+/// Panics if the k-mer is empty or longer than 32 bases.
+pub fn pack(kmer: &[u8]) -> Option<u64> {
+    check_packed_k(kmer.len());
+    kmer.iter()
+        .try_fold(0, |packed, &base| encode(base).map(|code| packed << 2 | code))
+}
+
+/// Unpack a k-mer of length `k` from a `u64`, as uppercase bases
+///
+/// ```rust
+/// # use counter::kmer;
+/// assert_eq!(kmer::unpack(0b00_01_10_11, 4), b"ACGT".to_vec());
+/// ```
+///
+/// # Panics
+///
+/// Panics if `k` is zero or greater than 32.
+pub fn unpack(packed: u64, k: usize) -> Vec<u8> {
+    check_packed_k(k);
+    (0..k)
+        .rev()
+        .map(|i| b"ACGT"[(packed >> (2 * i) & 0b11) as usize])
+        .collect()
+}
+
+/// Returns the reverse complement of a packed k-mer of length `k`.
+///
+/// ```rust
+/// # use counter::kmer;
+/// let packed = kmer::pack(b"AACG").unwrap();
+/// assert_eq!(kmer::reverse_complement(packed, 4), kmer::pack(b"CGTT").unwrap());
+/// ```
+///
+/// # Panics
+///
+/// Panics if `k` is zero or greater than 32.
+pub fn reverse_complement(packed: u64, k: usize) -> u64 {
+    check_packed_k(k);
+    (0..k).fold(0, |reverse, i| reverse << 2 | (3 - (packed >> (2 * i) & 0b11)))
+}
+
+/// The packed forward and reverse-complement k-mers of a sequence, skipping any k-mer which
+/// contains something other than a base
+fn packed_windows<'a>(sequence: &'a [u8], k: usize) -> impl Iterator<Item = (u64, u64)> + 'a {
+    check_packed_k(k);
+    let mask = mask(k);
+    let shift = 2 * (k - 1);
+    let mut forward = 0u64;
+    let mut reverse = 0u64;
+    let mut valid = 0;
+    sequence.iter().filter_map(move |&base| match encode(base) {
+        Some(code) => {
+            forward = (forward << 2 | code) & mask;
+            reverse = reverse >> 2 | (3 - code) << shift;
+            valid += 1;
+            if valid >= k {
+                Some((forward, reverse))
+            } else {
+                None
+            }
+        }
+        None => {
+            valid = 0;
+            None
+        }
+    })
+}
+
+impl<'a, N> Counter<&'a [u8], N>
+where
+    N: PartialOrd + AddAssign + Zero + One,
+{
+    /// Create a new `Counter` of the k-mers of `sequence`: each run of `k` consecutive bytes,
+    /// as a slice of `sequence`
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let kmers = Counter::<_>::kmers(b"GATTACA", 2);
+    /// assert_eq!(kmers[&&b"AT"[..]], 1);
+    /// assert_eq!(kmers.len(), 6);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is zero.
+    pub fn kmers(sequence: &'a [u8], k: usize) -> Counter<&'a [u8], N> {
+        assert!(k > 0, "k-mers must have a length of at least one");
+        Counter::init(sequence.windows(k))
+    }
+}
+
+impl<N> Counter<u64, N>
+where
+    N: PartialOrd + AddAssign + Zero + One,
+{
+    /// Create a new `Counter` of the k-mers of a DNA sequence, each packed into a `u64`
+    /// with [`kmer::pack`](kmer/fn.pack.html)
+    ///
+    /// Bases may be in either case, and k-mers containing anything other than `A`, `C`, `G`
+    /// or `T`, such as `N`, are skipped.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// use counter::kmer;
+    ///
+    /// let kmers = Counter::<_>::packed_kmers(b"ACGTNACG", 3);
+    /// assert_eq!(kmers[&kmer::pack(b"ACG").unwrap()], 2);
+    /// assert_eq!(kmers.len(), 2);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is zero or greater than 32.
+    pub fn packed_kmers(sequence: &[u8], k: usize) -> Counter<u64, N> {
+        Counter::init(packed_windows(sequence, k).map(|(forward, _)| forward))
+    }
+
+    /// Create a new `Counter` of the canonical k-mers of a DNA sequence, packed like those
+    /// of [`packed_kmers`](#method.packed_kmers)
+    ///
+    /// A k-mer and its reverse complement are counted as one: the canonical k-mer is whichever
+    /// of the two packs to the smaller number.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// use counter::kmer;
+    ///
+    /// let kmers = Counter::<_>::canonical_kmers(b"AACGTT", 2);
+    /// // AA and TT are reverse complements of each other
+    /// assert_eq!(kmers[&kmer::pack(b"AA").unwrap()], 2);
+    /// assert_eq!(kmers.get(&kmer::pack(b"TT").unwrap()), None);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is zero or greater than 32.
+    pub fn canonical_kmers(sequence: &[u8], k: usize) -> Counter<u64, N> {
+        Counter::init(
+            packed_windows(sequence, k).map(|(forward, reverse)| forward.min(reverse)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_round_trip() {
+        let kmer = b"ACGTTGCAACGTTGCAACGTTGCAACGTTGCA";
+        assert_eq!(kmer.len(), MAX_PACKED_K);
+        let packed = pack(kmer).unwrap();
+        assert_eq!(unpack(packed, kmer.len()), kmer.to_vec());
+        assert_eq!(pack(b"acgt"), pack(b"ACGT"));
+    }
+
+    #[test]
+    fn test_packed_kmers_match_slices() {
+        let sequence = b"ACGTACGGTACCATGACGTTTAGC";
+        for k in 1..8 {
+            let slices = Counter::<_>::kmers(sequence, k);
+            let packed = Counter::<_>::packed_kmers(sequence, k);
+            let unpacked = packed
+                .into_iter()
+                .map(|(kmer, count)| (unpack(kmer, k), count))
+                .collect::<Counter<Vec<u8>>>();
+            let expected = slices
+                .into_iter()
+                .map(|(kmer, count)| (kmer.to_vec(), count))
+                .collect::<Counter<Vec<u8>>>();
+            assert_eq!(unpacked, expected);
+        }
+    }
+
+    #[test]
+    fn test_canonical_kmers() {
+        let sequence = b"GATTACAGATTACA";
+        let k = 4;
+        let canonical = Counter::<_>::canonical_kmers(sequence, k);
+        let forward = Counter::<u64>::packed_kmers(sequence, k);
+        for (&kmer, &count) in forward.iter() {
+            let reverse = reverse_complement(kmer, k);
+            let expected = if reverse == kmer {
+                count
+            } else {
+                count + forward.get(&reverse).unwrap_or(&0)
+            };
+            assert_eq!(canonical[&kmer.min(reverse)], expected);
+        }
+        assert_eq!(canonical.total(), forward.total());
+    }
+
+    #[test]
+    fn test_full_length_kmers() {
+        let sequence = [b'T'; 40];
+        let kmers = Counter::<_>::canonical_kmers(&sequence, MAX_PACKED_K);
+        assert_eq!(kmers[&0], 9);
+    }
+}
@@ -12,6 +12,47 @@
 //! let counts_counts = char_counts.values().collect::<Counter<_>>();
 //! ```
 //!
+//! Prebuilt `(item, count)` pairs can be collected too; the counts of duplicate
+//! items are summed.
+//!
+//! ```rust
+//! # use counter::Counter;
+//! let counter = vec![('a', 1), ('b', 2), ('a', 3)].into_iter().collect::<Counter<char>>();
+//! assert_eq!(counter.get(&'a'), Some(&4));
+//! ```
+//!
+//! ## Count owned or borrowed items
+//!
+//! A `Counter` owns its keys, so it can count values produced on the fly and
+//! be returned from a function which owns its input. Counting references is
+//! still possible: just count an iterator of references.
+//!
+//! ```rust
+//! # use counter::Counter;
+//! fn word_counts(text: String) -> Counter<String> {
+//!     text.split_whitespace().map(str::to_lowercase).collect()
+//! }
+//!
+//! let counts = word_counts("The cat saw the dog".to_string());
+//! assert_eq!(counts.get("the"), Some(&2));
+//!
+//! let words = vec!["a", "b", "a"];
+//! let borrowed = words.iter().collect::<Counter<&&str>>();
+//! assert_eq!(borrowed.get(&&"a"), Some(&2));
+//! ```
+//!
+//! ## Count by a derived key
+//!
+//! Count a key derived from each item, rather than the item itself, with `init_by_key` or
+//! the `CountsBy` iterator extension.
+//!
+//! ```rust
+//! # use counter::CountsBy;
+//! let lines = vec!["GET / 200", "GET /missing 404", "POST /form 200"];
+//! let by_status = lines.iter().counts_by(|line| line.rsplit(' ').next().unwrap());
+//! assert_eq!(by_status[&"200"], 2);
+//! ```
+//!
 //! ## Update a count
 //!
 //! ```rust
@@ -97,8 +138,8 @@
 //!
 //! let inty_counts = intys.iter().collect::<Counter<_>>();
 //! println!("{:?}", inty_counts);
-//! // {Inty { i: 8 }: 2, Inty { i: 0 }: 3, Inty { i: 9 }: 1, Inty { i: 3 }: 1,
-//! //  Inty { i: 7 }: 1, Inty { i: 6 }: 1, Inty { i: 5 }: 1}
+//! // Counter({Inty { i: 0 }: 3, Inty { i: 8 }: 2, Inty { i: 3 }: 1, Inty { i: 5 }: 1,
+//! //          Inty { i: 6 }: 1, Inty { i: 7 }: 1, Inty { i: 9 }: 1})
 //! assert!(inty_counts.get(&Inty { i: 8 }) == Some(&2));
 //! assert!(inty_counts.get(&Inty { i: 0 }) == Some(&3));
 //! assert!(inty_counts.get(&Inty { i: 6 }) == Some(&1));
@@ -112,62 +153,508 @@
 //!
 //! ```rust
 //! # use counter::Counter;
-//! # use std::collections::HashMap;
+//! # #[cfg(not(feature = "std"))] extern crate hashbrown;
+//! # #[cfg(not(feature = "std"))] use hashbrown::HashMap;
+//! # #[cfg(feature = "std")] use std::collections::HashMap;
 //! let counter: Counter<_, i8> = "abbccc".chars().collect();
 //! let expected: HashMap<char, i8> = [('a', 1), ('b', 2), ('c', 3)].iter().cloned().collect();
 //! assert!(counter.into_map() == expected);
 //! ```
+//!
+//! ## Use your own hasher
+//!
+//! Like `HashMap`, `Counter` takes an optional `BuildHasher` type parameter,
+//! so a faster or deterministic hasher can be used. All operators preserve the
+//! hasher type.
+//!
+//! ```rust
+//! # use counter::Counter;
+//! use std::collections::hash_map::DefaultHasher;
+//! use std::hash::BuildHasherDefault;
+//!
+//! type Hasher = BuildHasherDefault<DefaultHasher>;
+//! let counter: Counter<_, usize, Hasher> = "abbccc".chars().collect();
+//! let other: Counter<_, usize, Hasher> = "bccddd".chars().collect();
+//! let union: Counter<_, usize, Hasher> = counter | other;
+//! assert_eq!(union.get(&'d'), Some(&3));
+//! ```
+//!
+//! ## Keep the items sorted
+//!
+//! [`BTreeCounter`](struct.BTreeCounter.html) is backed by a `BTreeMap`, so its items only
+//! need to be `Ord` and are iterated in sorted order.
+//!
+//! ```rust
+//! # use counter::BTreeCounter;
+//! let counter = "abracadabra".chars().collect::<BTreeCounter<_>>();
+//! let table = counter.into_iter().collect::<Vec<_>>();
+//! assert_eq!(table, vec![('a', 5), ('b', 2), ('c', 1), ('d', 1), ('r', 2)]);
+//! ```
+//!
+//! ## Count bytes
+//!
+//! [`ByteCounter`](struct.ByteCounter.html) keeps its counts in an array of 256 `usize`s, which is much faster than
+//! hashing each byte, and with the `std` feature can count everything read from an
+//! `io::Read`er.
+//!
+//! ```rust
+//! # use counter::ByteCounter;
+//! let mut counter = ByteCounter::new();
+//! counter.update(b"hello world");
+//! assert_eq!(counter[b'o'], 2);
+//! ```
+//!
+//! ## Count from many threads
+//!
+//! [`concurrent::ConcurrentCounter`](concurrent/struct.ConcurrentCounter.html) shards its
+//! items across several locks, so it can be updated through a shared reference, and turned
+//! into an ordinary `Counter` with `snapshot()` or `into_counter()`.
+//!
+//! For a set of items which is known up front,
+//! [`concurrent::AtomicCounter`](concurrent/struct.AtomicCounter.html) keeps an atomic count
+//! per item, so counting them takes no locks at all.
+//!
+//! ## Count approximately
+//!
+//! [`sketch::CountMinSketch`](sketch/struct.CountMinSketch.html) estimates counts in a fixed
+//! amount of memory, which suits streams with too many distinct items to count exactly.
+//! Its estimates are never too low, so a second pass over the stream can count the heavy
+//! items exactly.
+//!
+//! ```rust
+//! # use counter::sketch::CountMinSketch;
+//! let mut sketch = CountMinSketch::<str>::new(1024, 4);
+//! let urls = "/ /about / /blog /".split(' ');
+//! for url in urls.clone() {
+//!     sketch.update(url);
+//! }
+//! assert!(sketch.estimate("/") >= 3);
+//!
+//! let heavy = sketch.count_heavy(urls, 2);
+//! assert_eq!(heavy[&"/"], 3);
+//! ```
+//!
+//! To find the most common items in a single pass,
+//! [`sketch::HeavyHitters`](sketch/struct.HeavyHitters.html) tracks a bounded number of
+//! candidates, with bounds on how far off each count may be.
+//! [`sketch::LossyCounter`](sketch/struct.LossyCounter.html) similarly finds every item above
+//! a given frequency, with counts which are off by at most a chosen fraction of the total.
+//!
+//! And to estimate how many distinct items a stream has,
+//! [`sketch::DistinctEstimator`](sketch/struct.DistinctEstimator.html) uses the HyperLogLog
+//! algorithm.
+//!
+//! ## Count recent items
+//!
+//! [`rolling::WindowedCounter`](rolling/struct.WindowedCounter.html) only keeps the counts
+//! added within a trailing window of time, such as the last minute, or of sequence numbers,
+//! such as the last thousand events.
+//!
+//! ```rust
+//! # use counter::rolling::WindowedCounter;
+//! let mut errors = WindowedCounter::<_>::new(60);
+//! errors.increment("timeout", 0);
+//! errors.increment("refused", 30);
+//! errors.advance(75);
+//! assert_eq!(errors.count_of(&"timeout"), 0);
+//! assert_eq!(errors.count_of(&"refused"), 1);
+//! ```
+//!
+//! [`rolling::DecayingCounter`](rolling/struct.DecayingCounter.html) instead lets its counts
+//! fade away gradually, halving them every half-life.
+//!
+//! ## Count on several replicas
+//!
+//! [`Counter::merge_crdt`](struct.Counter.html#method.merge_crdt) merges grow-only counters
+//! kept on several replicas, and [`crdt::PnCounter`](crdt/struct.PnCounter.html) also allows
+//! decrements, so that the replicas agree however their states are exchanged.
+//!
+//! # Optional features
+//!
+//! - `std` (enabled by default): use `std::collections::HashMap`. Without it, the crate is
+//!   `no_std`, only needs `alloc`, and `Counter` is backed by `hashbrown::HashMap`.
+//! - `csv`: add `Counter::to_csv_writer` and `Counter::from_csv_reader`, which write and read
+//!   `item,count` rows with a configurable delimiter and order. This enables `serde` too.
+//! - `indexmap`: add [`IndexCounter`](struct.IndexCounter.html), which iterates its items in
+//!   the order in which they were first seen, and breaks ties in `most_common` the same way.
+//! - `rand`: add `Counter::sample`, `sample_n`, `sample_without_replacement`, `choose` and
+//!   `elements_shuffled`, and `FrequencyDistribution::sample`, which pick random items with the
+//!   probabilities of their counts.
+//! - `rayon`: implement `FromParallelIterator` and `ParallelExtend` for `Counter`, so that
+//!   items can be counted in parallel with `par_iter().collect()` and `par_extend`.
+//! - `serde`: implement `Serialize` and `Deserialize` for `Counter`, representing it as a
+//!   map from items to counts. Deserialization sums the counts of duplicate items.
+//!   For items which can't be map keys, such as tuples in JSON, `serde_pairs` represents a
+//!   counter as a sequence of `[item, count]` pairs instead.
+//! - `unicode-normalization`: add `Counter::init_normalized` and `update_normalized`, which
+//!   bring strings into a Unicode normalization form, optionally case-folded, before counting
+//!   them, so that canonically equivalent strings are counted together.
+//! - `unicode-segmentation`: make `Counter::words` split text into words by the Unicode word
+//!   boundary rules rather than at every character which isn't alphanumeric or an apostrophe,
+//!   and add `Counter::graphemes`, which counts extended grapheme clusters rather than `char`s.
 
-#[cfg(test)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(all(test, feature = "std"))]
 #[macro_use]
 extern crate maplit;
+#[cfg(all(test, not(feature = "std")))]
+#[macro_use]
+extern crate std;
 
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate core;
+#[cfg(feature = "csv")]
+extern crate csv;
+#[cfg(not(feature = "std"))]
+extern crate hashbrown;
 extern crate num_traits;
-use num_traits::{One, Zero};
+#[cfg(feature = "indexmap")]
+extern crate indexmap;
+#[cfg(feature = "rand")]
+extern crate rand;
+#[cfg(feature = "rayon")]
+extern crate rayon;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(feature = "unicode-normalization")]
+extern crate unicode_normalization;
+#[cfg(feature = "unicode-segmentation")]
+extern crate unicode_segmentation;
+use num_traits::{CheckedAdd, One, SaturatingAdd, Signed, ToPrimitive, Unsigned, Zero};
+
+use alloc::borrow::ToOwned;
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{BuildHasher, Hash, Hasher};
+use core::iter;
+use core::mem;
+use core::ops::{
+    Add, AddAssign, BitAnd, BitAndAssign, BitOr, BitOrAssign, Deref, DerefMut, Index, IndexMut,
+    Mul, MulAssign, Neg, Sub, SubAssign,
+};
+
+#[cfg(not(feature = "std"))]
+use hashbrown::hash_map::{self, HashMap};
+#[cfg(not(feature = "std"))]
+use hashbrown::DefaultHashBuilder as RandomState;
+#[cfg(feature = "std")]
+use std::collections::hash_map::{self, HashMap, RandomState};
+
+#[macro_use]
+mod macros;
+
+mod btree;
+mod bytes;
+#[cfg(feature = "std")]
+pub mod concurrent;
+pub mod crdt;
+#[cfg(feature = "csv")]
+mod csv_table;
+mod diff;
+mod distribution;
+mod error;
+#[cfg(feature = "std")]
+pub mod export;
+mod histogram;
+#[cfg(feature = "indexmap")]
+mod index;
+pub mod kmer;
+#[cfg(feature = "rayon")]
+mod parallel;
+pub mod rolling;
+#[cfg(feature = "rand")]
+mod sampling;
+#[cfg(feature = "serde")]
+pub mod serde_pairs;
+#[cfg(feature = "serde")]
+mod serialization;
+pub mod sketch;
+mod stats;
+mod text;
 
-use std::collections::HashMap;
-use std::hash::Hash;
-use std::iter;
-use std::ops::{Add, AddAssign, BitAnd, BitOr, Deref, DerefMut, Sub, SubAssign};
+pub use btree::BTreeCounter;
+pub use bytes::ByteCounter;
+#[cfg(feature = "csv")]
+pub use csv_table::{CsvOptions, CsvOrder};
+pub use diff::{CounterDelta, CounterDiff};
+pub use distribution::FrequencyDistribution;
+pub use error::OverflowError;
+pub use histogram::Histogram;
+#[cfg(feature = "indexmap")]
+pub use index::IndexCounter;
+pub use stats::TestStatistic;
+#[cfg(feature = "unicode-normalization")]
+pub use text::Normalization;
 
-type CounterMap<T, N> = HashMap<T, N>;
+type CounterMap<T, N, S> = HashMap<T, N, S>;
 
-#[derive(Clone, PartialEq, Eq, Debug, Default)]
-pub struct Counter<T: Hash + Eq, N = usize> {
-    map: CounterMap<T, N>,
+#[derive(Clone)]
+pub struct Counter<T: Hash + Eq, N = usize, S = RandomState> {
+    map: CounterMap<T, N, S>,
+    // necessary for `Index::index` since we cannot declare generic `static` variables.
+    zero: N,
 }
 
-impl<T, N> Counter<T, N>
+impl<T, N, S> Counter<T, N, S>
 where
     T: Hash + Eq,
     N: PartialOrd + AddAssign + Zero + One,
+    S: BuildHasher,
 {
-    /// Create a new `Counter` initialized with the given iterable
-    pub fn init<I>(iterable: I) -> Counter<T, N>
+    /// Add the counts of the elements from the given iterable to this counter
+    pub fn update<I>(&mut self, iterable: I)
     where
         I: IntoIterator<Item = T>,
     {
-        let mut counter = Counter::new();
-        counter.update(iterable);
-        counter
+        for item in iterable.into_iter() {
+            let entry = self.map.entry(item).or_insert_with(N::zero);
+            *entry += N::one();
+        }
     }
 
-    /// Add the counts of the elements from the given iterable to this counter
-    pub fn update<I>(&mut self, iterable: I)
+    /// Add the counts of the keys which `key` derives from the elements of the given iterable
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mut counter = Counter::<usize>::new();
+    /// counter.update_by_key(vec!["a", "bb", "cc"], |word| word.len());
+    /// assert_eq!(counter[&2], 2);
+    /// ```
+    pub fn update_by_key<I, F>(&mut self, iterable: I, mut key: F)
     where
-        I: IntoIterator<Item = T>,
+        I: IntoIterator,
+        F: FnMut(I::Item) -> T,
     {
         for item in iterable.into_iter() {
-            let entry = self.map.entry(item).or_insert_with(N::zero);
+            let entry = self.map.entry(key(item)).or_insert_with(N::zero);
             *entry += N::one();
         }
     }
+
+    /// Add one to the count of the given item, returning its new count
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mut counter = "abb".chars().collect::<Counter<_>>();
+    /// assert_eq!(counter.increment('b'), 3);
+    /// assert_eq!(counter.increment('c'), 1);
+    /// ```
+    pub fn increment(&mut self, item: T) -> N
+    where
+        N: Clone,
+    {
+        self.add_count(item, N::one())
+    }
+
+    /// Add `count` to the count of the given item, returning its new count
+    ///
+    /// This takes a single hash lookup.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mut counter = "abb".chars().collect::<Counter<_>>();
+    /// assert_eq!(counter.add_count('b', 3), 5);
+    /// assert_eq!(counter.add_count('c', 2), 2);
+    /// assert_eq!(counter.add_count('d', 0), 0);
+    /// assert!(!counter.contains_key(&'d'));
+    /// ```
+    pub fn add_count(&mut self, item: T, count: N) -> N
+    where
+        N: Clone,
+    {
+        if count.is_zero() {
+            return self.map.get(&item).cloned().unwrap_or_else(N::zero);
+        }
+        let entry = self.map.entry(item).or_insert_with(N::zero);
+        *entry += count;
+        entry.clone()
+    }
+
+    /// Add `count` to the count of the given item, returning its new count, or an error if
+    /// the count would overflow, in which case the counter is left unchanged
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mut counter = Counter::<_, u8>::init("abb".chars());
+    /// assert_eq!(counter.checked_add('b', 250), Ok(252));
+    /// assert!(counter.checked_add('b', 4).is_err());
+    /// assert_eq!(counter[&'b'], 252);
+    /// ```
+    pub fn checked_add(&mut self, item: T, count: N) -> Result<N, OverflowError<T>>
+    where
+        N: Clone + CheckedAdd,
+    {
+        match self.map.get_mut(&item) {
+            Some(total) => match total.checked_add(&count) {
+                Some(sum) => {
+                    *total = sum.clone();
+                    Ok(sum)
+                }
+                None => Err(OverflowError::new(item)),
+            },
+            None if count.is_zero() => Ok(count),
+            None => {
+                self.map.insert(item, count.clone());
+                Ok(count)
+            }
+        }
+    }
+
+    /// Add the counts of the elements from the given iterable to this counter, like
+    /// [`update`](#method.update), but stop with an error at the first element whose count
+    /// would overflow
+    ///
+    /// The elements before that one are still counted.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mut counter = Counter::<_, u8>::new();
+    /// assert!(counter.checked_update(vec!['a'; 255]).is_ok());
+    /// let error = counter.checked_update("ba".chars()).unwrap_err();
+    /// assert_eq!(error.item(), &'a');
+    /// assert_eq!(counter[&'b'], 1);
+    /// ```
+    pub fn checked_update<I>(&mut self, iterable: I) -> Result<(), OverflowError<T>>
+    where
+        I: IntoIterator<Item = T>,
+        N: Clone + CheckedAdd,
+    {
+        for item in iterable {
+            self.checked_add(item, N::one())?;
+        }
+        Ok(())
+    }
+
+    /// Add `count` to the count of the given item, returning its new count, which stays at
+    /// the largest possible count rather than overflowing
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mut counter = Counter::<_, u8>::init("abb".chars());
+    /// assert_eq!(counter.saturating_add('b', 250), 252);
+    /// assert_eq!(counter.saturating_add('b', 250), 255);
+    /// ```
+    pub fn saturating_add(&mut self, item: T, count: N) -> N
+    where
+        N: Clone + SaturatingAdd,
+    {
+        if count.is_zero() {
+            return self.map.get(&item).cloned().unwrap_or_else(N::zero);
+        }
+        let entry = self.map.entry(item).or_insert_with(N::zero);
+        *entry = entry.saturating_add(&count);
+        entry.clone()
+    }
+
+    /// Add the counts of the elements from the given iterable to this counter, like
+    /// [`update`](#method.update), but let the counts stay at the largest possible count
+    /// rather than overflowing
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mut counter = Counter::<_, u8>::new();
+    /// counter.saturating_update(vec!['a'; 300]);
+    /// assert_eq!(counter[&'a'], 255);
+    /// ```
+    pub fn saturating_update<I>(&mut self, iterable: I)
+    where
+        I: IntoIterator<Item = T>,
+        N: SaturatingAdd,
+    {
+        for item in iterable {
+            let entry = self.map.entry(item).or_insert_with(N::zero);
+            *entry = entry.saturating_add(&N::one());
+        }
+    }
+
+    /// Add the counts of the given `(item, count)` pairs to this counter
+    ///
+    /// Since a `Counter` is itself an iterator of `(item, count)` pairs, this also merges
+    /// another counter into this one without cloning its items.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// # #[cfg(not(feature = "std"))] extern crate hashbrown;
+    /// # #[cfg(not(feature = "std"))] use hashbrown::HashMap;
+    /// # #[cfg(feature = "std")] use std::collections::HashMap;
+    /// let mut counter = "abbccc".chars().collect::<Counter<_>>();
+    /// counter.update_from_pairs(vec![('a', 2), ('d', 1)]);
+    /// counter.update_from_pairs("bd".chars().collect::<Counter<_>>());
+    /// let expect = [('a', 3), ('b', 3), ('c', 3), ('d', 2)].iter().cloned().collect::<HashMap<_, _>>();
+    /// assert_eq!(counter.into_map(), expect);
+    /// ```
+    pub fn update_from_pairs<I>(&mut self, pairs: I)
+    where
+        I: IntoIterator<Item = (T, N)>,
+    {
+        self.extend(pairs);
+    }
 }
 
-impl<T, N> Counter<T, N>
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: AddAssign + Zero,
+    S: BuildHasher,
+{
+    /// Add the counts of `other` to this counter, moving its items rather than cloning them
+    ///
+    /// If `other` has more items, the two maps are swapped first, so that the smaller one is
+    /// the one which is iterated over and reinserted.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mut counter = "aab".chars().collect::<Counter<_>>();
+    /// counter.merge_from("abbbc".chars().collect());
+    /// assert_eq!((counter[&'a'], counter[&'b'], counter[&'c']), (3, 4, 1));
+    /// ```
+    pub fn merge_from(&mut self, mut other: Counter<T, N, S>) {
+        if other.map.len() > self.map.len() {
+            mem::swap(&mut self.map, &mut other.map);
+        }
+        for (item, count) in other.map {
+            *self.map.entry(item).or_insert_with(N::zero) += count;
+        }
+    }
+
+    /// Add the counts of `other` to this counter, cloning the items which this counter doesn't
+    /// have yet
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mut counter = "aab".chars().collect::<Counter<_>>();
+    /// let other = "abbbc".chars().collect::<Counter<_>>();
+    /// counter.merge_from_ref(&other);
+    /// assert_eq!((counter[&'a'], counter[&'b'], counter[&'c']), (3, 4, 1));
+    /// assert_eq!(other.len(), 3);
+    /// ```
+    pub fn merge_from_ref(&mut self, other: &Counter<T, N, S>)
+    where
+        T: Clone,
+        N: Clone,
+    {
+        for (item, count) in other.map.iter() {
+            match self.map.get_mut(item) {
+                Some(entry) => *entry += count.clone(),
+                None => {
+                    self.map.insert(item.clone(), count.clone());
+                }
+            }
+        }
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
 where
     T: Hash + Eq,
     N: PartialOrd + SubAssign + Zero + One,
+    S: BuildHasher,
 {
     /// Remove the counts of the elements from the given iterable to this counter
     ///
@@ -175,7 +662,9 @@ where
     ///
     /// ```rust
     /// # use counter::Counter;
-    /// # use std::collections::HashMap;
+    /// # #[cfg(not(feature = "std"))] extern crate hashbrown;
+    /// # #[cfg(not(feature = "std"))] use hashbrown::HashMap;
+    /// # #[cfg(feature = "std")] use std::collections::HashMap;
     /// let mut counter = "abbccc".chars().collect::<Counter<_>>();
     /// counter.subtract("abba".chars());
     /// let expect = [('c', 3)].iter().cloned().collect::<HashMap<_, _>>();
@@ -200,333 +689,3297 @@ where
     }
 }
 
-impl<T, N> Counter<T, N>
+impl<T, N, S> Counter<T, N, S>
 where
     T: Hash + Eq,
+    N: Signed + SubAssign,
+    S: BuildHasher,
 {
-    /// Create a new, empty `Counter`
-    pub fn new() -> Counter<T, N> {
-        Counter {
-            map: HashMap::new(),
-        }
-    }
-    
-    /// Consumes this counter and returns a HashMap mapping the items to the counts.
-    pub fn into_map(self) -> HashMap<T, N> {
-        self.map
-    }
-}
-
-impl<T, N> Counter<T, N>
-where
-    T: Hash + Eq + Clone,
-    N: Clone + Ord,
-{
-    /// Create an iterator over `(frequency, elem)` pairs, sorted most to least common.
-    ///
-    /// ```rust
-    /// # use counter::Counter;
-    /// let mc = "pappaopolo".chars().collect::<Counter<_>>().most_common();
-    /// let expected = vec![('p', 4), ('o', 3), ('a', 2), ('l', 1)];
-    /// assert_eq!(mc, expected);
-    /// ```
-    ///
-    /// Note that the ordering of duplicates is unstable.
-    pub fn most_common(&self) -> Vec<(T, N)> {
-        use std::cmp::Ordering;
-        self.most_common_tiebreaker(|ref _a, ref _b| Ordering::Equal)
-    }
-
-    /// Create an iterator over `(frequency, elem)` pairs, sorted most to least common.
-    ///
-    /// In the event that two keys have an equal frequency, use the supplied ordering function
-    /// to further arrange the results.
+    /// Remove the counts of the elements from the given iterable to this counter,
+    /// allowing the counts to become negative
     ///
-    /// For example, we can sort reverse-alphabetically:
+    /// Unlike [`subtract`](#method.subtract), only counts which become exactly zero are removed.
     ///
     /// ```rust
     /// # use counter::Counter;
-    /// let counter = "eaddbbccc".chars().collect::<Counter<_>>();
-    /// let by_common = counter.most_common_tiebreaker(|&a, &b| b.cmp(&a));
-    /// let expected = vec![('c', 3), ('d', 2), ('b', 2), ('e', 1), ('a', 1)];
-    /// assert_eq!(by_common, expected);
+    /// # #[cfg(not(feature = "std"))] extern crate hashbrown;
+    /// # #[cfg(not(feature = "std"))] use hashbrown::HashMap;
+    /// # #[cfg(feature = "std")] use std::collections::HashMap;
+    /// let mut counter = "abbccc".chars().collect::<Counter<_, i64>>();
+    /// counter.subtract_signed("abbbd".chars());
+    /// let expect = [('b', -1), ('c', 3), ('d', -1)].iter().cloned().collect::<HashMap<_, _>>();
+    /// assert_eq!(counter.into_map(), expect);
     /// ```
-    pub fn most_common_tiebreaker<F>(&self, tiebreaker: F) -> Vec<(T, N)>
+    pub fn subtract_signed<I>(&mut self, iterable: I)
     where
-        F: Fn(&T, &T) -> ::std::cmp::Ordering,
+        I: IntoIterator<Item = T>,
     {
-        use std::cmp::Ordering;
-
-        let mut items = self.map
-            .iter()
-            .map(|(key, count)| (key.clone(), count.clone()))
-            .collect::<Vec<_>>();
-        items.sort_by(|&(ref a_item, ref a_count), &(ref b_item, ref b_count)| {
-            match b_count.cmp(&a_count) {
-                Ordering::Equal => tiebreaker(&a_item, &b_item),
-                unequal => unequal,
+        for item in iterable.into_iter() {
+            match self.map.entry(item) {
+                hash_map::Entry::Occupied(mut entry) => {
+                    *entry.get_mut() -= N::one();
+                    if entry.get().is_zero() {
+                        entry.remove();
+                    }
+                }
+                hash_map::Entry::Vacant(entry) => {
+                    entry.insert(-N::one());
+                }
             }
-        });
-        items
+        }
     }
-}
 
-impl<T, N> Counter<T, N>
-where
-    T: Hash + Eq + Clone + Ord,
-    N: Clone + Ord,
-{
-    /// Create an iterator over `(frequency, elem)` pairs, sorted most to least common.
+    /// Remove all items whose count is not positive
     ///
-    /// In the event that two keys have an equal frequency, use the natural ordering of the keys
-    /// to further sort the results.
+    /// This is the equivalent of Python's unary `+` on a `Counter`.
     ///
     /// ```rust
     /// # use counter::Counter;
-    /// let mc = "abracadabra".chars().collect::<Counter<_>>().most_common_ordered();
-    /// let expect = vec![('a', 5), ('b', 2), ('r', 2), ('c', 1), ('d', 1)];
-    /// assert_eq!(mc, expect);
+    /// # #[cfg(not(feature = "std"))] extern crate hashbrown;
+    /// # #[cfg(not(feature = "std"))] use hashbrown::HashMap;
+    /// # #[cfg(feature = "std")] use std::collections::HashMap;
+    /// let mut counter = "abbccc".chars().collect::<Counter<_, i64>>();
+    /// counter.subtract_signed("abbbd".chars());
+    /// counter.keep_positive();
+    /// let expect = [('c', 3)].iter().cloned().collect::<HashMap<_, _>>();
+    /// assert_eq!(counter.into_map(), expect);
     /// ```
-    pub fn most_common_ordered(&self) -> Vec<(T, N)> {
-        self.most_common_tiebreaker(|ref a, ref b| a.cmp(&b))
+    pub fn keep_positive(&mut self) {
+        self.map.retain(|_, count| count.is_positive());
     }
 }
 
-impl<T, N> AddAssign for Counter<T, N>
+impl<T, N, S> Counter<T, N, S>
 where
-    T: Clone + Hash + Eq,
-    N: Clone + Zero + AddAssign,
+    T: Hash + Eq,
+    N: Clone + PartialOrd + SubAssign + Zero,
+    S: BuildHasher,
 {
-    /// Add another counter to this counter
+    /// Subtract the counts of another counter from this counter, key by key
     ///
-    /// `c += d;` -> `c[x] += d[x]` for all `x`
+    /// Non-positive counts are automatically removed
     ///
     /// ```rust
     /// # use counter::Counter;
-    /// # use std::collections::HashMap;
-    /// let mut c = "aaab".chars().collect::<Counter<_>>();
+    /// # #[cfg(not(feature = "std"))] extern crate hashbrown;
+    /// # #[cfg(not(feature = "std"))] use hashbrown::HashMap;
+    /// # #[cfg(feature = "std")] use std::collections::HashMap;
+    /// let mut counter = "abbccc".chars().collect::<Counter<_>>();
+    /// let other = "abbcd".chars().collect::<Counter<_>>();
+    /// counter.subtract_counter(&other);
+    /// let expect = [('c', 2)].iter().cloned().collect::<HashMap<_, _>>();
+    /// assert_eq!(counter.into_map(), expect);
+    /// ```
+    pub fn subtract_counter(&mut self, rhs: &Counter<T, N, S>) {
+        for (key, value) in rhs.map.iter() {
+            let mut remove = false;
+            if let Some(entry) = self.map.get_mut(key) {
+                if *entry >= *value {
+                    *entry -= value.clone();
+                } else {
+                    remove = true;
+                }
+                if *entry == N::zero() {
+                    remove = true;
+                }
+            }
+            if remove {
+                self.map.remove(key);
+            }
+        }
+    }
+}
+
+impl<T, N> Counter<T, N>
+where
+    T: Hash + Eq,
+    N: Zero,
+{
+    /// Create a new, empty `Counter`
+    pub fn new() -> Counter<T, N> {
+        Counter {
+            map: HashMap::new(),
+            zero: N::zero(),
+        }
+    }
+
+    /// Create a new, empty `Counter` with space for at least `capacity` items
+    pub fn with_capacity(capacity: usize) -> Counter<T, N> {
+        Counter {
+            map: HashMap::with_capacity(capacity),
+            zero: N::zero(),
+        }
+    }
+
+    /// Create a new `Counter` initialized with the given iterable
+    pub fn init<I>(iterable: I) -> Counter<T, N>
+    where
+        I: IntoIterator<Item = T>,
+        N: PartialOrd + AddAssign + Zero + One,
+    {
+        let mut counter = Counter::new();
+        counter.update(iterable);
+        counter
+    }
+
+    /// Create a new `Counter` which counts the keys that `key` derives from the elements of
+    /// the given iterable, rather than the elements themselves
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// struct Response {
+    ///     status: u16,
+    ///     path: &'static str,
+    /// }
+    ///
+    /// let responses = vec![
+    ///     Response { status: 200, path: "/" },
+    ///     Response { status: 404, path: "/missing" },
+    ///     Response { status: 200, path: "/about" },
+    /// ];
+    /// let by_status = Counter::<_>::init_by_key(&responses, |response| response.status);
+    /// assert_eq!(by_status[&200], 2);
+    /// assert_eq!(by_status[&404], 1);
+    /// ```
+    pub fn init_by_key<I, F>(iterable: I, key: F) -> Counter<T, N>
+    where
+        I: IntoIterator,
+        F: FnMut(I::Item) -> T,
+        N: PartialOrd + AddAssign + Zero + One,
+    {
+        let mut counter = Counter::new();
+        counter.update_by_key(iterable, key);
+        counter
+    }
+
+    /// Count the values that `value` derives from the elements of the given iterable
+    /// separately for each of the groups that `key` puts them in
+    ///
+    /// [`from_groups`](#method.from_groups) flattens the groups back into one counter of
+    /// `(group, value)` pairs.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let events = vec![("alice", "login"), ("bob", "login"), ("alice", "upload")];
+    /// let per_user = Counter::<_>::group_counts(events, |&(user, _)| user, |(_, event)| event);
+    /// assert_eq!(per_user["alice"].len(), 2);
+    /// assert_eq!(per_user["bob"]["login"], 1);
+    /// ```
+    pub fn group_counts<I, K, F, G>(
+        iterable: I,
+        mut key: F,
+        mut value: G,
+    ) -> HashMap<K, Counter<T, N>>
+    where
+        I: IntoIterator,
+        K: Hash + Eq,
+        F: FnMut(&I::Item) -> K,
+        G: FnMut(I::Item) -> T,
+        N: AddAssign + One,
+    {
+        let mut groups = HashMap::new();
+        for element in iterable {
+            let group = groups.entry(key(&element)).or_insert_with(Counter::new);
+            *group.map.entry(value(element)).or_insert_with(N::zero) += N::one();
+        }
+        groups
+    }
+
+    /// Create a new `Counter` from `(item, count)` pairs, such as the counts of several
+    /// shards, summing the counts of duplicate items and dropping items whose total count
+    /// is zero
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = Counter::<_>::from_pairs(vec![("a", 2), ("b", 0), ("a", 1)]);
+    /// assert_eq!(counter[&"a"], 3);
+    /// assert!(!counter.contains_key(&"b"));
+    /// ```
+    pub fn from_pairs<I>(pairs: I) -> Counter<T, N>
+    where
+        I: IntoIterator<Item = (T, N)>,
+        N: AddAssign,
+    {
+        let mut counter = Counter::new();
+        counter.extend(pairs);
+        counter.map.retain(|_, count| !count.is_zero());
+        counter
+    }
+
+    /// Like [`from_pairs`](#method.from_pairs), but returns an error holding the item whose
+    /// count overflowed instead of overflowing.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = Counter::<_, u8>::try_from_pairs(vec![("a", 200), ("a", 50)]).unwrap();
+    /// assert_eq!(counter[&"a"], 250);
+    ///
+    /// let error = Counter::<_, u8>::try_from_pairs(vec![("a", 200), ("a", 100)]).unwrap_err();
+    /// assert_eq!(error.item(), &"a");
+    /// ```
+    pub fn try_from_pairs<I>(pairs: I) -> Result<Counter<T, N>, OverflowError<T>>
+    where
+        I: IntoIterator<Item = (T, N)>,
+        N: CheckedAdd,
+    {
+        let mut counter: Counter<T, N> = Counter::new();
+        for (item, count) in pairs {
+            match counter.map.get_mut(&item) {
+                Some(total) => match total.checked_add(&count) {
+                    Some(sum) => *total = sum,
+                    None => return Err(OverflowError::new(item)),
+                },
+                None => {
+                    counter.map.insert(item, count);
+                }
+            }
+        }
+        counter.map.retain(|_, count| !count.is_zero());
+        Ok(counter)
+    }
+}
+
+impl<'a, N> Counter<&'a str, N>
+where
+    N: PartialOrd + AddAssign + Zero + One,
+{
+    /// Create a new `Counter` of the character n-grams of `text`: each run of `n` consecutive
+    /// `char`s, as a slice of `text`
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let bigrams = Counter::<_>::char_ngrams("banana", 2);
+    /// assert_eq!(bigrams[&"an"], 2);
+    /// assert_eq!(bigrams[&"na"], 2);
+    /// assert_eq!(bigrams[&"ba"], 1);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero.
+    pub fn char_ngrams(text: &'a str, n: usize) -> Counter<&'a str, N> {
+        assert!(n > 0, "n-grams must have a length of at least one");
+        let boundaries = text
+            .char_indices()
+            .map(|(index, _)| index)
+            .chain(iter::once(text.len()))
+            .collect::<Vec<_>>();
+        Counter::init(
+            boundaries
+                .windows(n + 1)
+                .map(|window| &text[window[0]..window[n]]),
+        )
+    }
+}
+
+impl<T, N> Counter<Vec<T>, N>
+where
+    T: Hash + Eq + Clone,
+    N: PartialOrd + AddAssign + Zero + One,
+{
+    /// Create a new `Counter` of the n-grams of the given iterable, such as a sequence of words:
+    /// each run of `n` consecutive items, collected into a `Vec`
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let bigrams = Counter::<_>::word_ngrams("to be or not to be".split(' '), 2);
+    /// assert_eq!(bigrams[&vec!["to", "be"]], 2);
+    /// assert_eq!(bigrams[&vec!["be", "or"]], 1);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero.
+    pub fn word_ngrams<I>(iterable: I, n: usize) -> Counter<Vec<T>, N>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        assert!(n > 0, "n-grams must have a length of at least one");
+        let items = iterable.into_iter().collect::<Vec<_>>();
+        Counter::init(items.windows(n).map(<[T]>::to_vec))
+    }
+}
+
+impl<A, B, N> Counter<(A, B), N>
+where
+    A: Hash + Eq,
+    B: Hash + Eq,
+    N: PartialOrd + AddAssign + Zero + One,
+{
+    /// Create a new `Counter` of the pairs from the given iterable, such as a co-occurrence
+    /// table
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let text = "the cat saw the dog".split(' ').collect::<Vec<_>>();
+    /// let bigrams = Counter::<_>::pairs(text.windows(2).map(|pair| (pair[0], pair[1])));
+    /// assert_eq!(bigrams[&("the", "cat")], 1);
+    /// assert_eq!(bigrams.marginal_left()[&"the"], 2);
+    /// ```
+    pub fn pairs<I>(iterable: I) -> Counter<(A, B), N>
+    where
+        I: IntoIterator<Item = (A, B)>,
+    {
+        Counter::init(iterable)
+    }
+
+    /// Create a new `Counter` of `(group, item)` pairs from counters of the items in each
+    /// group, such as the map returned by [`group_counts`](#method.group_counts)
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let events = vec![("alice", "login"), ("bob", "login"), ("alice", "login")];
+    /// let per_user = Counter::<_>::group_counts(events, |&(user, _)| user, |(_, event)| event);
+    /// let pairs = Counter::<_>::from_groups(per_user);
+    /// assert_eq!(pairs[&("alice", "login")], 2);
+    /// ```
+    pub fn from_groups<I, S>(groups: I) -> Counter<(A, B), N>
+    where
+        I: IntoIterator<Item = (A, Counter<B, N, S>)>,
+        A: Clone,
+        S: BuildHasher,
+    {
+        let mut counter = Counter::new();
+        for (group, items) in groups {
+            counter.extend(
+                items
+                    .map
+                    .into_iter()
+                    .map(|(item, count)| ((group.clone(), item), count)),
+            );
+        }
+        counter
+    }
+}
+
+impl<A, B, N, S> Counter<(A, B), N, S>
+where
+    A: Hash + Eq,
+    B: Hash + Eq,
+    N: Clone + AddAssign + Zero,
+    S: BuildHasher + Clone,
+{
+    /// The counts of the first items of the pairs, summing over the second items
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let table = Counter::<_>::pairs(vec![("a", 1), ("a", 2), ("b", 1)]);
+    /// let left = table.marginal_left();
+    /// assert_eq!((left[&"a"], left[&"b"]), (2, 1));
+    /// ```
+    pub fn marginal_left(&self) -> Counter<A, N, S>
+    where
+        A: Clone,
+    {
+        let mut marginal = Counter::with_hasher(self.map.hasher().clone());
+        for ((a, _), count) in self.map.iter() {
+            *marginal.map.entry(a.clone()).or_insert_with(N::zero) += count.clone();
+        }
+        marginal
+    }
+
+    /// The counts of the second items of the pairs, summing over the first items
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let table = Counter::<_>::pairs(vec![("a", 1), ("a", 2), ("b", 1)]);
+    /// let right = table.marginal_right();
+    /// assert_eq!((right[&1], right[&2]), (2, 1));
+    /// ```
+    pub fn marginal_right(&self) -> Counter<B, N, S>
+    where
+        B: Clone,
+    {
+        let mut marginal = Counter::with_hasher(self.map.hasher().clone());
+        for ((_, b), count) in self.map.iter() {
+            *marginal.map.entry(b.clone()).or_insert_with(N::zero) += count.clone();
+        }
+        marginal
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Zero,
+    S: BuildHasher,
+{
+    /// Create a new, empty `Counter` which will use the given hasher to hash the items
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// use std::collections::hash_map::RandomState;
+    ///
+    /// let mut counter: Counter<_, usize, _> = Counter::with_hasher(RandomState::new());
+    /// counter.update("abbccc".chars());
+    /// assert_eq!(counter.get(&'c'), Some(&3));
+    /// ```
+    pub fn with_hasher(hash_builder: S) -> Counter<T, N, S> {
+        Counter {
+            map: HashMap::with_hasher(hash_builder),
+            zero: N::zero(),
+        }
+    }
+
+    /// Create a new, empty `Counter` with space for at least `capacity` items,
+    /// which will use the given hasher to hash the items
+    pub fn with_capacity_and_hasher(capacity: usize, hash_builder: S) -> Counter<T, N, S> {
+        Counter {
+            map: HashMap::with_capacity_and_hasher(capacity, hash_builder),
+            zero: N::zero(),
+        }
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    S: BuildHasher,
+{
+    /// Consumes this counter and returns a HashMap mapping the items to the counts.
+    pub fn into_map(self) -> HashMap<T, N, S> {
+        self.map
+    }
+
+    /// Returns the number of distinct items in the counter.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = "abbccc".chars().collect::<Counter<_>>();
+    /// assert_eq!(counter.len(), 3);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if the counter contains no items.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Removes all items from the counter, keeping the allocated memory for reuse.
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    /// Returns a reference to the count of the given item, if it is present.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = "abbccc".chars().collect::<Counter<_>>();
+    /// assert_eq!(counter.get(&'b'), Some(&2));
+    /// assert_eq!(counter.get(&'d'), None);
+    /// ```
+    pub fn get<Q>(&self, item: &Q) -> Option<&N>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.get(item)
+    }
+
+    /// Returns the count of the given item, or zero if it is not present.
+    ///
+    /// The item can be any borrowed form of the counter's item type, so a
+    /// `Counter<String>` can be queried with a `&str` without allocating.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = "a b a".split_whitespace().map(str::to_string).collect::<Counter<_>>();
+    /// assert_eq!(counter.count_of("a"), 2);
+    /// assert_eq!(counter.count_of("c"), 0);
+    /// ```
+    pub fn count_of<Q>(&self, item: &Q) -> N
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        N: Clone + Zero,
+    {
+        self.map.get(item).cloned().unwrap_or_else(N::zero)
+    }
+
+    /// Returns `true` if the counter contains the given item.
+    pub fn contains_key<Q>(&self, item: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.contains_key(item)
+    }
+
+    /// An iterator visiting all the items in arbitrary order.
+    pub fn keys(&self) -> hash_map::Keys<'_, T, N> {
+        self.map.keys()
+    }
+
+    /// An iterator visiting all the counts in arbitrary order.
+    pub fn values(&self) -> hash_map::Values<'_, T, N> {
+        self.map.values()
+    }
+
+    /// An iterator visiting all the `(item, count)` pairs in arbitrary order.
+    pub fn iter(&self) -> hash_map::Iter<'_, T, N> {
+        self.map.iter()
+    }
+
+    /// Removes an item from the counter, returning its count if it was present.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mut counter = "abbccc".chars().collect::<Counter<_>>();
+    /// assert_eq!(counter.remove(&'b'), Some(2));
+    /// assert_eq!(counter.remove(&'b'), None);
+    /// ```
+    pub fn remove<Q>(&mut self, item: &Q) -> Option<N>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.remove(item)
+    }
+
+    /// Sets the count of an item, returning its previous count if it was present.
+    ///
+    /// Setting a count of zero removes the item.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mut counter = "abbccc".chars().collect::<Counter<_>>();
+    /// assert_eq!(counter.set_count('a', 5), Some(1));
+    /// assert_eq!(counter.set_count('d', 2), None);
+    /// assert_eq!(counter.set_count('c', 0), Some(3));
+    /// assert_eq!(counter[&'a'], 5);
+    /// assert_eq!(counter[&'d'], 2);
+    /// assert!(!counter.contains_key(&'c'));
+    /// ```
+    pub fn set_count(&mut self, item: T, count: N) -> Option<N>
+    where
+        N: Zero,
+    {
+        if count.is_zero() {
+            self.map.remove(&item)
+        } else {
+            self.map.insert(item, count)
+        }
+    }
+
+    /// Retains only the items specified by the predicate.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// # #[cfg(not(feature = "std"))] extern crate hashbrown;
+    /// # #[cfg(not(feature = "std"))] use hashbrown::HashMap;
+    /// # #[cfg(feature = "std")] use std::collections::HashMap;
+    /// let mut counter = "abbccc".chars().collect::<Counter<_>>();
+    /// counter.retain(|_, &mut count| count >= 2);
+    /// let expect = [('b', 2), ('c', 3)].iter().cloned().collect::<HashMap<_, _>>();
+    /// assert_eq!(counter.into_map(), expect);
+    /// ```
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&T, &mut N) -> bool,
+    {
+        self.map.retain(f);
+    }
+
+    /// Clears the counter, returning all `(item, count)` pairs as an iterator.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mut counter = "abbccc".chars().collect::<Counter<_>>();
+    /// let mut drained = counter.drain().collect::<Vec<_>>();
+    /// drained.sort();
+    /// assert_eq!(drained, vec![('a', 1), ('b', 2), ('c', 3)]);
+    /// assert!(counter.is_empty());
+    /// ```
+    pub fn drain(&mut self) -> hash_map::Drain<'_, T, N> {
+        self.map.drain()
+    }
+
+    /// Creates an iterator which removes and yields the `(item, count)` pairs
+    /// for which the predicate returns `true`.
+    ///
+    /// Entries which are not yielded because the iterator is dropped early are kept.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// # #[cfg(not(feature = "std"))] extern crate hashbrown;
+    /// # #[cfg(not(feature = "std"))] use hashbrown::HashMap;
+    /// # #[cfg(feature = "std")] use std::collections::HashMap;
+    /// let mut counter = "abbccc".chars().collect::<Counter<_>>();
+    /// let mut rare = counter.extract_if(|_, count| *count < 2).collect::<Vec<_>>();
+    /// rare.sort();
+    /// assert_eq!(rare, vec![('a', 1)]);
+    /// let expect = [('b', 2), ('c', 3)].iter().cloned().collect::<HashMap<_, _>>();
+    /// assert_eq!(counter.into_map(), expect);
+    /// ```
+    pub fn extract_if<F>(&mut self, pred: F) -> hash_map::ExtractIf<'_, T, N, F>
+    where
+        F: FnMut(&T, &mut N) -> bool,
+    {
+        self.map.extract_if(pred)
+    }
+
+    /// Removes the items whose count is less than `min_count` and returns them as a new
+    /// counter, leaving the more frequent items in this one.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mut vocabulary = Counter::<_>::init("the cat the dog the end".split(' '));
+    /// let rare = vocabulary.split_off_below(2);
+    /// assert_eq!(vocabulary.len(), 1);
+    /// assert_eq!(rare.len(), 3);
+    /// assert_eq!(rare.total(), 3);
+    /// ```
+    pub fn split_off_below(&mut self, min_count: N) -> Counter<T, N, S>
+    where
+        N: PartialOrd + Zero,
+        S: Clone,
+    {
+        let mut rare = Counter::with_hasher(self.map.hasher().clone());
+        rare.map.extend(self.map.extract_if(|_, count| *count < min_count));
+        rare
+    }
+
+    /// Removes the items whose count is less than `min_count`, returning the sum of their
+    /// counts
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mut counter = "abbcccc".chars().collect::<Counter<_>>();
+    /// assert_eq!(counter.prune_below(3), 3);
+    /// assert_eq!(counter.total(), 4);
+    /// ```
+    pub fn prune_below(&mut self, min_count: N) -> N
+    where
+        N: PartialOrd + AddAssign + Zero,
+    {
+        let mut removed = N::zero();
+        for (_, count) in self.map.extract_if(|_, count| *count < min_count) {
+            removed += count;
+        }
+        removed
+    }
+
+    /// An iterator over the `(item, count)` pairs whose count is at least `min_count`
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = "abbccc".chars().collect::<Counter<_>>();
+    /// let mut frequent = counter.items_with_count_at_least(2).collect::<Vec<_>>();
+    /// frequent.sort();
+    /// assert_eq!(frequent, vec![(&'b', &2), (&'c', &3)]);
+    /// ```
+    pub fn items_with_count_at_least(&self, min_count: N) -> impl Iterator<Item = (&T, &N)> + '_
+    where
+        N: PartialOrd,
+    {
+        self.map.iter().filter(move |&(_, count)| *count >= min_count)
+    }
+
+    /// An iterator over the `(item, count)` pairs whose count is at most `max_count`
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = "abbccc".chars().collect::<Counter<_>>();
+    /// let rare = counter.items_with_count_at_most(1).collect::<Vec<_>>();
+    /// assert_eq!(rare, vec![(&'a', &1)]);
+    /// ```
+    pub fn items_with_count_at_most(&self, max_count: N) -> impl Iterator<Item = (&T, &N)> + '_
+    where
+        N: PartialOrd,
+    {
+        self.map.iter().filter(move |&(_, count)| *count <= max_count)
+    }
+
+    /// A new counter of the items whose count is at least `min_count`
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = "abbccc".chars().collect::<Counter<_>>();
+    /// let frequent = counter.at_least(2);
+    /// assert_eq!(frequent.len(), 2);
+    /// assert_eq!(frequent[&'c'], 3);
+    /// ```
+    pub fn at_least(&self, min_count: N) -> Counter<T, N, S>
+    where
+        T: Clone,
+        N: Clone + PartialOrd + Zero,
+        S: Clone,
+    {
+        let mut counter = Counter::with_hasher(self.map.hasher().clone());
+        counter.map.extend(
+            self.items_with_count_at_least(min_count)
+                .map(|(item, count)| (item.clone(), count.clone())),
+        );
+        counter
+    }
+
+    /// A new counter of the items whose count is at most `max_count`
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = "abbccc".chars().collect::<Counter<_>>();
+    /// let rare = counter.at_most(2);
+    /// assert_eq!(rare.len(), 2);
+    /// assert_eq!(rare[&'c'], 0);
+    /// ```
+    pub fn at_most(&self, max_count: N) -> Counter<T, N, S>
+    where
+        T: Clone,
+        N: Clone + PartialOrd + Zero,
+        S: Clone,
+    {
+        let mut counter = Counter::with_hasher(self.map.hasher().clone());
+        counter.map.extend(
+            self.items_with_count_at_most(max_count)
+                .map(|(item, count)| (item.clone(), count.clone())),
+        );
+        counter
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Clone + Zero,
+    S: BuildHasher,
+{
+    /// Sum all the counts.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = "abbccc".chars().collect::<Counter<_>>();
+    /// assert_eq!(counter.total(), 6);
+    /// ```
+    pub fn total(&self) -> N {
+        self.map
+            .values()
+            .fold(N::zero(), |total, count| total + count.clone())
+    }
+
+    /// Sum all the counts, returning `None` if the sum overflows.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter: Counter<_, u8> = "abbccc".chars().collect();
+    /// assert_eq!(counter.checked_total(), Some(6));
+    ///
+    /// let big: Counter<char, u8> = vec![('a', 200), ('b', 100)].into_iter().collect();
+    /// assert_eq!(big.checked_total(), None);
+    /// ```
+    pub fn checked_total(&self) -> Option<N>
+    where
+        N: CheckedAdd,
+    {
+        self.map
+            .values()
+            .try_fold(N::zero(), |total, count| total.checked_add(count))
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: PartialOrd + Zero,
+    S: BuildHasher,
+{
+    /// Returns `true` if every item's count in this counter is at most its count in `other`,
+    /// where a missing item has a count of zero.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let letters = "tareslm".chars().collect::<Counter<_>>();
+    /// assert!("smelt".chars().collect::<Counter<_>>().is_subset(&letters));
+    /// assert!(!"letters".chars().collect::<Counter<_>>().is_subset(&letters));
+    /// ```
+    pub fn is_subset(&self, other: &Counter<T, N, S>) -> bool {
+        self.map
+            .iter()
+            .all(|(item, count)| count <= other.map.get(item).unwrap_or(&other.zero))
+            && other
+                .map
+                .iter()
+                .all(|(item, count)| self.map.contains_key(item) || self.zero <= *count)
+    }
+
+    /// Returns `true` if every item's count in this counter is at least its count in `other`,
+    /// where a missing item has a count of zero.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let letters = "tareslm".chars().collect::<Counter<_>>();
+    /// assert!(letters.is_superset(&"smelt".chars().collect()));
+    /// ```
+    pub fn is_superset(&self, other: &Counter<T, N, S>) -> bool {
+        other.is_subset(self)
+    }
+
+    /// Returns `true` if no item has a nonzero count in both counters.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let vowels = "aeiou".chars().collect::<Counter<_>>();
+    /// assert!(vowels.is_disjoint(&"rhythm".chars().collect()));
+    /// assert!(!vowels.is_disjoint(&"rhyme".chars().collect()));
+    /// ```
+    pub fn is_disjoint(&self, other: &Counter<T, N, S>) -> bool {
+        let (smaller, larger) = if self.map.len() <= other.map.len() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        smaller.map.iter().all(|(item, count)| {
+            count.is_zero() || larger.map.get(item).unwrap_or(&larger.zero).is_zero()
+        })
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Clone + Hash + Eq,
+    N: Clone + PartialOrd + Sub<Output = N> + Zero,
+    S: BuildHasher + Clone,
+{
+    /// Create a counter of how much each item's count differs between this counter and
+    /// `other`, in either direction, leaving out the items whose counts are the same.
+    ///
+    /// `out = c.symmetric_difference(&d);` -> `out[x] == |c[x] - d[x]|` for all `x`
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// # #[cfg(not(feature = "std"))] extern crate hashbrown;
+    /// # #[cfg(not(feature = "std"))] use hashbrown::HashMap;
+    /// # #[cfg(feature = "std")] use std::collections::HashMap;
+    /// let c = "aaab".chars().collect::<Counter<_>>();
+    /// let d = "abbc".chars().collect::<Counter<_>>();
+    ///
+    /// let e = c.symmetric_difference(&d);
+    ///
+    /// let expect = [('a', 2), ('b', 1), ('c', 1)].iter().cloned().collect::<HashMap<_, _>>();
+    /// assert_eq!(e.into_map(), expect);
+    /// ```
+    pub fn symmetric_difference(&self, other: &Counter<T, N, S>) -> Counter<T, N, S> {
+        fn abs_diff<N>(a: &N, b: &N) -> N
+        where
+            N: Clone + PartialOrd + Sub<Output = N>,
+        {
+            if a >= b {
+                a.clone() - b.clone()
+            } else {
+                b.clone() - a.clone()
+            }
+        }
+
+        let mut counter = Counter::with_hasher(self.map.hasher().clone());
+        for (item, count) in self.map.iter() {
+            let difference = abs_diff(count, other.map.get(item).unwrap_or(&other.zero));
+            if !difference.is_zero() {
+                counter.map.insert(item.clone(), difference);
+            }
+        }
+        for (item, count) in other.map.iter() {
+            if !self.map.contains_key(item) && !count.is_zero() {
+                counter.map.insert(item.clone(), abs_diff(count, &self.zero));
+            }
+        }
+        counter
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Clone + Hash + Eq,
+    N: Zero,
+    S: BuildHasher + Clone,
+{
+    /// Create a counter which combines the counts of each item in this counter and `other`
+    /// with `combine`, leaving out the items whose combined count is zero
+    ///
+    /// `combine` is called once for every item in either counter, with a count of zero for
+    /// the counter the item is missing from.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let morning: Counter<_, f64> = vec![("tea", 4.0), ("coffee", 2.0)].into_iter().collect();
+    /// let evening: Counter<_, f64> = vec![("tea", 2.0), ("cocoa", 1.0)].into_iter().collect();
+    ///
+    /// let average = morning.merge_with(&evening, |a, b| (a + b) / 2.0);
+    /// assert_eq!(average[&"tea"], 3.0);
+    /// assert_eq!(average[&"coffee"], 1.0);
+    /// assert_eq!(average[&"cocoa"], 0.5);
+    /// ```
+    pub fn merge_with<F>(&self, other: &Counter<T, N, S>, mut combine: F) -> Counter<T, N, S>
+    where
+        F: FnMut(&N, &N) -> N,
+    {
+        let mut counter = Counter::with_hasher(self.map.hasher().clone());
+        for (item, count) in self.map.iter() {
+            let combined = combine(count, other.map.get(item).unwrap_or(&other.zero));
+            if !combined.is_zero() {
+                counter.map.insert(item.clone(), combined);
+            }
+        }
+        for (item, count) in other.map.iter() {
+            if !self.map.contains_key(item) {
+                let combined = combine(&self.zero, count);
+                if !combined.is_zero() {
+                    counter.map.insert(item.clone(), combined);
+                }
+            }
+        }
+        counter
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Clone + Ord + Zero,
+    S: BuildHasher + Default,
+{
+    /// The union of many counters, such as the counters of many shards: the largest count of
+    /// each item in any of them
+    ///
+    /// This folds the counters into the largest one seen so far, so only the entries of the
+    /// smaller counters are moved and none are cloned. It returns an empty counter if there are
+    /// no counters.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// # #[cfg(not(feature = "std"))] extern crate hashbrown;
+    /// # #[cfg(not(feature = "std"))] use hashbrown::HashMap;
+    /// # #[cfg(feature = "std")] use std::collections::HashMap;
+    /// let shards = vec!["aab", "abbb", "c"]
+    ///     .into_iter()
+    ///     .map(|s| s.chars().collect::<Counter<_>>());
+    /// let union = Counter::union_all(shards);
+    /// let expect = [('a', 2), ('b', 3), ('c', 1)].iter().cloned().collect::<HashMap<_, _>>();
+    /// assert_eq!(union.into_map(), expect);
+    /// ```
+    pub fn union_all<I>(counters: I) -> Counter<T, N, S>
+    where
+        I: IntoIterator<Item = Counter<T, N, S>>,
+    {
+        let mut union = Counter::default();
+        for mut counter in counters {
+            if counter.map.len() > union.map.len() {
+                mem::swap(&mut union, &mut counter);
+            }
+            union |= counter;
+        }
+        union
+    }
+
+    /// The intersection of many counters: the smallest count of each item which is in all of
+    /// them
+    ///
+    /// This folds the counters into the smallest one seen so far, and stops looking at them
+    /// once the intersection is empty. It returns an empty counter if there are no counters.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// # #[cfg(not(feature = "std"))] extern crate hashbrown;
+    /// # #[cfg(not(feature = "std"))] use hashbrown::HashMap;
+    /// # #[cfg(feature = "std")] use std::collections::HashMap;
+    /// let shards = vec!["aabc", "abbb", "ab"]
+    ///     .into_iter()
+    ///     .map(|s| s.chars().collect::<Counter<_>>());
+    /// let intersection = Counter::intersection_all(shards);
+    /// let expect = [('a', 1), ('b', 1)].iter().cloned().collect::<HashMap<_, _>>();
+    /// assert_eq!(intersection.into_map(), expect);
+    /// ```
+    pub fn intersection_all<I>(counters: I) -> Counter<T, N, S>
+    where
+        I: IntoIterator<Item = Counter<T, N, S>>,
+    {
+        let mut counters = counters.into_iter();
+        let mut intersection = match counters.next() {
+            Some(counter) => counter,
+            None => return Counter::default(),
+        };
+        for mut counter in counters {
+            if intersection.map.is_empty() {
+                break;
+            }
+            if counter.map.len() < intersection.map.len() {
+                mem::swap(&mut intersection, &mut counter);
+            }
+            intersection &= counter;
+        }
+        intersection
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: AddAssign + Zero,
+    S: BuildHasher + Clone,
+{
+    /// Consumes this counter and returns one whose items are transformed by `f`, summing the
+    /// counts of the items which `f` maps to the same one.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let words = Counter::<_>::init(vec!["The", "the", "cat", "THE"]);
+    /// let lowercase = words.map_keys(str::to_lowercase);
+    /// assert_eq!(lowercase["the"], 3);
+    /// assert_eq!(lowercase["cat"], 1);
+    /// ```
+    pub fn map_keys<U, F>(self, mut f: F) -> Counter<U, N, S>
+    where
+        U: Hash + Eq,
+        F: FnMut(T) -> U,
+    {
+        let mut counter =
+            Counter::with_capacity_and_hasher(self.map.len(), self.map.hasher().clone());
+        counter.extend(self.map.into_iter().map(|(item, count)| (f(item), count)));
+        counter
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq + ToPrimitive,
+    N: AddAssign + Zero,
+    S: BuildHasher + Clone,
+{
+    /// Consumes this counter of numbers and returns the counts of the histogram bins they fall
+    /// in, which are delimited by `edges` in ascending order.
+    ///
+    /// Bin `i` holds the items from `edges[i - 1]` up to but not including `edges[i]`, so bin
+    /// `0` holds everything below the first edge and bin `edges.len()` everything from the last
+    /// edge up. Items which can't be converted to a number, or are NaN, are left out. For
+    /// other kinds of bins, use [`map_keys`](#method.map_keys).
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let ages = Counter::<u8>::init(vec![3, 15, 18, 24, 40, 67, 70]);
+    /// let bins = ages.bucketize(&[18.0, 65.0]);
+    /// assert_eq!((bins[&0], bins[&1], bins[&2]), (2, 3, 2));
+    /// ```
+    pub fn bucketize(self, edges: &[f64]) -> Counter<usize, N, S> {
+        let mut counter = Counter::with_hasher(self.map.hasher().clone());
+        for (item, count) in self.map {
+            let value = match item.to_f64() {
+                Some(value) if !value.is_nan() => value,
+                _ => continue,
+            };
+            let bin = edges.partition_point(|&edge| edge <= value);
+            *counter.map.entry(bin).or_insert_with(N::zero) += count;
+        }
+        counter
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Zero,
+    S: BuildHasher,
+{
+    /// Replace every count with the result of `f`, removing the items whose count becomes
+    /// zero
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// # #[cfg(not(feature = "std"))] extern crate hashbrown;
+    /// # #[cfg(not(feature = "std"))] use hashbrown::HashMap;
+    /// # #[cfg(feature = "std")] use std::collections::HashMap;
+    /// let mut counter = "abbcccccc".chars().collect::<Counter<_>>();
+    /// counter.map_counts(|count| count.min(4) / 2);
+    /// let expect = [('b', 1), ('c', 2)].iter().cloned().collect::<HashMap<_, _>>();
+    /// assert_eq!(counter.into_map(), expect);
+    /// ```
+    pub fn map_counts<F>(&mut self, mut f: F)
+    where
+        F: FnMut(N) -> N,
+    {
+        self.map.retain(|_, count| {
+            *count = f(mem::replace(count, N::zero()));
+            !count.is_zero()
+        });
+    }
+
+    /// Consumes this counter and returns one whose counts are the results of `f`, which may
+    /// be of a different type, leaving out the items whose new count is zero
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = "abbbbbbbb".chars().collect::<Counter<_>>();
+    /// let scaled = counter.map_counts_into(|count| (count as f64).log2());
+    /// assert_eq!(scaled[&'b'], 3.0);
+    /// assert!(!scaled.contains_key(&'a'));
+    /// ```
+    pub fn map_counts_into<M, F>(self, mut f: F) -> Counter<T, M, S>
+    where
+        M: Zero,
+        F: FnMut(N) -> M,
+        S: Clone,
+    {
+        let mut counter =
+            Counter::with_capacity_and_hasher(self.map.len(), self.map.hasher().clone());
+        counter.map.extend(
+            self.map
+                .into_iter()
+                .map(|(item, count)| (item, f(count)))
+                .filter(|(_, count)| !count.is_zero()),
+        );
+        counter
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: ToPrimitive,
+    S: BuildHasher,
+{
+    fn total_f64(&self) -> f64 {
+        self.map.values().filter_map(N::to_f64).sum()
+    }
+
+    /// The probability of each item: its count divided by the total of the counts
+    ///
+    /// Counts which can't be represented as an `f64` are treated as zero; if the total is
+    /// zero, the probabilities are not finite.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = "abbb".chars().collect::<Counter<_>>();
+    /// let probabilities = counter.probabilities();
+    /// assert_eq!(probabilities[&'a'], 0.25);
+    /// assert_eq!(probabilities[&'b'], 0.75);
+    /// ```
+    pub fn probabilities(&self) -> HashMap<&T, f64, S>
+    where
+        S: Clone,
+    {
+        let total = self.total_f64();
+        let mut probabilities =
+            HashMap::with_capacity_and_hasher(self.map.len(), self.map.hasher().clone());
+        probabilities.extend(
+            self.map
+                .iter()
+                .map(|(item, count)| (item, count.to_f64().unwrap_or(0.0) / total)),
+        );
+        probabilities
+    }
+
+    /// Consumes this counter and returns the probability of each item, like
+    /// [`probabilities`](#method.probabilities)
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = "abbb".chars().collect::<Counter<_>>();
+    /// assert_eq!(counter.into_probabilities()[&'b'], 0.75);
+    /// ```
+    pub fn into_probabilities(self) -> HashMap<T, f64, S>
+    where
+        S: Clone,
+    {
+        let total = self.total_f64();
+        let mut probabilities =
+            HashMap::with_capacity_and_hasher(self.map.len(), self.map.hasher().clone());
+        probabilities.extend(
+            self.map
+                .into_iter()
+                .map(|(item, count)| (item, count.to_f64().unwrap_or(0.0) / total)),
+        );
+        probabilities
+    }
+
+    /// The add-`k` smoothed probability of the given item: its count plus `k`, divided by the
+    /// total plus `k` for each of the `vocab_size` possible items
+    ///
+    /// Unlike [`probabilities`](#method.probabilities), this gives items which were never
+    /// counted a probability too. With `k` set to one, this is Laplace smoothing.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = "aab".chars().collect::<Counter<_>>();
+    /// // 26 letters: a, b, and 24 which weren't seen
+    /// assert_eq!(counter.smoothed_prob(&'a', 1.0, 26), 3.0 / 29.0);
+    /// assert_eq!(counter.smoothed_prob(&'z', 1.0, 26), 1.0 / 29.0);
+    /// ```
+    pub fn smoothed_prob<Q>(&self, item: &Q, k: f64, vocab_size: usize) -> f64
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let count = self.map.get(item).and_then(N::to_f64).unwrap_or(0.0);
+        (count + k) / (self.total_f64() + k * vocab_size as f64)
+    }
+
+    /// The add-`k` smoothed probability of each counted item, like
+    /// [`smoothed_prob`](#method.smoothed_prob)
+    ///
+    /// Each of the `vocab_size - self.len()` items which weren't counted has the probability
+    /// `k / (total + k * vocab_size)`.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = "aab".chars().collect::<Counter<_>>();
+    /// let smoothed = counter.smoothed_probabilities(1.0, 4);
+    /// assert_eq!(smoothed[&'a'], 3.0 / 7.0);
+    /// assert_eq!(smoothed[&'b'], 2.0 / 7.0);
+    /// ```
+    pub fn smoothed_probabilities(&self, k: f64, vocab_size: usize) -> HashMap<&T, f64, S>
+    where
+        S: Clone,
+    {
+        let denominator = self.total_f64() + k * vocab_size as f64;
+        let mut probabilities =
+            HashMap::with_capacity_and_hasher(self.map.len(), self.map.hasher().clone());
+        probabilities.extend(self.map.iter().map(|(item, count)| {
+            (item, (count.to_f64().unwrap_or(0.0) + k) / denominator)
+        }));
+        probabilities
+    }
+
+    /// Estimate the probability of each item, and of the items which were never counted,
+    /// with the Good-Turing estimator
+    ///
+    /// An item counted `r` times gets the adjusted count `(r + 1) * n(r + 1) / n(r)`, where
+    /// `n(r)` is the number of items counted `r` times, and the unseen items share the
+    /// probability `n(1) / total`. When no item was counted `r + 1` times, the count `r` is
+    /// kept instead. The probabilities of the counted items are then scaled so that they add
+    /// up to one, together with the unseen probability.
+    ///
+    /// Only positive whole counts are used.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = "abcdeeff".chars().collect::<Counter<_>>();
+    /// let estimate = counter.good_turing();
+    /// // four of the eight characters were only seen once
+    /// assert_eq!(estimate.unseen_probability, 0.5);
+    /// assert!(estimate.probabilities[&'e'] > estimate.probabilities[&'a']);
+    /// ```
+    pub fn good_turing(&self) -> GoodTuring<'_, T, S>
+    where
+        S: Clone,
+    {
+        let counts = self
+            .map
+            .iter()
+            .filter_map(|(item, count)| count.to_u64().map(|count| (item, count)))
+            .filter(|&(_, count)| count > 0)
+            .collect::<Vec<_>>();
+        let mut count_of_counts = HashMap::<u64, u64>::new();
+        for &(_, count) in &counts {
+            *count_of_counts.entry(count).or_insert(0) += 1;
+        }
+        let total = counts.iter().map(|&(_, count)| count as f64).sum::<f64>();
+        let unseen_probability = if total > 0.0 {
+            *count_of_counts.get(&1).unwrap_or(&0) as f64 / total
+        } else {
+            0.0
+        };
+
+        let mut probabilities =
+            HashMap::with_capacity_and_hasher(counts.len(), self.map.hasher().clone());
+        probabilities.extend(counts.iter().map(|&(item, count)| {
+            let adjusted = match count_of_counts.get(&(count + 1)) {
+                Some(&next) => (count + 1) as f64 * next as f64 / count_of_counts[&count] as f64,
+                None => count as f64,
+            };
+            (item, adjusted)
+        }));
+        let adjusted_total = probabilities.values().sum::<f64>();
+        for probability in probabilities.values_mut() {
+            *probability *= (1.0 - unseen_probability) / adjusted_total;
+        }
+
+        GoodTuring {
+            probabilities,
+            unseen_probability,
+        }
+    }
+
+    fn positive_counts(&self) -> impl Iterator<Item = f64> + '_ {
+        self.map
+            .values()
+            .filter_map(N::to_f64)
+            .filter(|&count| count > 0.0)
+    }
+
+    /// The Shannon entropy of the items' probabilities, in bits
+    ///
+    /// Counts which aren't positive are left out. This method requires the `std` feature.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = "aabc".chars().collect::<Counter<_>>();
+    /// assert_eq!(counter.entropy(), 1.5);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn entropy(&self) -> f64 {
+        self.entropy_nats() / core::f64::consts::LN_2
+    }
+
+    /// The Shannon entropy of the items' probabilities, in nats, using the natural logarithm
+    ///
+    /// Counts which aren't positive are left out. This method requires the `std` feature.
+    #[cfg(feature = "std")]
+    pub fn entropy_nats(&self) -> f64 {
+        let total = self.positive_counts().sum::<f64>();
+        -self
+            .positive_counts()
+            .map(|count| {
+                let probability = count / total;
+                probability * probability.ln()
+            })
+            .sum::<f64>()
+    }
+
+    /// The Gini impurity of the items' probabilities: the chance that two items picked at
+    /// random, with replacement, are different
+    ///
+    /// Counts which aren't positive are left out. An empty counter has an impurity of zero.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let pure = "aaaa".chars().collect::<Counter<_>>();
+    /// assert_eq!(pure.gini_impurity(), 0.0);
+    /// let mixed = "aabb".chars().collect::<Counter<_>>();
+    /// assert_eq!(mixed.gini_impurity(), 0.5);
+    /// ```
+    pub fn gini_impurity(&self) -> f64 {
+        let total = self.positive_counts().sum::<f64>();
+        if total == 0.0 {
+            return 0.0;
+        }
+        1.0 - self
+            .positive_counts()
+            .map(|count| (count / total) * (count / total))
+            .sum::<f64>()
+    }
+
+    fn positive_count_of(&self, item: &T) -> f64 {
+        match self.map.get(item).and_then(N::to_f64) {
+            Some(count) if count > 0.0 => count,
+            _ => 0.0,
+        }
+    }
+
+    /// The Kullback-Leibler divergence of this counter's distribution from `other`'s, in bits
+    ///
+    /// This measures how much information is lost when `other`'s probabilities are used in
+    /// place of this counter's. It isn't symmetric, and is infinite when an item that was
+    /// counted here has a probability of zero in `other`, unless `zeros` says otherwise.
+    /// Counts which aren't positive are left out. This method requires the `std` feature.
+    ///
+    /// ```rust
+    /// # use counter::{Counter, ZeroProbability};
+    /// let p = "aabb".chars().collect::<Counter<_>>();
+    /// let q = "abbb".chars().collect::<Counter<_>>();
+    /// let divergence = p.kl_divergence(&q, ZeroProbability::Infinite);
+    /// assert!((divergence - 0.207_518_749).abs() < 1e-9);
+    ///
+    /// let r = "aaaa".chars().collect::<Counter<_>>();
+    /// assert_eq!(p.kl_divergence(&r, ZeroProbability::Infinite), std::f64::INFINITY);
+    /// assert_eq!(p.kl_divergence(&r, ZeroProbability::Skip), -0.5);
+    /// assert!(p.kl_divergence(&r, ZeroProbability::Smooth(1.0)).is_finite());
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn kl_divergence(&self, other: &Counter<T, N, S>, zeros: ZeroProbability) -> f64 {
+        let total = self.positive_counts().sum::<f64>();
+        let other_total = other.positive_counts().sum::<f64>();
+        let counted = self.map.keys().filter(|item| self.positive_count_of(item) > 0.0);
+
+        match zeros {
+            ZeroProbability::Smooth(k) => {
+                let other_only = other
+                    .map
+                    .keys()
+                    .filter(|item| self.positive_count_of(item) == 0.0)
+                    .map(|item| other.positive_count_of(item))
+                    .filter(|&count| count > 0.0)
+                    .collect::<Vec<_>>();
+                let vocab_size = (self.positive_counts().count() + other_only.len()) as f64;
+                let denominator = total + k * vocab_size;
+                let other_denominator = other_total + k * vocab_size;
+                let term = |count: f64, other_count: f64| {
+                    let p = (count + k) / denominator;
+                    p * (p / ((other_count + k) / other_denominator)).log2()
+                };
+                counted
+                    .map(|item| term(self.positive_count_of(item), other.positive_count_of(item)))
+                    .chain(other_only.into_iter().map(|count| term(0.0, count)))
+                    .sum()
+            }
+            ZeroProbability::Infinite | ZeroProbability::Skip => {
+                let mut divergence = 0.0;
+                for item in counted {
+                    let p = self.positive_count_of(item) / total;
+                    let q = other.positive_count_of(item) / other_total;
+                    if q > 0.0 {
+                        divergence += p * (p / q).log2();
+                    } else if zeros == ZeroProbability::Infinite {
+                        return f64::INFINITY;
+                    }
+                }
+                divergence
+            }
+        }
+    }
+
+    /// The Jensen-Shannon divergence between this counter's distribution and `other`'s, in
+    /// bits
+    ///
+    /// Unlike the [KL divergence](#method.kl_divergence), this is symmetric and always finite:
+    /// it is between zero, for the same distribution, and one, for distributions with no items
+    /// in common. Counts which aren't positive are left out. A counter with no positive counts
+    /// is treated as having nothing in common with any other, so the divergence between two
+    /// such counters is zero, and between one of them and any other counter one. This method
+    /// requires the `std` feature.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let p = "aabb".chars().collect::<Counter<_>>();
+    /// let q = "cd".chars().collect::<Counter<_>>();
+    /// assert_eq!(p.js_divergence(&p), 0.0);
+    /// assert_eq!(p.js_divergence(&q), 1.0);
+    /// assert_eq!(p.js_divergence(&Counter::new()), 1.0);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn js_divergence(&self, other: &Counter<T, N, S>) -> f64 {
+        let total = self.positive_counts().sum::<f64>();
+        let other_total = other.positive_counts().sum::<f64>();
+        match (total > 0.0, other_total > 0.0) {
+            (false, false) => return 0.0,
+            (true, true) => {}
+            _ => return 1.0,
+        }
+        // the term of one distribution's divergence from the mixture, where its probability
+        // is `p` and the other's is `q`
+        let term = |p: f64, q: f64| {
+            if p > 0.0 {
+                0.5 * p * (2.0 * p / (p + q)).log2()
+            } else {
+                0.0
+            }
+        };
+
+        let mut divergence = 0.0;
+        for item in self.map.keys() {
+            let p = self.positive_count_of(item) / total;
+            let q = other.positive_count_of(item) / other_total;
+            divergence += term(p, q) + term(q, p);
+        }
+        for item in other.map.keys().filter(|item| !self.map.contains_key(item)) {
+            divergence += term(other.positive_count_of(item) / other_total, 0.0);
+        }
+        divergence
+    }
+
+    /// The Jensen-Shannon distance between this counter's distribution and `other`'s: the
+    /// square root of the [Jensen-Shannon divergence](#method.js_divergence), which is a metric
+    ///
+    /// This method requires the `std` feature.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let p = "ab".chars().collect::<Counter<_>>();
+    /// let q = "bc".chars().collect::<Counter<_>>();
+    /// assert!((p.js_distance(&q) - 0.5f64.sqrt()).abs() < 1e-12);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn js_distance(&self, other: &Counter<T, N, S>) -> f64 {
+        self.js_divergence(other).sqrt()
+    }
+}
+
+/// How [`Counter::kl_divergence`](struct.Counter.html#method.kl_divergence) handles items
+/// which have a probability of zero in the other counter
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ZeroProbability {
+    /// The divergence is infinite, as it is mathematically
+    Infinite,
+    /// Leave the items out, so the divergence stays finite but may be negative
+    Skip,
+    /// Add `k` to the count of every item counted in either counter, as with
+    /// [`smoothed_prob`](struct.Counter.html#method.smoothed_prob)
+    Smooth(f64),
+}
+
+/// Good-Turing estimates of the probabilities of the items of a counter.
+///
+/// This `struct` is created by the [`good_turing`](struct.Counter.html#method.good_turing)
+/// method on [`Counter`](struct.Counter.html).
+#[derive(Clone, Debug)]
+pub struct GoodTuring<'a, T: 'a, S = RandomState> {
+    /// The estimated probability of each counted item
+    pub probabilities: HashMap<&'a T, f64, S>,
+    /// The estimated total probability of all the items which weren't counted
+    pub unseen_probability: f64,
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Clone + Hash + Eq + Zero,
+    S: BuildHasher,
+{
+    /// Count how many items have each count, leaving out items whose count is zero
+    ///
+    /// This is also known as the frequency of frequencies. For example, the number of hapax
+    /// legomena, the words which occur only once in a text, is the count of `1`.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let words = Counter::<_>::init("a rose is a rose is a rose named rosa".split(' '));
+    /// let count_of_counts = words.count_of_counts();
+    /// assert_eq!(count_of_counts[&3], 2); // "a" and "rose"
+    /// assert_eq!(count_of_counts[&2], 1); // "is"
+    /// assert_eq!(count_of_counts[&1], 2); // the hapaxes "named" and "rosa"
+    /// ```
+    pub fn count_of_counts(&self) -> Counter<N> {
+        let mut count_of_counts = Counter::new();
+        count_of_counts.update(self.map.values().filter(|count| !count.is_zero()).cloned());
+        count_of_counts
+    }
+
+    /// The same as [`count_of_counts`](#method.count_of_counts), under the name used for it
+    /// in the literature on smoothing.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let words = Counter::<_>::init("a rose is a rose".split(' '));
+    /// assert_eq!(words.frequency_of_frequencies(), words.count_of_counts());
+    /// ```
+    pub fn frequency_of_frequencies(&self) -> Counter<N> {
+        self.count_of_counts()
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Clone + Hash + Eq + Add<Output = T>,
+    N: Clone + Zero + AddAssign + Mul<Output = N>,
+    S: BuildHasher + Clone,
+{
+    /// The convolution of this counter with `other`: the count of each sum `x + y` of an item
+    /// of this counter and an item of `other` is the sum of the products `self[x] * other[y]`.
+    ///
+    /// If the counters are the distributions of two independent random variables `X` and `Y`,
+    /// this is the distribution of `X + Y`. Items whose count is zero are left out. This takes
+    /// `O(self.len() * other.len())` time.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let die = Counter::<u32>::init(1..=6);
+    /// let two_dice = die.convolve(&die);
+    /// assert_eq!(two_dice.len(), 11);
+    /// assert_eq!(two_dice[&7], 6);
+    /// assert_eq!(two_dice[&12], 1);
+    /// assert_eq!(two_dice.total(), 36);
+    /// ```
+    pub fn convolve(&self, other: &Counter<T, N, S>) -> Counter<T, N, S> {
+        let mut sums = Counter::with_hasher(self.map.hasher().clone());
+        for (x, x_count) in self.map.iter() {
+            for (y, y_count) in other.map.iter() {
+                let product = x_count.clone() * y_count.clone();
+                if !product.is_zero() {
+                    *sums.map.entry(x.clone() + y.clone()).or_insert_with(N::zero) += product;
+                }
+            }
+        }
+        sums
+    }
+}
+
+/// How [`Counter::div_counts`](struct.Counter.html#method.div_counts) rounds each quotient
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Rounding {
+    /// Round down
+    Floor,
+    /// Round to the nearest integer, with halves rounded up
+    Round,
+    /// Round up
+    Ceil,
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Clone + PartialOrd + Unsigned,
+    S: BuildHasher,
+{
+    /// Divide every count by `divisor`, rounding as given, and remove the items whose count
+    /// becomes zero.
+    ///
+    /// ```rust
+    /// # use counter::{Counter, Rounding};
+    /// # #[cfg(not(feature = "std"))] extern crate hashbrown;
+    /// # #[cfg(not(feature = "std"))] use hashbrown::HashMap;
+    /// # #[cfg(feature = "std")] use std::collections::HashMap;
+    /// let mut counter = Counter::<_>::from_pairs(vec![('a', 10), ('b', 5), ('c', 1)]);
+    /// counter.div_counts(4, Rounding::Round);
+    /// let expect = [('a', 3), ('b', 1)].iter().cloned().collect::<HashMap<_, _>>();
+    /// assert_eq!(counter.into_map(), expect);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `divisor` is zero.
+    pub fn div_counts(&mut self, divisor: N, rounding: Rounding) {
+        assert!(!divisor.is_zero(), "cannot divide counts by zero");
+        self.map.retain(|_, count| {
+            let remainder = count.clone() % divisor.clone();
+            let mut quotient = count.clone() / divisor.clone();
+            let round_up = match rounding {
+                Rounding::Floor => false,
+                Rounding::Round => remainder.clone() >= divisor.clone() - remainder,
+                Rounding::Ceil => !remainder.is_zero(),
+            };
+            if round_up {
+                quotient = quotient + N::one();
+            }
+            *count = quotient;
+            !count.is_zero()
+        });
+    }
+}
+
+impl<T, N, S> Default for Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Zero,
+    S: BuildHasher + Default,
+{
+    fn default() -> Counter<T, N, S> {
+        Counter::with_hasher(S::default())
+    }
+}
+
+impl<T, N, S> PartialEq for Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: PartialEq,
+    S: BuildHasher,
+{
+    /// Two counters are equal if every item has the same count in both, where an item which
+    /// is missing from one counter has a count of zero there.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mut counter = Counter::<_>::init("aab".chars());
+    /// counter.insert('c', 0);
+    /// assert_eq!(counter, Counter::init("aba".chars()));
+    /// assert_ne!(counter, Counter::init("ab".chars()));
+    /// ```
+    fn eq(&self, other: &Counter<T, N, S>) -> bool {
+        self.map
+            .iter()
+            .all(|(item, count)| other.map.get(item).unwrap_or(&other.zero) == count)
+            && other
+                .map
+                .iter()
+                .all(|(item, count)| self.map.contains_key(item) || *count == self.zero)
+    }
+}
+
+impl<T, N, S> Eq for Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Eq,
+    S: BuildHasher,
+{
+}
+
+impl<T, N, S> PartialOrd for Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: PartialOrd + Zero,
+    S: BuildHasher,
+{
+    /// Compare counters by multiset inclusion, like Python's `Counter`: `a <= b` if
+    /// [`a.is_subset(&b)`](struct.Counter.html#method.is_subset). Counters where neither
+    /// includes the other can't be compared.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let a = "ab".chars().collect::<Counter<_>>();
+    /// let b = "abb".chars().collect::<Counter<_>>();
+    /// let c = "bc".chars().collect::<Counter<_>>();
+    /// assert!(a < b);
+    /// assert!(b >= a);
+    /// assert_eq!(a.partial_cmp(&c), None);
+    /// ```
+    fn partial_cmp(&self, other: &Counter<T, N, S>) -> Option<Ordering> {
+        match (self.is_subset(other), other.is_subset(self)) {
+            (true, true) => Some(Ordering::Equal),
+            (true, false) => Some(Ordering::Less),
+            (false, true) => Some(Ordering::Greater),
+            (false, false) => None,
+        }
+    }
+
+    fn le(&self, other: &Counter<T, N, S>) -> bool {
+        self.is_subset(other)
+    }
+
+    fn ge(&self, other: &Counter<T, N, S>) -> bool {
+        self.is_superset(other)
+    }
+}
+
+impl<T, N, S> Hash for Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Hash + PartialEq,
+    S: BuildHasher,
+{
+    /// Hash the counter consistently with `PartialEq`: the result doesn't depend on the order
+    /// of the underlying map or its hasher, and items with a count of zero are skipped.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// # use std::collections::HashSet;
+    /// let signatures = ["listen", "silent", "enlist", "banana"]
+    ///     .iter()
+    ///     .map(|word| word.chars().collect::<Counter<_>>())
+    ///     .collect::<HashSet<_>>();
+    /// assert_eq!(signatures.len(), 2);
+    /// ```
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let mut len = 0usize;
+        let mut sum = 0u64;
+        for (item, count) in self.map.iter().filter(|&(_, count)| *count != self.zero) {
+            let mut hasher = EntryHasher::new();
+            item.hash(&mut hasher);
+            count.hash(&mut hasher);
+            sum = sum.wrapping_add(hasher.finish());
+            len += 1;
+        }
+        state.write_usize(len);
+        state.write_u64(sum);
+    }
+}
+
+/// A fixed-key FNV-1a hasher for the entries of a counter, so that equal counters hash the
+/// same even when their maps use different random keys
+struct EntryHasher(u64);
+
+impl EntryHasher {
+    fn new() -> EntryHasher {
+        EntryHasher(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for EntryHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+impl<T, N, S> fmt::Display for Counter<T, N, S>
+where
+    T: Hash + Eq + fmt::Debug,
+    N: Ord + fmt::Display,
+    S: BuildHasher,
+{
+    /// Format the counter like Python's `Counter`, from the most common item to the least.
+    ///
+    /// Items are formatted with `Debug`; items with the same count are sorted by how they
+    /// are formatted, so the output doesn't depend on the order of the underlying map.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = "abbccc".chars().collect::<Counter<_>>();
+    /// assert_eq!(counter.to_string(), "Counter({'c': 3, 'b': 2, 'a': 1})");
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use core::cmp::Reverse;
+
+        let mut items = self
+            .map
+            .iter()
+            .map(|(item, count)| (format!("{:?}", item), count))
+            .collect::<Vec<_>>();
+        items.sort_by(|(a_item, a_count), (b_item, b_count)| {
+            (Reverse(a_count), a_item).cmp(&(Reverse(b_count), b_item))
+        });
+
+        f.write_str("Counter({")?;
+        for (i, (item, count)) in items.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{}: {}", item, count)?;
+        }
+        f.write_str("})")
+    }
+}
+
+impl<T, N, S> fmt::Debug for Counter<T, N, S>
+where
+    T: Hash + Eq + fmt::Debug,
+    N: PartialOrd + fmt::Debug,
+{
+    /// Format the counter as a map from items to counts, from the most common item to the
+    /// least.
+    ///
+    /// As with `Display`, items with the same count are sorted lexically by their `Debug`
+    /// output, so the output is the same from one run to the next. For numbers, this puts
+    /// `10` before `2`; use [`debug_ordered`](#method.debug_ordered) to sort such ties by the
+    /// items themselves.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = "abbcc".chars().collect::<Counter<_>>();
+    /// assert_eq!(format!("{:?}", counter), "Counter({'b': 2, 'c': 2, 'a': 1})");
+    ///
+    /// let counter = Counter::<_>::from_pairs(vec![(2, 2), (10, 2)]);
+    /// assert_eq!(format!("{:?}", counter), "Counter({10: 2, 2: 2})");
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use core::cmp::Ordering;
+
+        let mut items = self
+            .map
+            .iter()
+            .map(|(item, count)| (format!("{:?}", item), item, count))
+            .collect::<Vec<_>>();
+        items.sort_by(|(a_key, _, a_count), (b_key, _, b_count)| {
+            b_count
+                .partial_cmp(a_count)
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| a_key.cmp(b_key))
+        });
+
+        f.debug_tuple("Counter")
+            .field(&SortedEntries(
+                items.into_iter().map(|(_, item, count)| (item, count)).collect(),
+            ))
+            .finish()
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq + Ord,
+    N: Ord,
+{
+    /// Format the counter like its `Debug` implementation, but sort items with the same count
+    /// by their natural ordering, as in
+    /// [`most_common_ordered`](#method.most_common_ordered).
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = Counter::<_>::from_pairs(vec![(2, 2), (10, 2), (1, 3)]);
+    /// assert_eq!(format!("{:?}", counter.debug_ordered()), "Counter({1: 3, 2: 2, 10: 2})");
+    /// ```
+    pub fn debug_ordered(&self) -> DebugOrdered<'_, T, N> {
+        use core::cmp::Reverse;
+
+        let mut items = self.map.iter().collect::<Vec<_>>();
+        items.sort_by(|&(a_item, a_count), &(b_item, b_count)| {
+            (Reverse(a_count), a_item).cmp(&(Reverse(b_count), b_item))
+        });
+        DebugOrdered(SortedEntries(items))
+    }
+}
+
+/// A `Debug` formatter for a counter which sorts items with the same count by their natural
+/// ordering.
+///
+/// This `struct` is created by the [`debug_ordered`](struct.Counter.html#method.debug_ordered)
+/// method on [`Counter`](struct.Counter.html).
+pub struct DebugOrdered<'a, T: 'a, N: 'a>(SortedEntries<'a, T, N>);
+
+impl<'a, T, N> fmt::Debug for DebugOrdered<'a, T, N>
+where
+    T: fmt::Debug,
+    N: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Counter").field(&self.0).finish()
+    }
+}
+
+struct SortedEntries<'a, T: 'a, N: 'a>(Vec<(&'a T, &'a N)>);
+
+impl<'a, T, N> fmt::Debug for SortedEntries<'a, T, N>
+where
+    T: fmt::Debug,
+    N: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.0.iter().cloned()).finish()
+    }
+}
+
+/// An iterator over the items of a counter, each repeated as many times as its count.
+///
+/// This `struct` is created by the [`elements`](struct.Counter.html#method.elements)
+/// method on [`Counter`](struct.Counter.html).
+pub struct Elements<'a, T: 'a, N: 'a> {
+    iter: hash_map::Iter<'a, T, N>,
+    current: Option<(&'a T, N)>,
+}
+
+impl<'a, T, N> Iterator for Elements<'a, T, N>
+where
+    N: Clone + PartialOrd + SubAssign + Zero + One,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        loop {
+            if let Some((item, remaining)) = &mut self.current {
+                if *remaining > N::zero() {
+                    *remaining -= N::one();
+                    return Some(*item);
+                }
+            }
+            let (item, count) = self.iter.next()?;
+            self.current = Some((item, count.clone()));
+        }
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Clone + PartialOrd + SubAssign + Zero + One,
+    S: BuildHasher,
+{
+    /// Create an iterator over the items, each repeated as many times as its count.
+    ///
+    /// Items with a non-positive count are skipped, and the order of the items is arbitrary.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = "abbccc".chars().collect::<Counter<_>>();
+    /// let mut elements = counter.elements().cloned().collect::<Vec<_>>();
+    /// elements.sort();
+    /// assert_eq!(elements, vec!['a', 'b', 'b', 'c', 'c', 'c']);
+    /// ```
+    pub fn elements(&self) -> Elements<'_, T, N> {
+        Elements {
+            iter: self.map.iter(),
+            current: None,
+        }
+    }
+}
+
+/// An `(item, count)` pair ordered by its count alone.
+struct ByCount<'a, T: 'a, N: 'a> {
+    count: &'a N,
+    item: &'a T,
+}
+
+impl<'a, T, N: Ord> PartialEq for ByCount<'a, T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.count == other.count
+    }
+}
+
+impl<'a, T, N: Ord> Eq for ByCount<'a, T, N> {}
+
+impl<'a, T, N: Ord> PartialOrd for ByCount<'a, T, N> {
+    fn partial_cmp(&self, other: &Self) -> Option<::core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<'a, T, N: Ord> Ord for ByCount<'a, T, N> {
+    fn cmp(&self, other: &Self) -> ::core::cmp::Ordering {
+        self.count.cmp(other.count)
+    }
+}
+
+/// A lazy iterator over `(&item, count)` pairs, from most to least common.
+///
+/// This `struct` is created by the [`most_common_iter`](struct.Counter.html#method.most_common_iter)
+/// method on [`Counter`](struct.Counter.html).
+pub struct MostCommon<'a, T: 'a, N: 'a> {
+    heap: ::alloc::collections::BinaryHeap<ByCount<'a, T, N>>,
+}
+
+impl<'a, T, N> Iterator for MostCommon<'a, T, N>
+where
+    N: Clone + Ord,
+{
+    type Item = (&'a T, N);
+
+    fn next(&mut self) -> Option<(&'a T, N)> {
+        self.heap
+            .pop()
+            .map(|ByCount { count, item }| (item, count.clone()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.heap.len(), Some(self.heap.len()))
+    }
+}
+
+impl<'a, T, N> ExactSizeIterator for MostCommon<'a, T, N> where N: Clone + Ord {}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Clone + Ord,
+    S: BuildHasher,
+{
+    /// Create a lazy iterator over `(&elem, frequency)` pairs, from most to least common.
+    ///
+    /// Building the iterator takes `O(len)` time, and each step takes `O(log len)`,
+    /// so taking only the first few items is much cheaper than sorting the whole counter.
+    /// The items don't need to be `Clone`.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = "pappaopolo".chars().collect::<Counter<_>>();
+    /// let mc = counter.most_common_iter().take(2).collect::<Vec<_>>();
+    /// assert_eq!(mc, vec![(&'p', 4), (&'o', 3)]);
+    /// ```
+    ///
+    /// Note that the ordering of duplicates is unstable.
+    pub fn most_common_iter(&self) -> MostCommon<'_, T, N> {
+        MostCommon {
+            heap: self.map
+                .iter()
+                .map(|(item, count)| ByCount { count, item })
+                .collect(),
+        }
+    }
+
+    /// Create a vector of `(frequency, elems)` groups of the items with the same count,
+    /// sorted most to least common.
+    ///
+    /// This shows ties explicitly, rather than putting the tied items in an arbitrary order.
+    /// The order of the items within each group is arbitrary.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = "abracadabra".chars().collect::<Counter<_>>();
+    /// let mut groups = counter.most_common_grouped();
+    /// for (_, items) in groups.iter_mut() {
+    ///     items.sort();
+    /// }
+    /// assert_eq!(groups, vec![(5, vec![&'a']), (2, vec![&'b', &'r']), (1, vec![&'c', &'d'])]);
+    /// ```
+    pub fn most_common_grouped(&self) -> Vec<(N, Vec<&T>)> {
+        let mut entries = self.map.iter().collect::<Vec<_>>();
+        entries.sort_unstable_by(|(_, a), (_, b)| b.cmp(a));
+        entries
+            .chunk_by(|(_, a), (_, b)| a == b)
+            .map(|group| {
+                let items = group.iter().map(|&(item, _)| item).collect();
+                (group[0].1.clone(), items)
+            })
+            .collect()
+    }
+
+    /// A map from each count to the items which have it
+    ///
+    /// Unlike [`most_common_grouped`](#method.most_common_grouped), this can be queried for a
+    /// particular count, and it is sorted from the least count to the greatest. The order of
+    /// the items with each count is arbitrary.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = "abracadabra".chars().collect::<Counter<_>>();
+    /// let by_count = counter.invert();
+    /// assert_eq!(by_count[&5], vec![&'a']);
+    /// assert_eq!(by_count[&1].len(), 2);
+    /// assert!(!by_count.contains_key(&3));
+    /// ```
+    pub fn invert(&self) -> BTreeMap<N, Vec<&T>> {
+        let mut inverted = BTreeMap::new();
+        for (item, count) in self.map.iter() {
+            inverted
+                .entry(count.clone())
+                .or_insert_with(Vec::new)
+                .push(item);
+        }
+        inverted
+    }
+
+    /// The `(&elem, frequency)` pair at index `k` of the items sorted from most to least
+    /// common, so `kth_most_common(0)` is the most common item, or `None` if `k` is at least
+    /// the number of items
+    ///
+    /// This uses a selection algorithm rather than sorting, so it takes `O(len)` time on
+    /// average.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = "pappaopolo".chars().collect::<Counter<_>>();
+    /// assert_eq!(counter.kth_most_common(1), Some((&'o', 3)));
+    /// assert_eq!(counter.kth_most_common(4), None);
+    /// ```
+    ///
+    /// Note that which of several items with the same count is returned is unspecified.
+    pub fn kth_most_common(&self, k: usize) -> Option<(&T, N)> {
+        if k >= self.map.len() {
+            return None;
+        }
+        let mut entries = self.map.iter().collect::<Vec<_>>();
+        let (_, &mut (item, count), _) =
+            entries.select_nth_unstable_by(k, |(_, a), (_, b)| b.cmp(a));
+        Some((item, count.clone()))
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq + Clone,
+    N: Clone + Ord,
+    S: BuildHasher,
+{
+    /// Create an iterator over `(frequency, elem)` pairs, sorted most to least common.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mc = "pappaopolo".chars().collect::<Counter<_>>().most_common();
+    /// let expected = vec![('p', 4), ('o', 3), ('a', 2), ('l', 1)];
+    /// assert_eq!(mc, expected);
+    /// ```
+    ///
+    /// Note that the ordering of duplicates is unstable. If the items are `Ord`,
+    /// [`most_common_ordered`](#method.most_common_ordered) gives a reproducible ordering.
+    pub fn most_common(&self) -> Vec<(T, N)> {
+        use core::cmp::Ordering;
+        self.most_common_tiebreaker(|_a, _b| Ordering::Equal)
+    }
+
+    /// Create an iterator over `(frequency, elem)` pairs, sorted most to least common.
+    ///
+    /// In the event that two keys have an equal frequency, use the supplied ordering function
+    /// to further arrange the results.
+    ///
+    /// For example, we can sort reverse-alphabetically:
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = "eaddbbccc".chars().collect::<Counter<_>>();
+    /// let by_common = counter.most_common_tiebreaker(|&a, &b| b.cmp(&a));
+    /// let expected = vec![('c', 3), ('d', 2), ('b', 2), ('e', 1), ('a', 1)];
+    /// assert_eq!(by_common, expected);
+    /// ```
+    pub fn most_common_tiebreaker<F>(&self, tiebreaker: F) -> Vec<(T, N)>
+    where
+        F: Fn(&T, &T) -> ::core::cmp::Ordering,
+    {
+        use core::cmp::Ordering;
+
+        // sort references, so that only the final order is cloned
+        let mut items = self.map.iter().collect::<Vec<_>>();
+        items.sort_unstable_by(|&(a_item, a_count), &(b_item, b_count)| {
+            match b_count.cmp(a_count) {
+                Ordering::Equal => tiebreaker(a_item, b_item),
+                unequal => unequal,
+            }
+        });
+        items
+            .into_iter()
+            .map(|(item, count)| (item.clone(), count.clone()))
+            .collect()
+    }
+
+    /// Create a vector of the `n` most common `(elem, frequency)` pairs, sorted most to least common.
+    ///
+    /// This keeps only `n` entries in a heap while scanning the counter, so it is
+    /// `O(len * log n)` rather than sorting every entry.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mc = "pappaopolo".chars().collect::<Counter<_>>().most_common_n(2);
+    /// let expected = vec![('p', 4), ('o', 3)];
+    /// assert_eq!(mc, expected);
+    /// ```
+    ///
+    /// Note that the ordering of duplicates is unstable.
+    pub fn most_common_n(&self, n: usize) -> Vec<(T, N)> {
+        use core::cmp::Reverse;
+        use alloc::collections::BinaryHeap;
+
+        if n == 0 {
+            return Vec::new();
+        }
+
+        let mut heap = BinaryHeap::with_capacity(n);
+        for (item, count) in self.map.iter() {
+            if heap.len() < n {
+                heap.push(Reverse(ByCount { count, item }));
+            } else if let Some(mut least) = heap.peek_mut() {
+                if *count > *(least.0).count {
+                    *least = Reverse(ByCount { count, item });
+                }
+            }
+        }
+
+        heap.into_sorted_vec()
+            .into_iter()
+            .map(|Reverse(ByCount { count, item })| (item.clone(), count.clone()))
+            .collect()
+    }
+
+    /// Create a vector of `(elem, frequency)` pairs, sorted least to most common.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let lc = "pappaopolo".chars().collect::<Counter<_>>().least_common();
+    /// let expected = vec![('l', 1), ('a', 2), ('o', 3), ('p', 4)];
+    /// assert_eq!(lc, expected);
+    /// ```
+    ///
+    /// Note that the ordering of duplicates is unstable.
+    pub fn least_common(&self) -> Vec<(T, N)> {
+        let mut items = self.map.iter().collect::<Vec<_>>();
+        items.sort_unstable_by_key(|&(_, count)| count);
+        items
+            .into_iter()
+            .map(|(item, count)| (item.clone(), count.clone()))
+            .collect()
+    }
+
+    /// Create a vector of the `n` least common `(elem, frequency)` pairs, sorted least to most common.
+    ///
+    /// Like [`most_common_n`](#method.most_common_n), this only keeps `n` entries in a heap.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let lc = "pappaopolo".chars().collect::<Counter<_>>().least_common_n(2);
+    /// let expected = vec![('l', 1), ('a', 2)];
+    /// assert_eq!(lc, expected);
+    /// ```
+    ///
+    /// Note that the ordering of duplicates is unstable.
+    pub fn least_common_n(&self, n: usize) -> Vec<(T, N)> {
+        use alloc::collections::BinaryHeap;
+
+        if n == 0 {
+            return Vec::new();
+        }
+
+        let mut heap = BinaryHeap::with_capacity(n);
+        for (item, count) in self.map.iter() {
+            if heap.len() < n {
+                heap.push(ByCount { count, item });
+            } else if let Some(mut greatest) = heap.peek_mut() {
+                if *count < *greatest.count {
+                    *greatest = ByCount { count, item };
+                }
+            }
+        }
+
+        heap.into_sorted_vec()
+            .into_iter()
+            .map(|ByCount { count, item }| (item.clone(), count.clone()))
+            .collect()
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq + Clone + Ord,
+    N: Clone + Ord,
+    S: BuildHasher,
+{
+    /// Create an iterator over `(frequency, elem)` pairs, sorted most to least common.
+    ///
+    /// In the event that two keys have an equal frequency, use the natural ordering of the keys
+    /// to further sort the results.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mc = "abracadabra".chars().collect::<Counter<_>>().most_common_ordered();
+    /// let expect = vec![('a', 5), ('b', 2), ('r', 2), ('c', 1), ('d', 1)];
+    /// assert_eq!(mc, expect);
+    /// ```
+    ///
+    /// Unlike [`most_common`](#method.most_common), the output doesn't depend on the
+    /// iteration order of the underlying map, so it is the same on every run.
+    pub fn most_common_ordered(&self) -> Vec<(T, N)> {
+        self.most_common_tiebreaker(|a, b| a.cmp(b))
+    }
+}
+
+/// How [`Counter::ranks`](struct.Counter.html#method.ranks) ranks items with the same count
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RankTies {
+    /// Give tied items the best of their ranks, so the counts `3, 2, 2, 1` are ranked
+    /// `1, 2, 2, 4`
+    Min,
+    /// Give tied items the worst of their ranks, so the counts `3, 2, 2, 1` are ranked
+    /// `1, 3, 3, 4`
+    Max,
+    /// Give tied items the same rank without leaving gaps, so the counts `3, 2, 2, 1` are
+    /// ranked `1, 2, 2, 3`
+    Dense,
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Ord,
+    S: BuildHasher,
+{
+    /// The rank of each item by its count, where the most common item has rank 1
+    ///
+    /// Sorting the counter once here makes looking up many ranks cheap; for a single item,
+    /// [`rank_of`](#method.rank_of) doesn't sort.
+    ///
+    /// ```rust
+    /// # use counter::{Counter, RankTies};
+    /// let counter = "abbccddd".chars().collect::<Counter<_>>();
+    /// let ranks = counter.ranks(RankTies::Min);
+    /// assert_eq!((ranks[&'d'], ranks[&'b'], ranks[&'c'], ranks[&'a']), (1, 2, 2, 4));
+    /// let ranks = counter.ranks(RankTies::Dense);
+    /// assert_eq!(ranks[&'a'], 3);
+    /// ```
+    pub fn ranks(&self, ties: RankTies) -> HashMap<&T, usize, S>
+    where
+        S: Clone,
+    {
+        let mut entries = self.map.iter().collect::<Vec<_>>();
+        entries.sort_by(|(_, a), (_, b)| b.cmp(a));
+
+        let mut ranks = HashMap::with_capacity_and_hasher(entries.len(), self.map.hasher().clone());
+        let mut start = 0;
+        for (group, tied) in entries.chunk_by(|(_, a), (_, b)| a == b).enumerate() {
+            let rank = match ties {
+                RankTies::Min => start + 1,
+                RankTies::Max => start + tied.len(),
+                RankTies::Dense => group + 1,
+            };
+            ranks.extend(tied.iter().map(|&(item, _)| (item, rank)));
+            start += tied.len();
+        }
+        ranks
+    }
+
+    /// The rank of `item` by its count, as given by [`ranks`](#method.ranks), or `None` if it
+    /// isn't in the counter
+    ///
+    /// This takes `O(len)` time for [`RankTies::Min`](enum.RankTies.html#variant.Min) and
+    /// [`RankTies::Max`](enum.RankTies.html#variant.Max), and `O(len * log len)` for
+    /// [`RankTies::Dense`](enum.RankTies.html#variant.Dense).
+    ///
+    /// ```rust
+    /// # use counter::{Counter, RankTies};
+    /// let counter = "abbccddd".chars().collect::<Counter<_>>();
+    /// assert_eq!(counter.rank_of(&'c', RankTies::Max), Some(3));
+    /// assert_eq!(counter.rank_of(&'z', RankTies::Max), None);
+    /// ```
+    pub fn rank_of<Q>(&self, item: &Q, ties: RankTies) -> Option<usize>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let count = self.map.get(item)?;
+        let rank = match ties {
+            RankTies::Min => 1 + self.map.values().filter(|&other| other > count).count(),
+            RankTies::Max => self.map.values().filter(|&other| other >= count).count(),
+            RankTies::Dense => {
+                let mut greater = self
+                    .map
+                    .values()
+                    .filter(|&other| other > count)
+                    .collect::<Vec<_>>();
+                greater.sort();
+                greater.dedup();
+                1 + greater.len()
+            }
+        };
+        Some(rank)
+    }
+}
+
+impl<T, N, S> AddAssign for Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Zero + AddAssign,
+    S: BuildHasher,
+{
+    /// Add another counter to this counter
+    ///
+    /// `c += d;` -> `c[x] += d[x]` for all `x`
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// # #[cfg(not(feature = "std"))] extern crate hashbrown;
+    /// # #[cfg(not(feature = "std"))] use hashbrown::HashMap;
+    /// # #[cfg(feature = "std")] use std::collections::HashMap;
+    /// let mut c = "aaab".chars().collect::<Counter<_>>();
+    /// let d = "abb".chars().collect::<Counter<_>>();
+    ///
+    /// c += d;
+    ///
+    /// let expect = [('a', 4), ('b', 3)].iter().cloned().collect::<HashMap<_, _>>();
+    /// assert_eq!(c.into_map(), expect);
+    /// ```
+    fn add_assign(&mut self, rhs: Self) {
+        self.merge_from(rhs);
+    }
+}
+
+impl<T, N, S> Add for Counter<T, N, S>
+where
+    T: Clone + Hash + Eq,
+    N: Clone + PartialOrd + PartialEq + AddAssign + Zero,
+    S: BuildHasher,
+{
+    type Output = Counter<T, N, S>;
+
+    /// Add two counters together.
+    ///
+    /// `out = c + d;` -> `out[x] == c[x] + d[x]` for all `x`
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// # #[cfg(not(feature = "std"))] extern crate hashbrown;
+    /// # #[cfg(not(feature = "std"))] use hashbrown::HashMap;
+    /// # #[cfg(feature = "std")] use std::collections::HashMap;
+    /// let c = "aaab".chars().collect::<Counter<_>>();
+    /// let d = "abb".chars().collect::<Counter<_>>();
+    ///
+    /// let e = c + d;
+    ///
+    /// let expect = [('a', 4), ('b', 3)].iter().cloned().collect::<HashMap<_, _>>();
+    /// assert_eq!(e.into_map(), expect);
+    /// ```
+    fn add(self, rhs: Counter<T, N, S>) -> Self::Output {
+        let mut counter = self;
+        counter += rhs;
+        counter
+    }
+}
+
+impl<T, N, S> SubAssign for Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Clone + PartialOrd + PartialEq + SubAssign + Zero,
+    S: BuildHasher,
+{
+    /// Subtract (keeping only positive values).
+    ///
+    /// `c -= d;` -> `c[x] -= d[x]` for all `x`,
+    /// keeping only items with a value greater than N::zero().
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// # #[cfg(not(feature = "std"))] extern crate hashbrown;
+    /// # #[cfg(not(feature = "std"))] use hashbrown::HashMap;
+    /// # #[cfg(feature = "std")] use std::collections::HashMap;
+    /// let mut c = "aaab".chars().collect::<Counter<_>>();
+    /// let d = "abb".chars().collect::<Counter<_>>();
+    ///
+    /// c -= d;
+    ///
+    /// let expect = [('a', 2)].iter().cloned().collect::<HashMap<_, _>>();
+    /// assert_eq!(c.into_map(), expect);
+    /// ```
+    fn sub_assign(&mut self, rhs: Self) {
+        self.subtract_counter(&rhs);
+    }
+}
+
+impl<T, N, S> Sub for Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Clone + PartialOrd + PartialEq + SubAssign + Zero,
+    S: BuildHasher,
+{
+    type Output = Counter<T, N, S>;
+
+    /// Subtract (keeping only positive values).
+    ///
+    /// `out = c - d;` -> `out[x] == c[x] - d[x]` for all `x`,
+    /// keeping only items with a value greater than N::zero().
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// # #[cfg(not(feature = "std"))] extern crate hashbrown;
+    /// # #[cfg(not(feature = "std"))] use hashbrown::HashMap;
+    /// # #[cfg(feature = "std")] use std::collections::HashMap;
+    /// let c = "aaab".chars().collect::<Counter<_>>();
+    /// let d = "abb".chars().collect::<Counter<_>>();
+    ///
+    /// let e = c - d;
+    ///
+    /// let expect = [('a', 2)].iter().cloned().collect::<HashMap<_, _>>();
+    /// assert_eq!(e.into_map(), expect);
+    /// ```
+    fn sub(mut self, rhs: Counter<T, N, S>) -> Self::Output {
+        self -= rhs;
+        self
+    }
+}
+
+impl<T, N, S> BitAnd for Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Clone + Ord,
+    S: BuildHasher,
+{
+    type Output = Counter<T, N, S>;
+
+    /// Intersection
+    ///
+    /// `out = c & d;` -> `out[x] == min(c[x], d[x])`
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// # #[cfg(not(feature = "std"))] extern crate hashbrown;
+    /// # #[cfg(not(feature = "std"))] use hashbrown::HashMap;
+    /// # #[cfg(feature = "std")] use std::collections::HashMap;
+    /// let c = "aaab".chars().collect::<Counter<_>>();
+    /// let d = "abb".chars().collect::<Counter<_>>();
+    ///
+    /// let e = c & d;
+    ///
+    /// let expect = [('a', 1), ('b', 1)].iter().cloned().collect::<HashMap<_, _>>();
+    /// assert_eq!(e.into_map(), expect);
+    /// ```
+    fn bitand(mut self, rhs: Counter<T, N, S>) -> Self::Output {
+        self &= rhs;
+        self
+    }
+}
+
+impl<T, N, S> BitAndAssign for Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Clone + Ord,
+    S: BuildHasher,
+{
+    /// Intersection in place
+    ///
+    /// `c &= d;` -> `c[x] == min(c[x], d[x])`
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// # #[cfg(not(feature = "std"))] extern crate hashbrown;
+    /// # #[cfg(not(feature = "std"))] use hashbrown::HashMap;
+    /// # #[cfg(feature = "std")] use std::collections::HashMap;
+    /// let mut c = "aaab".chars().collect::<Counter<_>>();
+    /// let d = "abb".chars().collect::<Counter<_>>();
+    ///
+    /// c &= d;
+    ///
+    /// let expect = [('a', 1), ('b', 1)].iter().cloned().collect::<HashMap<_, _>>();
+    /// assert_eq!(c.into_map(), expect);
+    /// ```
+    fn bitand_assign(&mut self, rhs: Counter<T, N, S>) {
+        self.map.retain(|key, count| match rhs.map.get(key) {
+            Some(other_count) => {
+                if *other_count < *count {
+                    *count = other_count.clone();
+                }
+                true
+            }
+            None => false,
+        });
+    }
+}
+
+impl<T, N, S> BitOr for Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Ord + Zero,
+    S: BuildHasher,
+{
+    type Output = Counter<T, N, S>;
+
+    /// Union
+    ///
+    /// `out = c | d;` -> `out[x] == max(c[x], d[x])`
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// # #[cfg(not(feature = "std"))] extern crate hashbrown;
+    /// # #[cfg(not(feature = "std"))] use hashbrown::HashMap;
+    /// # #[cfg(feature = "std")] use std::collections::HashMap;
+    /// let c = "aaab".chars().collect::<Counter<_>>();
+    /// let d = "abb".chars().collect::<Counter<_>>();
+    ///
+    /// let e = c | d;
+    ///
+    /// let expect = [('a', 3), ('b', 2)].iter().cloned().collect::<HashMap<_, _>>();
+    /// assert_eq!(e.into_map(), expect);
+    /// ```
+    fn bitor(mut self, rhs: Counter<T, N, S>) -> Self::Output {
+        self |= rhs;
+        self
+    }
+}
+
+impl<T, N, S> BitOrAssign for Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Ord + Zero,
+    S: BuildHasher,
+{
+    /// Union in place
+    ///
+    /// `c |= d;` -> `c[x] == max(c[x], d[x])`
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// # #[cfg(not(feature = "std"))] extern crate hashbrown;
+    /// # #[cfg(not(feature = "std"))] use hashbrown::HashMap;
+    /// # #[cfg(feature = "std")] use std::collections::HashMap;
+    /// let mut c = "aaab".chars().collect::<Counter<_>>();
+    /// let d = "abb".chars().collect::<Counter<_>>();
+    ///
+    /// c |= d;
+    ///
+    /// let expect = [('a', 3), ('b', 2)].iter().cloned().collect::<HashMap<_, _>>();
+    /// assert_eq!(c.into_map(), expect);
+    /// ```
+    fn bitor_assign(&mut self, rhs: Counter<T, N, S>) {
+        for (key, value) in rhs.map.into_iter() {
+            let entry = self.map.entry(key).or_insert_with(N::zero);
+            if value > *entry {
+                *entry = value;
+            }
+        }
+    }
+}
+
+impl<T, N, S> Deref for Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Clone,
+    S: BuildHasher,
+{
+    type Target = CounterMap<T, N, S>;
+    fn deref(&self) -> &CounterMap<T, N, S> {
+        &self.map
+    }
+}
+
+impl<T, N, S> DerefMut for Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Clone,
+    S: BuildHasher,
+{
+    fn deref_mut(&mut self) -> &mut CounterMap<T, N, S> {
+        &mut self.map
+    }
+}
+
+impl<'a, T, N, S> AddAssign<&'a Counter<T, N, S>> for Counter<T, N, S>
+where
+    T: Clone + Hash + Eq,
+    N: Clone + Zero + AddAssign,
+    S: BuildHasher,
+{
+    /// Add another counter to this counter, without consuming it
+    ///
+    /// `c += &d;` -> `c[x] += d[x]` for all `x`
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// # #[cfg(not(feature = "std"))] extern crate hashbrown;
+    /// # #[cfg(not(feature = "std"))] use hashbrown::HashMap;
+    /// # #[cfg(feature = "std")] use std::collections::HashMap;
+    /// let mut c = "aaab".chars().collect::<Counter<_>>();
     /// let d = "abb".chars().collect::<Counter<_>>();
     ///
-    /// c += d;
+    /// c += &d;
     ///
     /// let expect = [('a', 4), ('b', 3)].iter().cloned().collect::<HashMap<_, _>>();
     /// assert_eq!(c.into_map(), expect);
     /// ```
-    fn add_assign(&mut self, rhs: Self) {
+    fn add_assign(&mut self, rhs: &'a Counter<T, N, S>) {
+        self.merge_from_ref(rhs);
+    }
+}
+
+impl<'a, T, N, S> SubAssign<&'a Counter<T, N, S>> for Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Clone + PartialOrd + PartialEq + SubAssign + Zero,
+    S: BuildHasher,
+{
+    /// Subtract another counter from this counter, without consuming it
+    /// (keeping only positive values).
+    ///
+    /// `c -= &d;` -> `c[x] -= d[x]` for all `x`,
+    /// keeping only items with a value greater than N::zero().
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// # #[cfg(not(feature = "std"))] extern crate hashbrown;
+    /// # #[cfg(not(feature = "std"))] use hashbrown::HashMap;
+    /// # #[cfg(feature = "std")] use std::collections::HashMap;
+    /// let mut c = "aaab".chars().collect::<Counter<_>>();
+    /// let d = "abb".chars().collect::<Counter<_>>();
+    ///
+    /// c -= &d;
+    ///
+    /// let expect = [('a', 2)].iter().cloned().collect::<HashMap<_, _>>();
+    /// assert_eq!(c.into_map(), expect);
+    /// ```
+    fn sub_assign(&mut self, rhs: &'a Counter<T, N, S>) {
+        self.subtract_counter(rhs);
+    }
+}
+
+impl<'a, T, N, S> BitAndAssign<&'a Counter<T, N, S>> for Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Clone + Ord,
+    S: BuildHasher,
+{
+    /// Intersection in place, without consuming the other counter
+    ///
+    /// `c &= &d;` -> `c[x] == min(c[x], d[x])`
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// # #[cfg(not(feature = "std"))] extern crate hashbrown;
+    /// # #[cfg(not(feature = "std"))] use hashbrown::HashMap;
+    /// # #[cfg(feature = "std")] use std::collections::HashMap;
+    /// let mut c = "aaab".chars().collect::<Counter<_>>();
+    /// let d = "abb".chars().collect::<Counter<_>>();
+    ///
+    /// c &= &d;
+    ///
+    /// let expect = [('a', 1), ('b', 1)].iter().cloned().collect::<HashMap<_, _>>();
+    /// assert_eq!(c.into_map(), expect);
+    /// ```
+    fn bitand_assign(&mut self, rhs: &'a Counter<T, N, S>) {
+        self.map.retain(|key, count| match rhs.map.get(key) {
+            Some(other_count) => {
+                if *other_count < *count {
+                    *count = other_count.clone();
+                }
+                true
+            }
+            None => false,
+        });
+    }
+}
+
+impl<'a, T, N, S> BitOrAssign<&'a Counter<T, N, S>> for Counter<T, N, S>
+where
+    T: Clone + Hash + Eq,
+    N: Clone + Ord + Zero,
+    S: BuildHasher,
+{
+    /// Union in place, without consuming the other counter
+    ///
+    /// `c |= &d;` -> `c[x] == max(c[x], d[x])`
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// # #[cfg(not(feature = "std"))] extern crate hashbrown;
+    /// # #[cfg(not(feature = "std"))] use hashbrown::HashMap;
+    /// # #[cfg(feature = "std")] use std::collections::HashMap;
+    /// let mut c = "aaab".chars().collect::<Counter<_>>();
+    /// let d = "abb".chars().collect::<Counter<_>>();
+    ///
+    /// c |= &d;
+    ///
+    /// let expect = [('a', 3), ('b', 2)].iter().cloned().collect::<HashMap<_, _>>();
+    /// assert_eq!(c.into_map(), expect);
+    /// ```
+    fn bitor_assign(&mut self, rhs: &'a Counter<T, N, S>) {
         for (key, value) in rhs.map.iter() {
             let entry = self.map.entry(key.clone()).or_insert_with(N::zero);
-            *entry += value.clone();
+            if *value > *entry {
+                *entry = value.clone();
+            }
         }
     }
 }
 
-impl<T, N> Add for Counter<T, N>
+impl<'b, T, N, S> Add<&'b Counter<T, N, S>> for &Counter<T, N, S>
 where
     T: Clone + Hash + Eq,
-    N: Clone + PartialOrd + PartialEq + AddAssign + Zero,
+    N: Clone + Zero + AddAssign,
+    S: BuildHasher + Clone,
 {
-    type Output = Counter<T, N>;
+    type Output = Counter<T, N, S>;
 
-    /// Add two counters together.
-    ///
-    /// `out = c + d;` -> `out[x] == c[x] + d[x]` for all `x`
+    /// Add two counters together, without consuming either counter.
     ///
     /// ```rust
     /// # use counter::Counter;
-    /// # use std::collections::HashMap;
+    /// # #[cfg(not(feature = "std"))] extern crate hashbrown;
+    /// # #[cfg(not(feature = "std"))] use hashbrown::HashMap;
+    /// # #[cfg(feature = "std")] use std::collections::HashMap;
     /// let c = "aaab".chars().collect::<Counter<_>>();
     /// let d = "abb".chars().collect::<Counter<_>>();
     ///
-    /// let e = c + d;
+    /// let e = &c + &d;
     ///
     /// let expect = [('a', 4), ('b', 3)].iter().cloned().collect::<HashMap<_, _>>();
     /// assert_eq!(e.into_map(), expect);
     /// ```
-    fn add(self, rhs: Counter<T, N>) -> Self::Output {
-        let mut counter = self;
+    fn add(self, rhs: &'b Counter<T, N, S>) -> Self::Output {
+        let mut counter = self.clone();
         counter += rhs;
         counter
     }
 }
 
-impl<T, N> SubAssign for Counter<T, N>
+impl<'a, T, N, S> Add<&'a Counter<T, N, S>> for Counter<T, N, S>
 where
-    T: Hash + Eq,
-    N: Clone + PartialOrd + PartialEq + SubAssign + Zero,
+    T: Clone + Hash + Eq,
+    N: Clone + Zero + AddAssign,
+    S: BuildHasher,
 {
-    /// Subtract (keeping only positive values).
+    type Output = Counter<T, N, S>;
+
+    /// Add two counters together, without consuming the right-hand counter.
+    fn add(mut self, rhs: &'a Counter<T, N, S>) -> Self::Output {
+        self += rhs;
+        self
+    }
+}
+
+impl<T, N, S> Add<Counter<T, N, S>> for &Counter<T, N, S>
+where
+    T: Clone + Hash + Eq,
+    N: Clone + Zero + AddAssign,
+    S: BuildHasher,
+{
+    type Output = Counter<T, N, S>;
+
+    /// Add two counters together, without consuming the left-hand counter.
     ///
-    /// `c -= d;` -> `c[x] -= d[x]` for all `x`,
-    /// keeping only items with a value greater than N::zero().
+    /// The operation is commutative, so this reuses the allocation of the right-hand counter.
+    fn add(self, mut rhs: Counter<T, N, S>) -> Self::Output {
+        rhs += self;
+        rhs
+    }
+}
+
+impl<'b, T, N, S> Sub<&'b Counter<T, N, S>> for &Counter<T, N, S>
+where
+    T: Clone + Hash + Eq,
+    N: Clone + PartialOrd + PartialEq + SubAssign + Zero,
+    S: BuildHasher + Clone,
+{
+    type Output = Counter<T, N, S>;
+
+    /// Subtract (keeping only positive values), without consuming either counter.
     ///
     /// ```rust
     /// # use counter::Counter;
-    /// # use std::collections::HashMap;
-    /// let mut c = "aaab".chars().collect::<Counter<_>>();
+    /// # #[cfg(not(feature = "std"))] extern crate hashbrown;
+    /// # #[cfg(not(feature = "std"))] use hashbrown::HashMap;
+    /// # #[cfg(feature = "std")] use std::collections::HashMap;
+    /// let c = "aaab".chars().collect::<Counter<_>>();
     /// let d = "abb".chars().collect::<Counter<_>>();
     ///
-    /// c -= d;
+    /// let e = &c - &d;
     ///
     /// let expect = [('a', 2)].iter().cloned().collect::<HashMap<_, _>>();
-    /// assert_eq!(c.into_map(), expect);
+    /// assert_eq!(e.into_map(), expect);
     /// ```
-    fn sub_assign(&mut self, rhs: Self) {
-        for (key, value) in rhs.map.iter() {
-            let mut remove = false;
-            if let Some(entry) = self.map.get_mut(key) {
-                if *entry >= *value {
-                    *entry -= value.clone();
-                } else {
-                    remove = true;
-                }
-                if *entry == N::zero() {
-                    remove = true;
-                }
-            }
-            if remove {
-                self.map.remove(key);
-            }
-        }
+    fn sub(self, rhs: &'b Counter<T, N, S>) -> Self::Output {
+        let mut counter = self.clone();
+        counter -= rhs;
+        counter
     }
 }
 
-impl<T, N> Sub for Counter<T, N>
+impl<'a, T, N, S> Sub<&'a Counter<T, N, S>> for Counter<T, N, S>
 where
-    T: Hash + Eq,
+    T: Clone + Hash + Eq,
     N: Clone + PartialOrd + PartialEq + SubAssign + Zero,
+    S: BuildHasher,
 {
-    type Output = Counter<T, N>;
+    type Output = Counter<T, N, S>;
 
-    /// Subtract (keeping only positive values).
-    ///
-    /// `out = c - d;` -> `out[x] == c[x] - d[x]` for all `x`,
-    /// keeping only items with a value greater than N::zero().
+    /// Subtract (keeping only positive values), without consuming the right-hand counter.
+    fn sub(mut self, rhs: &'a Counter<T, N, S>) -> Self::Output {
+        self -= rhs;
+        self
+    }
+}
+
+impl<T, N, S> Sub<Counter<T, N, S>> for &Counter<T, N, S>
+where
+    T: Clone + Hash + Eq,
+    N: Clone + PartialOrd + PartialEq + SubAssign + Zero,
+    S: BuildHasher + Clone,
+{
+    type Output = Counter<T, N, S>;
+
+    /// Subtract (keeping only positive values), without consuming the left-hand counter.
+    fn sub(self, rhs: Counter<T, N, S>) -> Self::Output {
+        let mut counter = self.clone();
+        counter -= &rhs;
+        counter
+    }
+}
+
+impl<'b, T, N, S> BitAnd<&'b Counter<T, N, S>> for &Counter<T, N, S>
+where
+    T: Clone + Hash + Eq,
+    N: Clone + Ord,
+    S: BuildHasher + Clone,
+{
+    type Output = Counter<T, N, S>;
+
+    /// Intersection, without consuming either counter.
     ///
     /// ```rust
     /// # use counter::Counter;
-    /// # use std::collections::HashMap;
+    /// # #[cfg(not(feature = "std"))] extern crate hashbrown;
+    /// # #[cfg(not(feature = "std"))] use hashbrown::HashMap;
+    /// # #[cfg(feature = "std")] use std::collections::HashMap;
     /// let c = "aaab".chars().collect::<Counter<_>>();
     /// let d = "abb".chars().collect::<Counter<_>>();
     ///
-    /// let e = c - d;
+    /// let e = &c & &d;
     ///
-    /// let expect = [('a', 2)].iter().cloned().collect::<HashMap<_, _>>();
+    /// let expect = [('a', 1), ('b', 1)].iter().cloned().collect::<HashMap<_, _>>();
     /// assert_eq!(e.into_map(), expect);
     /// ```
-    fn sub(mut self, rhs: Counter<T, N>) -> Self::Output {
-        self -= rhs;
+    fn bitand(self, rhs: &'b Counter<T, N, S>) -> Self::Output {
+        let mut counter = self.clone();
+        counter &= rhs;
+        counter
+    }
+}
+
+impl<'a, T, N, S> BitAnd<&'a Counter<T, N, S>> for Counter<T, N, S>
+where
+    T: Clone + Hash + Eq,
+    N: Clone + Ord,
+    S: BuildHasher,
+{
+    type Output = Counter<T, N, S>;
+
+    /// Intersection, without consuming the right-hand counter.
+    fn bitand(mut self, rhs: &'a Counter<T, N, S>) -> Self::Output {
+        self &= rhs;
         self
     }
 }
 
-impl<T, N> BitAnd for Counter<T, N>
+impl<T, N, S> BitAnd<Counter<T, N, S>> for &Counter<T, N, S>
 where
     T: Clone + Hash + Eq,
-    N: Clone + Ord + AddAssign + SubAssign + Zero + One,
+    N: Clone + Ord,
+    S: BuildHasher,
 {
-    type Output = Counter<T, N>;
+    type Output = Counter<T, N, S>;
 
-    /// Intersection
+    /// Intersection, without consuming the left-hand counter.
     ///
-    /// `out = c & d;` -> `out[x] == min(c[x], d[x])`
+    /// The operation is commutative, so this reuses the allocation of the right-hand counter.
+    fn bitand(self, mut rhs: Counter<T, N, S>) -> Self::Output {
+        rhs &= self;
+        rhs
+    }
+}
+
+impl<'b, T, N, S> BitOr<&'b Counter<T, N, S>> for &Counter<T, N, S>
+where
+    T: Clone + Hash + Eq,
+    N: Clone + Ord + Zero,
+    S: BuildHasher + Clone,
+{
+    type Output = Counter<T, N, S>;
+
+    /// Union, without consuming either counter.
     ///
     /// ```rust
     /// # use counter::Counter;
-    /// # use std::collections::HashMap;
+    /// # #[cfg(not(feature = "std"))] extern crate hashbrown;
+    /// # #[cfg(not(feature = "std"))] use hashbrown::HashMap;
+    /// # #[cfg(feature = "std")] use std::collections::HashMap;
     /// let c = "aaab".chars().collect::<Counter<_>>();
     /// let d = "abb".chars().collect::<Counter<_>>();
     ///
-    /// let e = c & d;
+    /// let e = &c | &d;
     ///
-    /// let expect = [('a', 1), ('b', 1)].iter().cloned().collect::<HashMap<_, _>>();
+    /// let expect = [('a', 3), ('b', 2)].iter().cloned().collect::<HashMap<_, _>>();
     /// assert_eq!(e.into_map(), expect);
     /// ```
-    fn bitand(self, rhs: Counter<T, N>) -> Self::Output {
-        use std::cmp::min;
-        use std::collections::HashSet;
+    fn bitor(self, rhs: &'b Counter<T, N, S>) -> Self::Output {
+        let mut counter = self.clone();
+        counter |= rhs;
+        counter
+    }
+}
+
+impl<'a, T, N, S> BitOr<&'a Counter<T, N, S>> for Counter<T, N, S>
+where
+    T: Clone + Hash + Eq,
+    N: Clone + Ord + Zero,
+    S: BuildHasher,
+{
+    type Output = Counter<T, N, S>;
 
-        let self_keys = self.map.keys().collect::<HashSet<_>>();
-        let other_keys = rhs.map.keys().collect::<HashSet<_>>();
-        let both_keys = self_keys.intersection(&other_keys);
+    /// Union, without consuming the right-hand counter.
+    fn bitor(mut self, rhs: &'a Counter<T, N, S>) -> Self::Output {
+        self |= rhs;
+        self
+    }
+}
 
-        let mut counter = Counter::new();
-        for key in both_keys {
-            counter.map.insert(
-                (*key).clone(),
-                min(self.map.get(*key).unwrap(), rhs.map.get(*key).unwrap()).clone(),
-            );
+impl<T, N, S> BitOr<Counter<T, N, S>> for &Counter<T, N, S>
+where
+    T: Clone + Hash + Eq,
+    N: Clone + Ord + Zero,
+    S: BuildHasher,
+{
+    type Output = Counter<T, N, S>;
+
+    /// Union, without consuming the left-hand counter.
+    ///
+    /// The operation is commutative, so this reuses the allocation of the right-hand counter.
+    fn bitor(self, mut rhs: Counter<T, N, S>) -> Self::Output {
+        rhs |= self;
+        rhs
+    }
+}
+
+impl<T, N, S> Neg for Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Signed,
+    S: BuildHasher,
+{
+    type Output = Counter<T, N, S>;
+
+    /// Negate the counts, keeping only the ones which become positive.
+    ///
+    /// `out = -c;` -> `out[x] == -c[x]` for all `x` where `c[x] < 0`
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// # #[cfg(not(feature = "std"))] extern crate hashbrown;
+    /// # #[cfg(not(feature = "std"))] use hashbrown::HashMap;
+    /// # #[cfg(feature = "std")] use std::collections::HashMap;
+    /// let mut c = "aaab".chars().collect::<Counter<_, i64>>();
+    /// c.subtract_signed("bbcc".chars());
+    ///
+    /// let e = -c;
+    ///
+    /// let expect = [('b', 1), ('c', 2)].iter().cloned().collect::<HashMap<_, _>>();
+    /// assert_eq!(e.into_map(), expect);
+    /// ```
+    fn neg(mut self) -> Self::Output {
+        self.map.retain(|_, count| count.is_negative());
+        for count in self.map.values_mut() {
+            let negative = mem::replace(count, N::zero());
+            *count = -negative;
         }
+        self
+    }
+}
 
-        counter
+impl<T, N, S> MulAssign<N> for Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Clone + MulAssign + Zero,
+    S: BuildHasher,
+{
+    /// Multiply every count by `rhs`, removing the items whose counts become zero.
+    ///
+    /// `c *= n;` -> `c[x] == old_c[x] * n` for all `x`
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// # #[cfg(not(feature = "std"))] extern crate hashbrown;
+    /// # #[cfg(not(feature = "std"))] use hashbrown::HashMap;
+    /// # #[cfg(feature = "std")] use std::collections::HashMap;
+    /// let mut c = "aab".chars().collect::<Counter<_>>();
+    /// c *= 3;
+    ///
+    /// let expect = [('a', 6), ('b', 3)].iter().cloned().collect::<HashMap<_, _>>();
+    /// assert_eq!(c.into_map(), expect);
+    /// ```
+    fn mul_assign(&mut self, rhs: N) {
+        self.map.retain(|_, count| {
+            *count *= rhs.clone();
+            !count.is_zero()
+        });
+    }
+}
+
+impl<T, N, S> Mul<N> for Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Clone + MulAssign + Zero,
+    S: BuildHasher,
+{
+    type Output = Counter<T, N, S>;
+
+    /// Multiply every count by `rhs`, such as to scale up the ingredients of a recipe.
+    ///
+    /// `out = c * n;` -> `out[x] == c[x] * n` for all `x`
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// # #[cfg(not(feature = "std"))] extern crate hashbrown;
+    /// # #[cfg(not(feature = "std"))] use hashbrown::HashMap;
+    /// # #[cfg(feature = "std")] use std::collections::HashMap;
+    /// let recipe: Counter<_> = vec![("egg", 2), ("flour", 3)].into_iter().collect();
+    /// let double = recipe * 2;
+    ///
+    /// let expect = [("egg", 4), ("flour", 6)].iter().cloned().collect::<HashMap<_, _>>();
+    /// assert_eq!(double.into_map(), expect);
+    /// ```
+    fn mul(mut self, rhs: N) -> Self::Output {
+        self *= rhs;
+        self
+    }
+}
+
+impl<'q, Q, T, N, S> Index<&'q Q> for Counter<T, N, S>
+where
+    T: Hash + Eq + Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+    S: BuildHasher,
+{
+    type Output = N;
+
+    /// Index in immutable contexts
+    ///
+    /// Returns a reference to a `zero` value for missing keys.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = Counter::<_>::init("aabbcc".chars());
+    /// assert_eq!(counter[&'a'], 2);
+    /// assert_eq!(counter[&'b'], 2);
+    /// assert_eq!(counter[&'c'], 2);
+    /// assert_eq!(counter[&'d'], 0);
+    /// ```
+    fn index(&self, key: &'q Q) -> &N {
+        self.map.get(key).unwrap_or(&self.zero)
+    }
+}
+
+impl<'q, Q, T, N, S> IndexMut<&'q Q> for Counter<T, N, S>
+where
+    T: Hash + Eq + Borrow<Q>,
+    Q: Hash + Eq + ToOwned<Owned = T> + ?Sized,
+    N: Zero,
+    S: BuildHasher,
+{
+    /// Index in mutable contexts
+    ///
+    /// If the given key is not present, creates a new entry and initializes it with a `zero` value.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mut counter = Counter::<_>::init("aabbcc".chars());
+    /// counter[&'c'] += 1;
+    /// counter[&'d'] += 1;
+    /// assert_eq!(counter[&'c'], 3);
+    /// assert_eq!(counter[&'d'], 1);
+    ///
+    /// let mut counter = Counter::<String>::new();
+    /// counter["word"] += 1;
+    /// assert_eq!(counter["word"], 1);
+    /// ```
+    fn index_mut(&mut self, key: &'q Q) -> &mut N {
+        if !self.map.contains_key(key) {
+            self.map.insert(key.to_owned(), N::zero());
+        }
+        self.map.get_mut(key).unwrap()
     }
 }
 
-impl<T, N> BitOr for Counter<T, N>
+impl<T, N, S> IntoIterator for Counter<T, N, S>
 where
-    T: Clone + Hash + Eq,
-    N: Clone + Ord + Zero,
+    T: Hash + Eq,
+    S: BuildHasher,
 {
-    type Output = Counter<T, N>;
+    type Item = (T, N);
+    type IntoIter = hash_map::IntoIter<T, N>;
 
-    /// Union
-    ///
-    /// `out = c | d;` -> `out[x] == max(c[x], d[x])`
+    /// Consumes the counter, producing an iterator over `(item, count)` pairs.
     ///
     /// ```rust
     /// # use counter::Counter;
-    /// # use std::collections::HashMap;
-    /// let c = "aaab".chars().collect::<Counter<_>>();
-    /// let d = "abb".chars().collect::<Counter<_>>();
-    ///
-    /// let e = c | d;
-    ///
-    /// let expect = [('a', 3), ('b', 2)].iter().cloned().collect::<HashMap<_, _>>();
-    /// assert_eq!(e.into_map(), expect);
+    /// let counter = "aab".chars().collect::<Counter<_>>();
+    /// let mut pairs = counter.into_iter().collect::<Vec<_>>();
+    /// pairs.sort();
+    /// assert_eq!(pairs, vec![('a', 2), ('b', 1)]);
     /// ```
-    fn bitor(self, rhs: Counter<T, N>) -> Self::Output {
-        use std::cmp::max;
-
-        let mut counter = self;
-        for (key, value) in rhs.map.iter() {
-            let entry = counter.map.entry(key.clone()).or_insert_with(N::zero);
-            *entry = max(&*entry, value).clone();
-        }
-        counter
+    fn into_iter(self) -> Self::IntoIter {
+        self.map.into_iter()
     }
 }
 
-impl<T, N> Deref for Counter<T, N>
+impl<'a, T, N, S> IntoIterator for &'a Counter<T, N, S>
 where
     T: Hash + Eq,
-    N: Clone,
+    S: BuildHasher,
 {
-    type Target = CounterMap<T, N>;
-    fn deref(&self) -> &CounterMap<T, N> {
-        &self.map
+    type Item = (&'a T, &'a N);
+    type IntoIter = hash_map::Iter<'a, T, N>;
+
+    /// Iterate over `(&item, &count)` pairs.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = "aab".chars().collect::<Counter<_>>();
+    /// let mut total = 0;
+    /// for (_, count) in &counter {
+    ///     total += count;
+    /// }
+    /// assert_eq!(total, 3);
+    /// ```
+    fn into_iter(self) -> Self::IntoIter {
+        self.map.iter()
     }
 }
 
-impl<T, N> DerefMut for Counter<T, N>
+impl<'a, T, N, S> IntoIterator for &'a mut Counter<T, N, S>
 where
     T: Hash + Eq,
-    N: Clone,
+    S: BuildHasher,
 {
-    fn deref_mut(&mut self) -> &mut CounterMap<T, N> {
-        &mut self.map
+    type Item = (&'a T, &'a mut N);
+    type IntoIter = hash_map::IterMut<'a, T, N>;
+
+    /// Iterate over `(&item, &mut count)` pairs, allowing the counts to be modified.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mut counter = "aab".chars().collect::<Counter<_>>();
+    /// for (_, count) in &mut counter {
+    ///     *count *= 2;
+    /// }
+    /// assert_eq!(counter.get(&'a'), Some(&4));
+    /// ```
+    fn into_iter(self) -> Self::IntoIter {
+        self.map.iter_mut()
     }
 }
 
-impl<I, T, N> AddAssign<I> for Counter<T, N>
+impl<I, T, N, S> AddAssign<I> for Counter<T, N, S>
 where
     I: IntoIterator<Item = T>,
     T: Hash + Eq,
     N: PartialOrd + AddAssign + SubAssign + Zero + One,
+    S: BuildHasher,
 {
     /// Directly add the counts of the elements of `I` to `self`
     ///
     /// ```rust
     /// # use counter::Counter;
-    /// # use std::collections::HashMap;
+    /// # #[cfg(not(feature = "std"))] extern crate hashbrown;
+    /// # #[cfg(not(feature = "std"))] use hashbrown::HashMap;
+    /// # #[cfg(feature = "std")] use std::collections::HashMap;
     /// let mut counter = Counter::init("abbccc".chars());
     ///
     /// counter += "aeeeee".chars();
@@ -539,11 +3992,12 @@ where
     }
 }
 
-impl<I, T, N> Add<I> for Counter<T, N>
+impl<I, T, N, S> Add<I> for Counter<T, N, S>
 where
     I: IntoIterator<Item = T>,
     T: Hash + Eq,
     N: PartialOrd + AddAssign + SubAssign + Zero + One,
+    S: BuildHasher,
 {
     type Output = Self;
     /// Consume self producing a Counter like self updated with the counts of
@@ -551,7 +4005,9 @@ where
     ///
     /// ```rust
     /// # use counter::Counter;
-    /// # use std::collections::HashMap;
+    /// # #[cfg(not(feature = "std"))] extern crate hashbrown;
+    /// # #[cfg(not(feature = "std"))] use hashbrown::HashMap;
+    /// # #[cfg(feature = "std")] use std::collections::HashMap;
     /// let counter = Counter::init("abbccc".chars());
     ///
     /// let new_counter = counter + "aeeeee".chars();
@@ -565,18 +4021,21 @@ where
     }
 }
 
-impl<I, T, N> SubAssign<I> for Counter<T, N>
+impl<I, T, N, S> SubAssign<I> for Counter<T, N, S>
 where
     I: IntoIterator<Item = T>,
     T: Hash + Eq,
     N: PartialOrd + AddAssign + SubAssign + Zero + One,
+    S: BuildHasher,
 {
     /// Directly subtract the counts of the elements of `I` from `self`,
     /// keeping only items with a value greater than N::zero().
     ///
     /// ```rust
     /// # use counter::Counter;
-    /// # use std::collections::HashMap;
+    /// # #[cfg(not(feature = "std"))] extern crate hashbrown;
+    /// # #[cfg(not(feature = "std"))] use hashbrown::HashMap;
+    /// # #[cfg(feature = "std")] use std::collections::HashMap;
     /// let mut c = "aaab".chars().collect::<Counter<_>>();
     /// c -= "abb".chars();
     ///
@@ -588,11 +4047,12 @@ where
     }
 }
 
-impl<I, T, N> Sub<I> for Counter<T, N>
+impl<I, T, N, S> Sub<I> for Counter<T, N, S>
 where
     I: IntoIterator<Item = T>,
     T: Clone + Hash + Eq,
     N: Clone + PartialOrd + AddAssign + SubAssign + Zero + One,
+    S: BuildHasher,
 {
     type Output = Self;
     /// Consume self producing a Counter like self with the counts of the
@@ -600,7 +4060,9 @@ where
     ///
     /// ```rust
     /// # use counter::Counter;
-    /// # use std::collections::HashMap;
+    /// # #[cfg(not(feature = "std"))] extern crate hashbrown;
+    /// # #[cfg(not(feature = "std"))] use hashbrown::HashMap;
+    /// # #[cfg(feature = "std")] use std::collections::HashMap;
     /// let c = "aaab".chars().collect::<Counter<_>>();
     /// let e = c - "abb".chars();
     ///
@@ -614,38 +4076,47 @@ where
     }
 }
 
-impl<T, N> iter::FromIterator<T> for Counter<T, N>
+impl<T, N, S> iter::FromIterator<T> for Counter<T, N, S>
 where
     T: Hash + Eq,
     N: PartialOrd + AddAssign + SubAssign + Zero + One,
+    S: BuildHasher + Default,
 {
     /// Produce a Counter from an iterator of items. This is called automatically
     /// by `iter.collect()`.
     ///
     /// ```rust
     /// # use counter::Counter;
-    /// # use std::collections::HashMap;
+    /// # #[cfg(not(feature = "std"))] extern crate hashbrown;
+    /// # #[cfg(not(feature = "std"))] use hashbrown::HashMap;
+    /// # #[cfg(feature = "std")] use std::collections::HashMap;
     /// let counter = "abbccc".chars().collect::<Counter<_>>();
     /// let expect = [('a', 1), ('b', 2), ('c', 3)].iter().cloned().collect::<HashMap<_, _>>();
     /// assert_eq!(counter.into_map(), expect);
     /// ```
     ///
     fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
-        Counter::<T, N>::init(iter)
+        let mut counter = Counter::with_hasher(S::default());
+        counter.update(iter);
+        counter
     }
 }
 
-impl<T, N> iter::FromIterator<(T, N)> for Counter<T, N>
+impl<T, N, S> iter::FromIterator<(T, N)> for Counter<T, N, S>
 where
     T: Hash + Eq,
     N: PartialOrd + AddAssign + SubAssign + Zero + One,
+    S: BuildHasher + Default,
 {
     /// `from_iter` creates a counter from `(item, count)` tuples.
     ///
     /// The counts of duplicate items are summed.
+    ///
     /// ```rust
     /// # use counter::Counter;
-    /// # use std::collections::HashMap;
+    /// # #[cfg(not(feature = "std"))] extern crate hashbrown;
+    /// # #[cfg(not(feature = "std"))] use hashbrown::HashMap;
+    /// # #[cfg(feature = "std")] use std::collections::HashMap;
     /// let counter = [('a', 1), ('b', 2), ('c', 3), ('a', 4)].iter()
     ///     .cloned().collect::<Counter<_>>();
     /// let expect = [('a', 5), ('b', 2), ('c', 3)].iter()
@@ -653,19 +4124,255 @@ where
     /// assert_eq!(counter.into_map(), expect);
     /// ```
     fn from_iter<I: IntoIterator<Item = (T, N)>>(iter: I) -> Self {
-        let mut cnt = Counter::new();
+        let mut cnt = Counter::with_hasher(S::default());
+        cnt.extend(iter);
+        cnt
+    }
+}
+
+impl<T, N, S> iter::Sum for Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Zero + AddAssign,
+    S: BuildHasher + Default,
+{
+    /// Add up an iterator of counters.
+    ///
+    /// The largest counter seen so far is used as the accumulator, so its allocation is reused
+    /// and only the entries of the smaller counters are moved into it.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// # #[cfg(not(feature = "std"))] extern crate hashbrown;
+    /// # #[cfg(not(feature = "std"))] use hashbrown::HashMap;
+    /// # #[cfg(feature = "std")] use std::collections::HashMap;
+    /// let counters = vec!["abb", "bcc", "a"]
+    ///     .into_iter()
+    ///     .map(|s| s.chars().collect::<Counter<_>>());
+    /// let total: Counter<_> = counters.sum();
+    /// let expect = [('a', 2), ('b', 3), ('c', 2)].iter().cloned().collect::<HashMap<_, _>>();
+    /// assert_eq!(total.into_map(), expect);
+    /// ```
+    fn sum<I: Iterator<Item = Counter<T, N, S>>>(iter: I) -> Self {
+        let mut total = Counter::default();
+        for counter in iter {
+            total.merge_from(counter);
+        }
+        total
+    }
+}
+
+impl<'a, T, N, S> iter::Sum<&'a Counter<T, N, S>> for Counter<T, N, S>
+where
+    T: Clone + Hash + Eq + 'a,
+    N: Clone + Zero + AddAssign + 'a,
+    S: BuildHasher + Default + 'a,
+{
+    /// Add up an iterator of references to counters.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// # #[cfg(not(feature = "std"))] extern crate hashbrown;
+    /// # #[cfg(not(feature = "std"))] use hashbrown::HashMap;
+    /// # #[cfg(feature = "std")] use std::collections::HashMap;
+    /// let counters = vec!["abb", "bcc", "a"]
+    ///     .into_iter()
+    ///     .map(|s| s.chars().collect::<Counter<_>>())
+    ///     .collect::<Vec<_>>();
+    /// let total: Counter<_> = counters.iter().sum();
+    /// let expect = [('a', 2), ('b', 3), ('c', 2)].iter().cloned().collect::<HashMap<_, _>>();
+    /// assert_eq!(total.into_map(), expect);
+    /// ```
+    fn sum<I: Iterator<Item = &'a Counter<T, N, S>>>(iter: I) -> Self {
+        let mut total = Counter::default();
+        for counter in iter {
+            total += counter;
+        }
+        total
+    }
+}
+
+impl<T, N, S> Extend<T> for Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: PartialOrd + AddAssign + Zero + One,
+    S: BuildHasher,
+{
+    /// Extend a counter with the counts of the elements of an iterator.
+    ///
+    /// This is equivalent to calling `update`.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// # #[cfg(not(feature = "std"))] extern crate hashbrown;
+    /// # #[cfg(not(feature = "std"))] use hashbrown::HashMap;
+    /// # #[cfg(feature = "std")] use std::collections::HashMap;
+    /// let mut counter = "abbccc".chars().collect::<Counter<_>>();
+    /// counter.extend("abc".chars());
+    /// let expect = [('a', 2), ('b', 3), ('c', 4)].iter().cloned().collect::<HashMap<_, _>>();
+    /// assert_eq!(counter.into_map(), expect);
+    /// ```
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.update(iter);
+    }
+}
+
+impl<T, N, S> Extend<(T, N)> for Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: AddAssign + Zero,
+    S: BuildHasher,
+{
+    /// Extend a counter with `(item, count)` tuples.
+    ///
+    /// The counts of duplicate items are summed.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// # #[cfg(not(feature = "std"))] extern crate hashbrown;
+    /// # #[cfg(not(feature = "std"))] use hashbrown::HashMap;
+    /// # #[cfg(feature = "std")] use std::collections::HashMap;
+    /// let mut counter = "abbccc".chars().collect::<Counter<_>>();
+    /// counter.extend(vec![('a', 1), ('d', 2), ('a', 3)]);
+    /// let expect = [('a', 5), ('b', 2), ('c', 3), ('d', 2)].iter()
+    ///     .cloned().collect::<HashMap<_, _>>();
+    /// assert_eq!(counter.into_map(), expect);
+    /// ```
+    fn extend<I: IntoIterator<Item = (T, N)>>(&mut self, iter: I) {
         for (item, item_count) in iter.into_iter() {
-            let entry = cnt.map.entry(item).or_insert_with(N::zero);
+            let entry = self.map.entry(item).or_insert_with(N::zero);
             *entry += item_count;
         }
-        cnt
     }
 }
 
+impl<T, N, S> Extend<Counter<T, N, S>> for Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: AddAssign + Zero,
+    S: BuildHasher,
+{
+    /// Add the counts of each of the counters to this one, as with
+    /// [`merge_from`](struct.Counter.html#method.merge_from).
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let shards = vec![
+    ///     "aab".chars().collect::<Counter<_>>(),
+    ///     "bc".chars().collect::<Counter<_>>(),
+    /// ];
+    /// let mut global = Counter::<char>::new();
+    /// global.extend(shards);
+    /// assert_eq!((global[&'a'], global[&'b'], global[&'c']), (2, 2, 1));
+    /// ```
+    fn extend<I: IntoIterator<Item = Counter<T, N, S>>>(&mut self, iter: I) {
+        for counter in iter {
+            self.merge_from(counter);
+        }
+    }
+}
+
+impl<T, N, S> From<HashMap<T, N, S>> for Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Zero,
+    S: BuildHasher,
+{
+    /// Wrap a map of items to counts in a counter, keeping its hasher.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// # #[cfg(not(feature = "std"))] extern crate hashbrown;
+    /// # #[cfg(not(feature = "std"))] use hashbrown::HashMap;
+    /// # #[cfg(feature = "std")] use std::collections::HashMap;
+    /// let map: HashMap<_, usize> = [('a', 1), ('b', 2)].iter().cloned().collect();
+    /// let counter = Counter::from(map);
+    /// assert_eq!(counter[&'b'], 2);
+    /// ```
+    ///
+    /// A map can't be added to or subtracted from a counter with an operator directly, because
+    /// that would overlap with adding an iterable of items, like `counter + "abc".chars()`.
+    /// Wrap it first; this doesn't copy the map.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// # #[cfg(not(feature = "std"))] extern crate hashbrown;
+    /// # #[cfg(not(feature = "std"))] use hashbrown::HashMap;
+    /// # #[cfg(feature = "std")] use std::collections::HashMap;
+    /// let mut counter = "aaab".chars().collect::<Counter<_>>();
+    /// let map: HashMap<_, usize> = [('a', 1), ('c', 2)].iter().cloned().collect();
+    /// counter += Counter::from(map.clone());
+    /// assert_eq!((counter[&'a'], counter[&'c']), (4, 2));
+    /// counter -= Counter::from(map);
+    /// assert_eq!((counter[&'a'], counter[&'c']), (3, 0));
+    /// ```
+    fn from(map: HashMap<T, N, S>) -> Counter<T, N, S> {
+        Counter {
+            map,
+            zero: N::zero(),
+        }
+    }
+}
+
+impl<T, N, S> From<Counter<T, N, S>> for HashMap<T, N, S>
+where
+    T: Hash + Eq,
+    S: BuildHasher,
+{
+    /// Unwrap the map of items to counts, like [`into_map`](struct.Counter.html#method.into_map).
+    fn from(counter: Counter<T, N, S>) -> HashMap<T, N, S> {
+        counter.map
+    }
+}
+
+impl<T, N, S> From<Vec<(T, N)>> for Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: AddAssign + Zero,
+    S: BuildHasher + Default,
+{
+    /// Create a counter from `(item, count)` pairs, summing the counts of duplicate items.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter: Counter<_> = vec![("a", 1), ("b", 2), ("a", 3)].into();
+    /// assert_eq!(counter[&"a"], 4);
+    /// assert_eq!(counter.len(), 2);
+    /// ```
+    fn from(pairs: Vec<(T, N)>) -> Counter<T, N, S> {
+        let mut counter = Counter::with_capacity_and_hasher(pairs.len(), S::default());
+        counter.extend(pairs);
+        counter
+    }
+}
+
+/// Counts the items of an iterator by a key derived from each one.
+///
+/// This trait is implemented for every `Iterator`.
+///
+/// ```rust
+/// # use counter::CountsBy;
+/// let lengths = "the quick brown fox".split(' ').counts_by(|word| word.len());
+/// assert_eq!(lengths[&3], 2);
+/// assert_eq!(lengths[&5], 2);
+/// ```
+pub trait CountsBy: Iterator + Sized {
+    /// Count the keys which `key` derives from the items of this iterator
+    fn counts_by<K, F>(self, key: F) -> Counter<K>
+    where
+        K: Hash + Eq,
+        F: FnMut(Self::Item) -> K,
+    {
+        Counter::init_by_key(self, key)
+    }
+}
+
+impl<I> CountsBy for I where I: Iterator {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::collections::HashMap;
+    use std::string::{String, ToString};
 
     #[test]
     fn test_creation() {
@@ -680,6 +4387,122 @@ mod tests {
         assert!(counter.map == expected);
     }
 
+    #[test]
+    fn test_owned_keys() {
+        fn word_counts(text: String) -> Counter<String> {
+            text.split_whitespace().map(str::to_string).collect()
+        }
+
+        let counter = word_counts("a b a c a".to_string());
+        let expected = hashmap!{
+            "a".to_string() => 3,
+            "b".to_string() => 1,
+            "c".to_string() => 1,
+        };
+        assert!(counter.map == expected);
+    }
+
+    #[test]
+    fn test_custom_hasher() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::BuildHasherDefault;
+        type Hasher = BuildHasherDefault<DefaultHasher>;
+
+        let mut counter: Counter<char, usize, Hasher> = Counter::with_hasher(Hasher::default());
+        counter.update("abbccc".chars());
+        let other: Counter<char, usize, Hasher> = "bccddd".chars().collect();
+        let out: Counter<char, usize, Hasher> = (counter.clone() & other.clone()) | other;
+        let expected = hashmap!{
+            'b' => 1,
+            'c' => 2,
+            'd' => 3,
+        };
+        assert!(out.map.into_iter().collect::<HashMap<_, _>>() == expected);
+    }
+
+    #[test]
+    fn test_conversions_keep_hasher() {
+        use std::collections::hash_map::DefaultHasher;
+
+        #[derive(Clone, Debug, PartialEq)]
+        struct Seeded(u64);
+
+        impl BuildHasher for Seeded {
+            type Hasher = DefaultHasher;
+
+            fn build_hasher(&self) -> DefaultHasher {
+                let mut hasher = DefaultHasher::new();
+                hasher.write_u64(self.0);
+                hasher
+            }
+        }
+
+        let mut counter = Counter::<u8, usize, _>::with_hasher(Seeded(7));
+        counter.update(vec![1, 2, 2, 30]);
+        assert_eq!(*counter.clone().map_keys(|n| n % 2).map.hasher(), Seeded(7));
+        assert_eq!(*counter.clone().bucketize(&[10.0]).map.hasher(), Seeded(7));
+        let doubled = counter.clone().map_counts_into(|count| count * 2);
+        assert_eq!(*doubled.map.hasher(), Seeded(7));
+        assert_eq!(*counter.clone().into_probabilities().hasher(), Seeded(7));
+    }
+
+    #[test]
+    fn test_map_access() {
+        let mut counter = Counter::<_>::init("abbccc".chars());
+        assert_eq!(counter.len(), 3);
+        assert!(!counter.is_empty());
+        assert_eq!(counter.get(&'c'), Some(&3));
+        assert_eq!(counter.get(&'d'), None);
+        assert!(counter.contains_key(&'a'));
+        assert!(!counter.contains_key(&'d'));
+
+        let mut keys = counter.keys().cloned().collect::<Vec<_>>();
+        keys.sort();
+        assert_eq!(keys, vec!['a', 'b', 'c']);
+        assert_eq!(counter.values().sum::<usize>(), 6);
+        assert_eq!(counter.iter().count(), 3);
+
+        counter.clear();
+        assert_eq!(counter.len(), 0);
+        assert!(counter.is_empty());
+    }
+
+    #[test]
+    fn test_retain_drain_extract_if() {
+        let mut counter = Counter::<_>::init("abbcccdddd".chars());
+        counter.retain(|&item, _| item != 'a');
+        let mut extracted = counter.extract_if(|_, count| *count > 3).collect::<Vec<_>>();
+        extracted.sort();
+        assert_eq!(extracted, vec![('d', 4)]);
+
+        let mut drained = counter.drain().collect::<Vec<_>>();
+        drained.sort();
+        assert_eq!(drained, vec![('b', 2), ('c', 3)]);
+        assert!(counter.map.is_empty());
+    }
+
+    #[test]
+    fn test_remove_and_set_count() {
+        let mut counter = Counter::<_>::init("abbccc".chars());
+        assert_eq!(counter.remove(&'a'), Some(1));
+        assert_eq!(counter.remove(&'z'), None);
+        assert_eq!(counter.set_count('b', 0), Some(2));
+        assert_eq!(counter.set_count('z', 0), None);
+        assert_eq!(counter.set_count('c', 1), Some(3));
+        let expected = hashmap!{
+            'c' => 1,
+        };
+        assert!(counter.map == expected);
+    }
+
+    #[test]
+    fn test_count_of_borrowed() {
+        let counter = Counter::<Vec<u8>>::init(vec![b"ab".to_vec(), b"ab".to_vec(), b"c".to_vec()]);
+        assert_eq!(counter.count_of(&b"ab"[..]), 2);
+        assert_eq!(counter.count_of(&b"c"[..]), 1);
+        assert_eq!(counter.count_of(&b"d"[..]), 0);
+    }
+
     #[test]
     fn test_update() {
         let mut counter = Counter::init("abbccc".chars());
@@ -700,6 +4523,36 @@ mod tests {
         assert!(counter.map == expected);
     }
 
+    #[test]
+    fn test_update_from_pairs() {
+        let mut counter = Counter::init("abbccc".chars());
+        counter.update_from_pairs(vec![('a', 2), ('e', 5)]);
+        counter.update_from_pairs(Counter::init("be".chars()));
+        let expected = hashmap!{
+            'a' => 3,
+            'b' => 3,
+            'c' => 3,
+            'e' => 6,
+        };
+        assert!(counter.map == expected);
+    }
+
+    #[test]
+    fn test_increment() {
+        let mut counter = Counter::init("abbccc".chars());
+        assert_eq!(counter.increment('a'), 2);
+        assert_eq!(counter.increment('d'), 1);
+        assert_eq!(counter.add_count('c', 4), 7);
+        assert_eq!(counter.add_count('e', 0), 0);
+        let expected = hashmap!{
+            'a' => 2,
+            'b' => 2,
+            'c' => 7,
+            'd' => 1,
+        };
+        assert!(counter.map == expected);
+    }
+
     #[test]
     fn test_add_update_iterable() {
         let mut counter = Counter::init("abbccc".chars());
@@ -752,6 +4605,44 @@ mod tests {
         assert!(counter.map == expected);
     }
 
+    #[test]
+    fn test_subtract_counter() {
+        let mut counter = Counter::init("abbccc".chars());
+        let other = Counter::init("bbccddd".chars());
+        counter.subtract_counter(&other);
+        let expected = hashmap!{
+            'a' => 1,
+            'c' => 1,
+        };
+        assert!(counter.map == expected);
+        assert_eq!(other, Counter::init("bbccddd".chars()));
+    }
+
+    #[test]
+    fn test_signed_counts() {
+        let mut counter: Counter<_, i64> = Counter::init("abbccc".chars());
+        counter.subtract_signed("bbbbcccddd".chars());
+        let expected = hashmap!{
+            'a' => 1,
+            'b' => -2,
+            'd' => -3,
+        };
+        assert!(counter.map == expected);
+
+        let negated = -counter.clone();
+        let expected = hashmap!{
+            'b' => 2,
+            'd' => 3,
+        };
+        assert!(negated.map == expected);
+
+        counter.keep_positive();
+        let expected = hashmap!{
+            'a' => 1,
+        };
+        assert!(counter.map == expected);
+    }
+
     #[test]
     fn test_sub_update_iterable() {
         let mut counter = Counter::init("abbccc".chars());
@@ -796,27 +4687,90 @@ mod tests {
     }
 
     #[test]
-    fn test_most_common_tiebreaker() {
-        let counter = Counter::init("eaddbbccc".chars());
-        let by_common = counter.most_common_tiebreaker(|&a, &b| a.cmp(&b));
-        let expected = vec![('c', 3), ('b', 2), ('d', 2), ('a', 1), ('e', 1)];
-        assert!(by_common == expected);
+    fn test_most_common_tiebreaker() {
+        let counter = Counter::init("eaddbbccc".chars());
+        let by_common = counter.most_common_tiebreaker(|&a, &b| a.cmp(&b));
+        let expected = vec![('c', 3), ('b', 2), ('d', 2), ('a', 1), ('e', 1)];
+        assert!(by_common == expected);
+    }
+
+    #[test]
+    fn test_most_common_tiebreaker_reversed() {
+        let counter = Counter::init("eaddbbccc".chars());
+        let by_common = counter.most_common_tiebreaker(|&a, &b| b.cmp(&a));
+        let expected = vec![('c', 3), ('d', 2), ('b', 2), ('e', 1), ('a', 1)];
+        assert!(by_common == expected);
+    }
+
+    #[test]
+    fn test_most_common_ordered() {
+        let counter = Counter::init("eaddbbccc".chars());
+        let by_common = counter.most_common_ordered();
+        let expected = vec![('c', 3), ('b', 2), ('d', 2), ('a', 1), ('e', 1)];
+        assert!(by_common == expected);
+    }
+
+    #[test]
+    fn test_most_common_n() {
+        let counter = Counter::init("abbcccdddd".chars());
+        assert_eq!(counter.most_common_n(0), vec![]);
+        assert_eq!(counter.most_common_n(2), vec![('d', 4), ('c', 3)]);
+        assert_eq!(counter.most_common_n(10), counter.most_common());
+    }
+
+    #[test]
+    fn test_most_common_iter() {
+        let counter = Counter::init("abbcccdddd".chars());
+        let mut mc = counter.most_common_iter();
+        assert_eq!(mc.len(), 4);
+        assert_eq!(mc.next(), Some((&'d', 4)));
+        assert_eq!(mc.len(), 3);
+        assert_eq!(
+            mc.collect::<Vec<_>>(),
+            vec![(&'c', 3), (&'b', 2), (&'a', 1)]
+        );
+    }
+
+    #[test]
+    fn test_most_common_ordered_is_deterministic() {
+        let expected = vec![('a', 2), ('b', 2), ('c', 2), ('d', 2), ('e', 2), ('f', 2)];
+        for _ in 0..10 {
+            let counter = Counter::<_>::init("fedcbaabcdef".chars());
+            assert_eq!(counter.most_common_ordered(), expected);
+        }
     }
 
     #[test]
-    fn test_most_common_tiebreaker_reversed() {
-        let counter = Counter::init("eaddbbccc".chars());
-        let by_common = counter.most_common_tiebreaker(|&a, &b| b.cmp(&a));
-        let expected = vec![('c', 3), ('d', 2), ('b', 2), ('e', 1), ('a', 1)];
-        assert!(by_common == expected);
+    fn test_least_common() {
+        let counter = Counter::init("abbcccdddd".chars());
+        let expected = vec![('a', 1), ('b', 2), ('c', 3), ('d', 4)];
+        assert_eq!(counter.least_common(), expected);
+        assert_eq!(counter.least_common_n(0), vec![]);
+        assert_eq!(counter.least_common_n(2), vec![('a', 1), ('b', 2)]);
+        assert_eq!(counter.least_common_n(10), expected);
     }
 
     #[test]
-    fn test_most_common_ordered() {
-        let counter = Counter::init("eaddbbccc".chars());
-        let by_common = counter.most_common_ordered();
-        let expected = vec![('c', 3), ('b', 2), ('d', 2), ('a', 1), ('e', 1)];
-        assert!(by_common == expected);
+    fn test_elements() {
+        let counter = Counter::<_>::init("abbccc".chars());
+        let mut elements = counter.elements().cloned().collect::<Vec<_>>();
+        elements.sort();
+        assert_eq!(elements, vec!['a', 'b', 'b', 'c', 'c', 'c']);
+        assert_eq!(Counter::<_>::init(counter.elements()).into_map().len(), 3);
+        assert_eq!(Counter::<char>::new().elements().next(), None);
+    }
+
+    #[test]
+    fn test_total() {
+        let counter = Counter::<_>::init("abbccc".chars());
+        assert_eq!(counter.total(), 6);
+        assert_eq!(counter.checked_total(), Some(6));
+        assert_eq!(Counter::<char>::new().total(), 0);
+
+        let counter = vec![('a', usize::MAX), ('b', 1)]
+            .into_iter()
+            .collect::<Counter<char>>();
+        assert_eq!(counter.checked_total(), None);
     }
 
     #[test]
@@ -859,6 +4813,41 @@ mod tests {
         assert!(out == expected);
     }
 
+    #[test]
+    fn test_intersection_assign() {
+        let mut d = Counter::<_>::init("abbccc".chars());
+        d &= Counter::init("bccddd".chars());
+        let expected = Counter::init("bcc".chars());
+        assert!(d == expected);
+    }
+
+    #[test]
+    fn test_union_assign() {
+        let mut d = Counter::<_>::init("abbccc".chars());
+        d |= Counter::init("bccddd".chars());
+        let expected = Counter::init("abbcccddd".chars());
+        assert!(d == expected);
+    }
+
+    #[test]
+    fn test_reference_operators() {
+        let d = Counter::<_>::init("abbccc".chars());
+        let e = Counter::<_>::init("bccddd".chars());
+
+        assert!(&d + &e == Counter::init("abbbcccccddd".chars()));
+        assert!(&d - &e == Counter::init("abc".chars()));
+        assert!(&d & &e == Counter::init("bcc".chars()));
+        assert!(&d | &e == Counter::init("abbcccddd".chars()));
+
+        assert!(d.clone() + &e == &d + e.clone());
+        assert!(d.clone() - &e == &d - e.clone());
+        assert!(d.clone() & &e == &d & e.clone());
+        assert!(d.clone() | &e == &d | e.clone());
+
+        assert!(d == Counter::init("abbccc".chars()));
+        assert!(e == Counter::init("bccddd".chars()));
+    }
+
     #[test]
     fn test_delete_key_from_backing_map() {
         let mut counter = Counter::<_>::init("aa-bb-cc".chars());
@@ -898,6 +4887,101 @@ mod tests {
         assert_eq!(counter.map, expected);
     }
 
+    #[test]
+    fn test_extend_simple() {
+        let mut counter = "abbccc".chars().collect::<Counter<_>>();
+        counter.extend("bccddd".chars());
+        let expected = hashmap!{
+            'a' => 1,
+            'b' => 3,
+            'c' => 5,
+            'd' => 3,
+        };
+        assert!(counter.map == expected);
+    }
+
+    #[test]
+    fn test_extend_tuple() {
+        let mut counter = "abbccc".chars().collect::<Counter<_>>();
+        counter.extend(vec![('a', 1), ('d', 2), ('a', 3)]);
+        let expected = hashmap!{
+            'a' => 5,
+            'b' => 2,
+            'c' => 3,
+            'd' => 2,
+        };
+        assert!(counter.map == expected);
+    }
+
+    #[test]
+    fn test_into_iter() {
+        let mut counter = "abbccc".chars().collect::<Counter<_>>();
+        for (_, count) in &mut counter {
+            *count += 1;
+        }
+        let mut pairs = (&counter)
+            .into_iter()
+            .map(|(&item, &count)| (item, count))
+            .collect::<Vec<_>>();
+        pairs.sort();
+        assert_eq!(pairs, vec![('a', 2), ('b', 3), ('c', 4)]);
+
+        let mut pairs = counter.into_iter().collect::<Vec<_>>();
+        pairs.sort();
+        assert_eq!(pairs, vec![('a', 2), ('b', 3), ('c', 4)]);
+    }
+
+    #[test]
+    fn test_index() {
+        let mut counter = "abbccc".chars().collect::<Counter<_>>();
+        assert_eq!(counter[&'b'], 2);
+        assert_eq!(counter[&'z'], 0);
+        assert!(!counter.map.contains_key(&'z'));
+
+        counter[&'z'] += 2;
+        counter[&'a'] += 1;
+        let expected = hashmap!{
+            'a' => 2,
+            'b' => 2,
+            'c' => 3,
+            'z' => 2,
+        };
+        assert!(counter.map == expected);
+    }
+
+    #[test]
+    fn test_index_borrowed() {
+        let mut counter = Counter::<String>::init(vec!["a".to_string()]);
+        counter["b"] += 1;
+        assert_eq!(counter["a"], 1);
+        assert_eq!(counter["b"], 1);
+        assert_eq!(counter["c"], 0);
+    }
+
+    #[test]
+    fn test_sum() {
+        let counters = vec![
+            Counter::<_>::init("ab".chars()),
+            Counter::init("bccddd".chars()),
+            Counter::init("a".chars()),
+        ];
+        let expected = Counter::init("aabbccddd".chars());
+        assert!(counters.iter().sum::<Counter<_>>() == expected);
+        assert!(counters.into_iter().sum::<Counter<_>>() == expected);
+        assert!(Vec::<Counter<char>>::new().into_iter().sum::<Counter<_>>() == Counter::new());
+
+        // summing owned counters doesn't need to clone their items
+        #[derive(Debug, Hash, PartialEq, Eq)]
+        struct Token(u8);
+
+        let counters = vec![
+            Counter::<_>::init(vec![Token(1)]),
+            Counter::init(vec![Token(1), Token(2), Token(2)]),
+        ];
+        let total = counters.into_iter().sum::<Counter<_>>();
+        assert_eq!((total[&Token(1)], total[&Token(2)]), (2, 2));
+    }
+
     #[test]
     fn test_count_minimal_type() {
         #[derive(Debug, Hash, PartialEq, Eq)]
@@ -955,4 +5039,570 @@ mod tests {
         };
         assert!(counter.map == expected);
     }
+
+    #[test]
+    fn test_init_by_key() {
+        let words = vec!["apple", "avocado", "banana", "blueberry", "cherry"];
+        let counter = Counter::<_>::init_by_key(&words, |word| word.chars().next().unwrap());
+        let expected = hashmap! { 'a' => 2, 'b' => 2, 'c' => 1 };
+        assert!(counter.map == expected);
+
+        let mut counter = words.iter().counts_by(|word| word.len());
+        counter.update_by_key(vec!["fig"], str::len);
+        let expected = hashmap! { 5 => 1, 7 => 1, 6 => 2, 9 => 1, 3 => 1 };
+        assert!(counter.map == expected);
+    }
+
+    #[test]
+    fn test_char_ngrams() {
+        let counter = Counter::<_>::char_ngrams("añaña", 3);
+        let expected = hashmap! { "aña" => 2, "ñañ" => 1 };
+        assert!(counter.map == expected);
+        assert!(Counter::<_>::char_ngrams("ab", 3).is_empty());
+        assert_eq!(Counter::<_>::char_ngrams("ab", 2).len(), 1);
+    }
+
+    #[test]
+    fn test_word_ngrams() {
+        let counter = Counter::<_>::word_ngrams(vec![1, 2, 1, 2, 1], 2);
+        let expected = hashmap! { vec![1, 2] => 2, vec![2, 1] => 2 };
+        assert!(counter.map == expected);
+        assert!(Counter::<Vec<u8>>::word_ngrams(vec![1], 2).is_empty());
+    }
+
+    #[test]
+    fn test_display() {
+        let counter = Counter::<_>::init(vec!["b", "a", "c", "a"]);
+        assert_eq!(format!("{}", counter), r#"Counter({"a": 2, "b": 1, "c": 1})"#);
+        assert_eq!(Counter::<u8>::new().to_string(), "Counter({})");
+    }
+
+    #[test]
+    fn test_eq_ignores_zero_counts() {
+        let mut a = Counter::<_, i32>::init("abc".chars());
+        let b = Counter::<_, i32>::init("cba".chars());
+        assert_eq!(a, b);
+
+        a.insert('z', 0);
+        assert_eq!(a, b);
+        assert_eq!(b, a);
+
+        a.insert('z', -1);
+        assert_ne!(a, b);
+        assert_ne!(b, a);
+    }
+
+    #[test]
+    fn test_hash_matches_eq() {
+        use std::collections::hash_map::DefaultHasher;
+
+        fn hash_of(counter: &Counter<char, i32>) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            counter.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let mut a = Counter::<_, i32>::init("mississippi".chars());
+        let b = Counter::<_, i32>::init("ppiiiissss".chars());
+        assert_ne!(a, b);
+        assert_ne!(hash_of(&a), hash_of(&b));
+
+        let c = "imsisispips".chars().collect::<Counter<_, i32>>();
+        assert_eq!(a, c);
+        assert_eq!(hash_of(&a), hash_of(&c));
+
+        a.insert('z', 0);
+        assert_eq!(hash_of(&a), hash_of(&c));
+    }
+
+    #[test]
+    fn test_map_and_vec_conversions() {
+        let map: HashMap<&str, usize> = hashmap! { "a" => 2, "b" => 1 };
+        let counter = Counter::from(map.clone());
+        assert_eq!(counter, Counter::init(vec!["a", "b", "a"]));
+        assert!(HashMap::from(counter) == map);
+
+        let counter: Counter<_, i32> = vec![('x', 2), ('y', -1), ('x', -2)].into();
+        assert!(counter.map == hashmap! { 'x' => 0, 'y' => -1 });
+    }
+
+    #[test]
+    fn test_from_pairs() {
+        let shards = vec![("a", 3), ("b", 2), ("a", -3), ("c", 0), ("b", 1)];
+        let counter = Counter::<_, i32>::from_pairs(shards.clone());
+        assert!(counter.map == hashmap! { "b" => 3 });
+        assert_eq!(Counter::try_from_pairs(shards).unwrap(), counter);
+
+        let error = Counter::<_, i8>::try_from_pairs(vec![('x', 1), ('y', 100), ('y', 100)])
+            .unwrap_err();
+        assert_eq!(error.into_item(), 'y');
+        assert_eq!(
+            OverflowError::new('y').to_string(),
+            "the count of 'y' overflowed"
+        );
+    }
+
+    #[test]
+    fn test_div_counts() {
+        let counts = vec![('a', 9), ('b', 6), ('c', 5), ('d', 1)];
+        let divide = |rounding| {
+            let mut counter = Counter::<_, u32>::from_pairs(counts.clone());
+            counter.div_counts(4, rounding);
+            counter.map
+        };
+        assert!(divide(Rounding::Floor) == hashmap! { 'a' => 2, 'b' => 1, 'c' => 1 });
+        assert!(divide(Rounding::Round) == hashmap! { 'a' => 2, 'b' => 2, 'c' => 1 });
+        assert!(
+            divide(Rounding::Ceil) == hashmap! { 'a' => 3, 'b' => 2, 'c' => 2, 'd' => 1 }
+        );
+    }
+
+    #[test]
+    fn test_mul_counts() {
+        let mut counter = Counter::<_, u8>::init("abb".chars()) * 4;
+        assert!(counter.map == hashmap! { 'a' => 4, 'b' => 8 });
+        counter *= 0;
+        assert!(counter.is_empty());
+    }
+
+    #[test]
+    fn test_checked_add() {
+        let mut counter = Counter::<_, i8>::new();
+        assert_eq!(counter.checked_add('a', 0), Ok(0));
+        assert!(counter.is_empty());
+        assert_eq!(counter.checked_add('a', 127), Ok(127));
+        assert_eq!(counter.checked_add('a', -27), Ok(100));
+        assert_eq!(counter.checked_add('a', 28), Err(OverflowError::new('a')));
+        assert_eq!(counter.checked_total(), Some(100));
+
+        counter.checked_update(vec!['b'; 27]).unwrap();
+        assert_eq!(counter.checked_update(vec!['b']), Ok(()));
+        assert_eq!(counter.checked_total(), None);
+    }
+
+    #[test]
+    fn test_saturating_add() {
+        let mut counter = Counter::<_, i8>::new();
+        assert_eq!(counter.saturating_add('a', 0), 0);
+        assert!(counter.is_empty());
+        assert_eq!(counter.saturating_add('a', 100), 100);
+        assert_eq!(counter.saturating_add('a', 100), 127);
+        assert_eq!(counter.saturating_add('a', -128), -1);
+
+        counter.saturating_update(vec!['b'; 200]);
+        assert!(counter.map == hashmap! { 'a' => -1, 'b' => 127 });
+    }
+
+    #[test]
+    fn test_subset_and_disjoint() {
+        let a = Counter::<_, i32>::from_pairs(vec![('a', 1), ('b', 2)]);
+        let b = Counter::<_, i32>::from_pairs(vec![('a', 1), ('b', 3), ('c', 1)]);
+        assert!(a.is_subset(&b));
+        assert!(b.is_superset(&a));
+        assert!(!b.is_subset(&a));
+        assert!(a.is_subset(&a));
+
+        let mut negative = a.clone();
+        negative.insert('z', -1);
+        assert!(negative.is_subset(&a));
+        assert!(!a.is_subset(&negative));
+
+        let mut c = Counter::<_, i32>::from_pairs(vec![('c', 4)]);
+        assert!(a.is_disjoint(&c));
+        assert!(!b.is_disjoint(&c));
+        c.insert('a', 0);
+        assert!(a.is_disjoint(&c));
+        assert!(c.is_disjoint(&a));
+    }
+
+    #[test]
+    fn test_partial_ord_inclusion() {
+        let mut a = Counter::<_>::init("aab".chars());
+        let b = Counter::<_>::init("aabc".chars());
+        assert_eq!(a.partial_cmp(&b), Some(Ordering::Less));
+        assert_eq!(b.partial_cmp(&a), Some(Ordering::Greater));
+        assert!(a <= b && a < b && !a.gt(&b));
+
+        a.insert('d', 0);
+        assert_eq!(a.partial_cmp(&a.clone()), Some(Ordering::Equal));
+        assert!(a < b);
+
+        a.insert('d', 1);
+        assert_eq!(a.partial_cmp(&b), None);
+        assert!(!a.le(&b) && !a.ge(&b));
+    }
+
+    #[test]
+    fn test_symmetric_difference() {
+        let mut a = Counter::<_, i32>::from_pairs(vec![('a', 3), ('b', -1), ('c', 2)]);
+        a.insert('e', 0);
+        let b = Counter::<_, i32>::from_pairs(vec![('a', 1), ('b', 1), ('c', 2), ('d', -4)]);
+        let expected = hashmap! { 'a' => 2, 'b' => 2, 'd' => 4 };
+        assert!(a.symmetric_difference(&b).map == expected);
+        assert!(b.symmetric_difference(&a).map == expected);
+        assert!(a.symmetric_difference(&a).is_empty());
+    }
+
+    #[test]
+    fn test_merge_with() {
+        let a = Counter::<_>::init("aaabc".chars());
+        let b = Counter::<_>::init("abbd".chars());
+
+        let mut calls = 0;
+        let max = a.merge_with(&b, |&x, &y| {
+            calls += 1;
+            x.max(y)
+        });
+        assert_eq!(calls, 4);
+        assert_eq!(max, &a | &b);
+        assert_eq!(a.merge_with(&b, |&x, &y| x.min(y)), &a & &b);
+
+        let only_a = a.merge_with(&b, |&x, &y| if y == 0 { x } else { 0 });
+        assert!(only_a.map == hashmap! { 'c' => 1 });
+    }
+
+    #[test]
+    fn test_union_and_intersection_all() {
+        let shards = vec!["abcc", "bccc", "cd", "cccc"]
+            .into_iter()
+            .map(|s| s.chars().collect::<Counter<_>>())
+            .collect::<Vec<_>>();
+        let pairwise_union = shards.iter().fold(Counter::new(), |union, c| &union | c);
+        assert_eq!(Counter::union_all(shards.clone()), pairwise_union);
+
+        let intersection = Counter::intersection_all(shards.clone());
+        assert!(intersection.map == hashmap! { 'c' => 1 });
+        assert_eq!(Counter::intersection_all(shards[1..].to_vec()), Counter::init(vec!['c']));
+
+        let empty = Vec::<Counter<char>>::new();
+        assert!(Counter::union_all(empty.clone()).is_empty());
+        assert!(Counter::intersection_all(empty).is_empty());
+    }
+
+    #[test]
+    fn test_map_keys() {
+        let timestamps = Counter::<u32>::init(vec![3600, 3601, 7300, 7199, 10]);
+        let hours = timestamps.map_keys(|seconds| seconds / 3600);
+        assert!(hours.map == hashmap! { 0 => 1, 1 => 3, 2 => 1 });
+    }
+
+    #[test]
+    fn test_map_counts() {
+        let mut counter = Counter::<_, i32>::from_pairs(vec![('a', 1), ('b', -2), ('c', 3)]);
+        counter.map_counts(|count| count - 1);
+        assert!(counter.map == hashmap! { 'b' => -3, 'c' => 2 });
+
+        let halves = counter.map_counts_into(|count| f64::from(count) / 2.0);
+        assert!(halves.map == hashmap! { 'b' => -1.5, 'c' => 1.0 });
+    }
+
+    #[test]
+    fn test_split_off_below() {
+        let mut counter = Counter::<_, i32>::from_pairs(vec![('a', 5), ('b', 2), ('c', -1)]);
+        let rare = counter.split_off_below(2);
+        assert!(counter.map == hashmap! { 'a' => 5, 'b' => 2 });
+        assert!(rare.map == hashmap! { 'c' => -1 });
+        assert!(counter.split_off_below(0).is_empty());
+    }
+
+    #[test]
+    fn test_prune_below() {
+        let mut counter = Counter::<_, f64>::from_pairs(vec![('a', 0.5), ('b', 0.25), ('c', 0.25)]);
+        assert_eq!(counter.prune_below(0.3), 0.5);
+        assert!(counter.map == hashmap! { 'a' => 0.5 });
+        assert_eq!(counter.prune_below(0.1), 0.0);
+    }
+
+    #[test]
+    fn test_probabilities() {
+        let counter = Counter::<_, u64>::init("aabbbbcc".chars());
+        let probabilities = counter.probabilities();
+        assert!(probabilities == hashmap! { &'a' => 0.25, &'b' => 0.5, &'c' => 0.25 });
+        assert_eq!(probabilities.values().sum::<f64>(), 1.0);
+        drop(probabilities);
+        assert!(counter.into_probabilities() == hashmap! { 'a' => 0.25, 'b' => 0.5, 'c' => 0.25 });
+
+        assert!(Counter::<char>::new().into_probabilities().is_empty());
+    }
+
+    #[test]
+    fn test_smoothed_probabilities() {
+        let counter = Counter::<_>::init("aaab".chars());
+        let smoothed = counter.smoothed_probabilities(0.5, 4);
+        assert!(smoothed == hashmap! { &'a' => 0.583_333_333_333_333_4, &'b' => 0.25 });
+        let unseen = counter.smoothed_prob(&'c', 0.5, 4);
+        assert_eq!(unseen, 0.5 / 6.0);
+        assert!((smoothed.values().sum::<f64>() + 2.0 * unseen - 1.0).abs() < 1e-12);
+
+        assert_eq!(counter.smoothed_prob(&'a', 0.0, 4), 0.75);
+        assert_eq!(Counter::<char>::new().smoothed_prob(&'a', 1.0, 2), 0.5);
+    }
+
+    #[test]
+    fn test_good_turing() {
+        // n(1) = 3, n(2) = 2, n(3) = 1
+        let counter = Counter::<_>::init("abcddeefff".chars());
+        assert!(counter.count_of_counts().map == hashmap! { 1 => 3, 2 => 2, 3 => 1 });
+
+        let estimate = counter.good_turing();
+        assert_eq!(estimate.unseen_probability, 0.3);
+        // adjusted counts: 2 * 2 / 3 for n(1), 3 * 1 / 2 for n(2), and 3 for n(3)
+        let scale = 0.7 / (3.0 * 4.0 / 3.0 + 2.0 * 1.5 + 3.0);
+        for &(item, adjusted) in &[('a', 4.0 / 3.0), ('d', 1.5), ('f', 3.0)] {
+            assert!((estimate.probabilities[&item] - adjusted * scale).abs() < 1e-12);
+        }
+        let total = estimate.probabilities.values().sum::<f64>() + estimate.unseen_probability;
+        assert!((total - 1.0).abs() < 1e-12);
+
+        let empty = Counter::<char>::new();
+        let empty = empty.good_turing();
+        assert!(empty.probabilities.is_empty());
+        assert_eq!(empty.unseen_probability, 0.0);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_entropy() {
+        let mut counter = Counter::<_, i32>::init("abcdabcd".chars());
+        counter.insert('z', 0);
+        counter.insert('y', -3);
+        assert_eq!(counter.entropy(), 2.0);
+        assert!((counter.entropy_nats() - 4f64.ln()).abs() < 1e-12);
+        assert_eq!(Counter::<char>::new().entropy(), 0.0);
+    }
+
+    #[test]
+    fn test_gini_impurity() {
+        let mut counter = Counter::<_, i32>::init("abcdabcd".chars());
+        counter.insert('z', 0);
+        counter.insert('y', -3);
+        assert_eq!(counter.gini_impurity(), 0.75);
+        assert_eq!(Counter::<char>::new().gini_impurity(), 0.0);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_kl_divergence() {
+        let p = Counter::<_>::init("aabb".chars());
+        let q = Counter::<_>::init("abbbccc".chars());
+        assert_eq!(p.kl_divergence(&p, ZeroProbability::Infinite), 0.0);
+
+        let expected = 0.5 * (0.5f64 / (1.0 / 7.0)).log2() + 0.5 * (0.5f64 / (3.0 / 7.0)).log2();
+        assert!((p.kl_divergence(&q, ZeroProbability::Infinite) - expected).abs() < 1e-12);
+        assert_eq!(q.kl_divergence(&p, ZeroProbability::Infinite), f64::INFINITY);
+
+        // with k = 1 over a, b and c, p becomes 3/7, 3/7, 1/7 and q becomes 2/10, 4/10, 4/10
+        let (p_smooth, q_smooth) = ([3.0 / 7.0, 3.0 / 7.0, 1.0 / 7.0], [0.2, 0.4, 0.4]);
+        let expected = p_smooth
+            .iter()
+            .zip(q_smooth.iter())
+            .map(|(&p, &q): (&f64, &f64)| p * (p / q).log2())
+            .sum::<f64>();
+        assert!((p.kl_divergence(&q, ZeroProbability::Smooth(1.0)) - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_js_divergence() {
+        let p = Counter::<_>::init("aabb".chars());
+        let q = Counter::<_>::init("abbbccc".chars());
+        let js = p.js_divergence(&q);
+        assert!((js - q.js_divergence(&p)).abs() < 1e-12);
+
+        // the mixture of p and q is 9/28, 13/28 and 3/14
+        let kl_p = 0.5 * (0.5f64 / (9.0 / 28.0)).log2() + 0.5 * (0.5f64 / (13.0 / 28.0)).log2();
+        let kl_q = (1.0 / 7.0) * ((1.0 / 7.0) / (9.0f64 / 28.0)).log2()
+            + (3.0 / 7.0) * ((3.0 / 7.0) / (13.0f64 / 28.0)).log2()
+            + (3.0 / 7.0) * ((3.0 / 7.0) / (3.0f64 / 14.0)).log2();
+        let expected = 0.5 * kl_p + 0.5 * kl_q;
+        assert!((js - expected).abs() < 1e-12);
+        assert!((p.js_distance(&q) - expected.sqrt()).abs() < 1e-12);
+
+        let mut empty = Counter::<char>::new();
+        assert_eq!(p.js_divergence(&empty), 1.0);
+        assert_eq!(empty.js_divergence(&p), 1.0);
+        assert_eq!(p.js_distance(&empty), 1.0);
+        assert_eq!(empty.js_distance(&p), 1.0);
+        assert_eq!(empty.js_divergence(&empty), 0.0);
+        empty.insert('a', 0);
+        assert_eq!(empty.js_distance(&Counter::new()), 0.0);
+
+        // items with a zero or negative count in self are only counted once
+        let mut zero = Counter::<_>::init(vec!['a']);
+        zero.insert('b', 0);
+        let b = Counter::<_>::init(vec!['b']);
+        assert_eq!(zero.js_divergence(&b), 1.0);
+        assert_eq!(b.js_divergence(&zero), 1.0);
+        let mut negative = Counter::<_, i32>::init(vec!['a']);
+        negative.insert('b', -2);
+        let b = Counter::<_, i32>::init(vec!['b']);
+        assert_eq!(negative.js_divergence(&b), 1.0);
+        assert_eq!(b.js_divergence(&negative), 1.0);
+    }
+
+    #[test]
+    fn test_bucketize() {
+        let counter = Counter::<i32>::init(vec![-5, 0, 3, 3, 9, 10, 11]);
+        let bins = counter.bucketize(&[0.0, 5.0, 10.0]);
+        let expected = hashmap! { 0 => 1, 1 => 3, 2 => 1, 3 => 2 };
+        assert!(bins.map == expected);
+        assert_eq!(Counter::<u8>::init(vec![1, 2]).bucketize(&[])[&0], 2);
+    }
+
+    #[test]
+    fn test_ranks() {
+        let counter =
+            Counter::<_>::from_pairs(vec![('a', 5), ('b', 3), ('c', 3), ('d', 3), ('e', 1)]);
+        let expected = [
+            (RankTies::Min, [1, 2, 2, 2, 5]),
+            (RankTies::Max, [1, 4, 4, 4, 5]),
+            (RankTies::Dense, [1, 2, 2, 2, 3]),
+        ];
+        for &(ties, ranks) in expected.iter() {
+            let all = counter.ranks(ties);
+            assert_eq!(all.len(), 5);
+            for (item, &rank) in "abcde".chars().zip(ranks.iter()) {
+                assert_eq!(all[&item], rank);
+                assert_eq!(counter.rank_of(&item, ties), Some(rank));
+            }
+        }
+        assert!(Counter::<char>::new().ranks(RankTies::Min).is_empty());
+    }
+
+    #[test]
+    fn test_most_common_grouped() {
+        let counter = Counter::<_>::from_pairs(vec![('a', 2), ('b', 1), ('c', 2), ('d', 2)]);
+        let mut groups = counter.most_common_grouped();
+        groups[0].1.sort();
+        assert_eq!(groups, vec![(2, vec![&'a', &'c', &'d']), (1, vec![&'b'])]);
+        assert!(Counter::<char>::new().most_common_grouped().is_empty());
+    }
+
+    #[test]
+    fn test_kth_most_common() {
+        let counter = Counter::<_>::from_pairs((1..=100).map(|i| (i, i * 7 % 101)));
+        let sorted = counter.most_common();
+        for (k, &(item, count)) in sorted.iter().enumerate() {
+            assert_eq!(counter.kth_most_common(k), Some((&item, count)));
+        }
+        assert_eq!(counter.kth_most_common(100), None);
+        assert_eq!(Counter::<char>::new().kth_most_common(0), None);
+    }
+
+    #[test]
+    fn test_count_thresholds() {
+        let counter = Counter::<_, i32>::from_pairs(vec![('a', -1), ('b', 2), ('c', 3), ('d', 5)]);
+        let mut frequent = counter.items_with_count_at_least(3).collect::<Vec<_>>();
+        frequent.sort();
+        assert_eq!(frequent, vec![(&'c', &3), (&'d', &5)]);
+        let mut rare = counter.items_with_count_at_most(2).collect::<Vec<_>>();
+        rare.sort();
+        assert_eq!(rare, vec![(&'a', &-1), (&'b', &2)]);
+
+        assert!(counter.at_least(3).map == hashmap! { 'c' => 3, 'd' => 5 });
+        assert!(counter.at_most(3).map == hashmap! { 'a' => -1, 'b' => 2, 'c' => 3 });
+        assert!(counter.at_least(6).is_empty());
+    }
+
+    #[test]
+    fn test_merge_from() {
+        let expected = hashmap! { 'a' => 3, 'b' => 4, 'c' => 1 };
+        for &(small, large) in [("aab", "abbbc"), ("abbbc", "aab")].iter() {
+            let mut counter = small.chars().collect::<Counter<_>>();
+            counter.merge_from(large.chars().collect());
+            assert!(counter.map == expected);
+
+            let mut counter = small.chars().collect::<Counter<_>>();
+            counter.merge_from_ref(&large.chars().collect());
+            assert!(counter.map == expected);
+        }
+    }
+
+    #[test]
+    fn test_extend_counters() {
+        let mut counter = Counter::<_>::init("ab".chars());
+        counter.extend(vec![
+            Counter::init("bcc".chars()),
+            Counter::new(),
+            Counter::init("abcdd".chars()),
+        ]);
+        let expected = hashmap! { 'a' => 2, 'b' => 3, 'c' => 3, 'd' => 2 };
+        assert!(counter.map == expected);
+    }
+
+    #[test]
+    fn test_convolve() {
+        let coin = Counter::<i32, i32>::from_pairs(vec![(0, 1), (1, 1)]);
+        let two = coin.convolve(&coin);
+        assert!(two.map == hashmap! { 0 => 1, 1 => 2, 2 => 1 });
+        let three = two.convolve(&coin);
+        assert!(three.map == hashmap! { 0 => 1, 1 => 3, 2 => 3, 3 => 1 });
+
+        let mut weighted = Counter::<i32, i32>::from_pairs(vec![(-1, 2), (5, 3)]);
+        weighted.insert(9, 0);
+        let sums = weighted.convolve(&coin);
+        assert!(sums.map == hashmap! { -1 => 2, 0 => 2, 5 => 3, 6 => 3 });
+        assert!(coin.convolve(&Counter::new()).is_empty());
+    }
+
+    #[test]
+    fn test_pair_marginals() {
+        let mut table = Counter::<_, i32>::pairs(vec![(1, 'x'), (1, 'y'), (2, 'x'), (1, 'x')]);
+        table.insert((3, 'z'), -1);
+        assert!(table.marginal_left().map == hashmap! { 1 => 3, 2 => 1, 3 => -1 });
+        assert!(table.marginal_right().map == hashmap! { 'x' => 3, 'y' => 1, 'z' => -1 });
+        assert!(Counter::<(u8, u8)>::new().marginal_left().is_empty());
+    }
+
+    #[test]
+    fn test_group_counts() {
+        let words = ["apple", "avocado", "banana", "apple", "blueberry", "cherry"];
+        let groups = Counter::<_>::group_counts(
+            words.iter(),
+            |word| word.chars().next().unwrap(),
+            |&word| word,
+        );
+        assert_eq!(groups.len(), 3);
+        assert!(groups[&'a'].map == hashmap! { "apple" => 2, "avocado" => 1 });
+        assert!(groups[&'c'].map == hashmap! { "cherry" => 1 });
+
+        let pairs = Counter::<_>::from_groups(groups);
+        assert_eq!(pairs.len(), 5);
+        assert_eq!(pairs[&('a', "apple")], 2);
+        assert_eq!(pairs.marginal_left()[&'b'], 2);
+    }
+
+    #[test]
+    fn test_invert() {
+        let counter = Counter::<_>::from_pairs(vec![('a', 2), ('b', 1), ('c', 2)]);
+        let mut inverted = counter.invert();
+        inverted.get_mut(&2).unwrap().sort();
+        let expected = vec![(1, vec![&'b']), (2, vec![&'a', &'c'])];
+        assert_eq!(inverted.into_iter().collect::<Vec<_>>(), expected);
+        assert!(Counter::<char>::new().invert().is_empty());
+    }
+
+    #[test]
+    fn test_debug_ordered() {
+        let counter = Counter::<_>::from_pairs(vec![(2, 2), (10, 2), (3, 1), (1, 1)]);
+        assert_eq!(format!("{:?}", counter), "Counter({10: 2, 2: 2, 1: 1, 3: 1})");
+        assert_eq!(
+            format!("{:?}", counter.debug_ordered()),
+            "Counter({2: 2, 10: 2, 1: 1, 3: 1})"
+        );
+        assert_eq!(format!("{:?}", Counter::<u8>::new().debug_ordered()), "Counter({})");
+    }
+
+    #[test]
+    fn test_debug_sorted() {
+        let counter = Counter::<_, f64>::init(vec![3, 1, 2, 1, 10]);
+        assert_eq!(
+            format!("{:?}", counter),
+            "Counter({1: 2.0, 10: 1.0, 2: 1.0, 3: 1.0})"
+        );
+        assert_eq!(
+            format!("{:#?}", Counter::<_>::init("aab".chars())),
+            "Counter(\n    {\n        'a': 2,\n        'b': 1,\n    },\n)"
+        );
+    }
 }
@@ -0,0 +1,54 @@
+/// Create a [`Counter`](struct.Counter.html) from a list of items, or from `item => count`
+/// pairs.
+///
+/// Items are counted like [`Counter::init`](struct.Counter.html#method.init), and the counts of
+/// duplicate items in the pair form are summed.
+///
+/// ```rust
+/// # #[macro_use] extern crate counter;
+/// # fn main() {
+/// use counter::Counter;
+///
+/// let letters: Counter<_> = counter!["a", "a", "b"];
+/// assert_eq!(letters, counter! { "a" => 2, "b" => 1 });
+///
+/// let empty: Counter<char> = counter![];
+/// assert!(empty.is_empty());
+/// # }
+/// ```
+#[macro_export]
+macro_rules! counter {
+    ($($item:expr => $count:expr),+ $(,)?) => {{
+        let mut counter = $crate::Counter::new();
+        counter.extend([$(($item, $count)),+]);
+        counter
+    }};
+    ($($item:expr),* $(,)?) => {
+        $crate::Counter::init([$($item),*])
+    };
+}
+
+/// A stand-in for `maplit::hashmap!` in tests without `std`, building the `hashbrown` map
+/// which backs `Counter` rather than a `std::collections::HashMap`.
+#[cfg(all(test, not(feature = "std")))]
+macro_rules! hashmap {
+    ($($key:expr => $value:expr),* $(,)?) => {{
+        let mut map = ::HashMap::default();
+        $(map.insert($key, $value);)*
+        map
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use Counter;
+
+    #[test]
+    fn test_counter_macro() {
+        let counter: Counter<_, i32> = counter! { 'a' => 1, 'b' => 2, 'a' => 3, };
+        assert!(counter.map == hashmap! { 'a' => 4, 'b' => 2 });
+
+        let counter: Counter<_> = counter![1, 2, 2,];
+        assert!(counter.map == hashmap! { 1 => 1, 2 => 2 });
+    }
+}
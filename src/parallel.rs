@@ -0,0 +1,168 @@
+//! Parallel counting with `rayon`, enabled by the `rayon` feature.
+//!
+//! Each rayon job counts its share of the items into a local `Counter`, and the
+//! local counters are then merged pairwise, always folding the smaller counter
+//! into the larger one. `par_extend` folds the result into the existing counter,
+//! so the counter keeps its hasher.
+
+use num_traits::{One, Zero};
+use rayon::iter::{FromParallelIterator, IntoParallelIterator, ParallelExtend, ParallelIterator};
+
+use std::hash::{BuildHasher, Hash};
+use std::mem;
+use std::ops::AddAssign;
+
+use Counter;
+
+/// Add all the counts of `rhs` to `lhs`.
+fn merge_into<T, N, S>(lhs: &mut Counter<T, N, S>, rhs: Counter<T, N, S>)
+where
+    T: Hash + Eq,
+    N: AddAssign + Zero,
+    S: BuildHasher,
+{
+    for (item, count) in rhs.map.into_iter() {
+        let entry = lhs.map.entry(item).or_insert_with(N::zero);
+        *entry += count;
+    }
+}
+
+/// Move all the counts of `rhs` into `lhs`, reusing the allocation of the larger of the two.
+///
+/// The result may have the hasher of either counter, so this is only used for merging the
+/// local counters of rayon jobs, which all have the default hasher.
+fn merge<T, N, S>(mut lhs: Counter<T, N, S>, mut rhs: Counter<T, N, S>) -> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: AddAssign + Zero,
+    S: BuildHasher,
+{
+    if rhs.map.len() > lhs.map.len() {
+        mem::swap(&mut lhs, &mut rhs);
+    }
+    merge_into(&mut lhs, rhs);
+    lhs
+}
+
+fn count_par_iter<I, T, N, S>(par_iter: I) -> Counter<T, N, S>
+where
+    I: IntoParallelIterator<Item = T>,
+    T: Hash + Eq + Send,
+    N: PartialOrd + AddAssign + Zero + One + Send,
+    S: BuildHasher + Default + Send,
+{
+    par_iter
+        .into_par_iter()
+        .fold(Counter::default, |mut counter, item| {
+            let entry = counter.map.entry(item).or_insert_with(N::zero);
+            *entry += N::one();
+            counter
+        })
+        .reduce(Counter::default, merge)
+}
+
+impl<T, N, S> FromParallelIterator<T> for Counter<T, N, S>
+where
+    T: Hash + Eq + Send,
+    N: PartialOrd + AddAssign + Zero + One + Send,
+    S: BuildHasher + Default + Send,
+{
+    /// Count the items of a parallel iterator.
+    ///
+    /// ```rust
+    /// # extern crate counter;
+    /// # extern crate rayon;
+    /// # use counter::Counter;
+    /// use rayon::prelude::*;
+    ///
+    /// # fn main() {
+    /// let counter = (0..1000).into_par_iter().map(|n| n % 3).collect::<Counter<_>>();
+    /// assert_eq!(counter[&0], 334);
+    /// assert_eq!(counter[&1], 333);
+    /// assert_eq!(counter[&2], 333);
+    /// # }
+    /// ```
+    fn from_par_iter<I>(par_iter: I) -> Self
+    where
+        I: IntoParallelIterator<Item = T>,
+    {
+        count_par_iter(par_iter)
+    }
+}
+
+impl<T, N, S> ParallelExtend<T> for Counter<T, N, S>
+where
+    T: Hash + Eq + Send,
+    N: PartialOrd + AddAssign + Zero + One + Send,
+    S: BuildHasher + Default + Send,
+{
+    /// Add the counts of the items of a parallel iterator to this counter.
+    ///
+    /// ```rust
+    /// # extern crate counter;
+    /// # extern crate rayon;
+    /// # use counter::Counter;
+    /// use rayon::prelude::*;
+    ///
+    /// # fn main() {
+    /// let mut counter = "abbccc".chars().collect::<Counter<_>>();
+    /// counter.par_extend(vec!['a', 'd'].into_par_iter());
+    /// assert_eq!(counter[&'a'], 2);
+    /// assert_eq!(counter[&'d'], 1);
+    /// # }
+    /// ```
+    fn par_extend<I>(&mut self, par_iter: I)
+    where
+        I: IntoParallelIterator<Item = T>,
+    {
+        let counted = count_par_iter(par_iter);
+        merge_into(self, counted);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rayon::prelude::*;
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{BuildHasher, Hasher};
+    use Counter;
+
+    #[test]
+    fn test_from_par_iter() {
+        let words = "the quick brown fox jumps over the lazy dog the end"
+            .split_whitespace()
+            .collect::<Vec<_>>();
+        let parallel = words.par_iter().cloned().collect::<Counter<_>>();
+        let sequential = words.iter().cloned().collect::<Counter<_>>();
+        assert_eq!(parallel, sequential);
+    }
+
+    #[test]
+    fn test_par_extend() {
+        let mut counter = (0..100u32).map(|n| n % 10).collect::<Counter<_>>();
+        counter.par_extend((0..1000u32).into_par_iter().map(|n| n % 10));
+        assert_eq!(counter.len(), 10);
+        assert!(counter.values().all(|&count| count == 110));
+    }
+
+    #[derive(Clone, Default, Debug, PartialEq)]
+    struct SeededState(u64);
+
+    impl BuildHasher for SeededState {
+        type Hasher = DefaultHasher;
+
+        fn build_hasher(&self) -> DefaultHasher {
+            let mut hasher = DefaultHasher::new();
+            hasher.write_u64(self.0);
+            hasher
+        }
+    }
+
+    #[test]
+    fn test_par_extend_keeps_hasher() {
+        let mut counter = Counter::<u32, usize, _>::with_hasher(SeededState(7));
+        counter.par_extend((0..10u32).into_par_iter());
+        assert_eq!(counter.len(), 10);
+        assert_eq!(*counter.map.hasher(), SeededState(7));
+    }
+}
@@ -0,0 +1,373 @@
+//! Counters which only remember recent items.
+//!
+//! [`DecayingCounter`](struct.DecayingCounter.html) requires the `std` feature.
+
+use alloc::collections::VecDeque;
+use core::borrow::Borrow;
+use core::hash::{BuildHasher, Hash};
+
+#[cfg(feature = "std")]
+use alloc::vec::Vec;
+
+use Counter;
+#[cfg(feature = "std")]
+use HashMap;
+use RandomState;
+
+/// A counter over a trailing window of time, which forgets each count once it is older than
+/// the window.
+///
+/// Times are plain `u64`s, so they can be timestamps in whatever unit suits, or sequence
+/// numbers: with one increment per sequence number, a window of `n` keeps the counts of the
+/// last `n` events. Expired counts are removed lazily, whenever the counter is updated or
+/// [`advance`](#method.advance)d.
+///
+/// ```rust
+/// # use counter::rolling::WindowedCounter;
+/// let mut errors = WindowedCounter::<_>::new(60);
+/// errors.increment("timeout", 0);
+/// errors.increment("refused", 30);
+/// errors.increment("timeout", 45);
+/// assert_eq!(errors.counts()[&"timeout"], 2);
+///
+/// errors.advance(75);
+/// assert_eq!(errors.counts()[&"timeout"], 1);
+/// assert_eq!(errors.count_of(&"refused"), 1);
+///
+/// errors.advance(150);
+/// assert!(errors.counts().is_empty());
+/// ```
+#[derive(Clone, Debug)]
+pub struct WindowedCounter<T: Hash + Eq, S = RandomState> {
+    window: u64,
+    now: u64,
+    events: VecDeque<(u64, T, usize)>,
+    counter: Counter<T, usize, S>,
+}
+
+impl<T> WindowedCounter<T>
+where
+    T: Hash + Eq,
+{
+    /// Create a new, empty `WindowedCounter` which keeps counts for `window` units of time
+    pub fn new(window: u64) -> WindowedCounter<T> {
+        WindowedCounter::with_hasher(window, RandomState::default())
+    }
+}
+
+impl<T, S> WindowedCounter<T, S>
+where
+    T: Hash + Eq,
+    S: BuildHasher,
+{
+    /// Create a new, empty `WindowedCounter` which keeps counts for `window` units of time,
+    /// and which will use the given hasher to hash the items
+    pub fn with_hasher(window: u64, hash_builder: S) -> WindowedCounter<T, S> {
+        WindowedCounter {
+            window,
+            now: 0,
+            events: VecDeque::new(),
+            counter: Counter::with_hasher(hash_builder),
+        }
+    }
+
+    /// The length of the window
+    pub fn window(&self) -> u64 {
+        self.window
+    }
+
+    /// The latest time this counter has been updated or advanced to
+    pub fn now(&self) -> u64 {
+        self.now
+    }
+
+    /// Move the window forward to end at `now`, forgetting the counts which were added at or
+    /// before `now - window`
+    ///
+    /// Time never goes backwards: if `now` is earlier than the current time, nothing happens.
+    pub fn advance(&mut self, now: u64) {
+        if now < self.now {
+            return;
+        }
+        self.now = now;
+        while let Some(&(time, _, _)) = self.events.front() {
+            if now - time < self.window {
+                break;
+            }
+            let (_, item, count) = self.events.pop_front().unwrap();
+            if let Some(remaining) = self.counter.map.get_mut(&item) {
+                *remaining -= count;
+                if *remaining == 0 {
+                    self.counter.map.remove(&item);
+                }
+            }
+        }
+    }
+
+    /// Add `count` to the count of the given item at time `now`
+    ///
+    /// A `now` earlier than the current time is treated as the current time.
+    pub fn add_count(&mut self, item: T, count: usize, now: u64)
+    where
+        T: Clone,
+    {
+        self.advance(now);
+        if count == 0 || self.window == 0 {
+            return;
+        }
+        *self.counter.map.entry(item.clone()).or_insert(0) += count;
+        self.events.push_back((self.now, item, count));
+    }
+
+    /// Add one to the count of the given item at time `now`
+    pub fn increment(&mut self, item: T, now: u64)
+    where
+        T: Clone,
+    {
+        self.add_count(item, 1, now);
+    }
+
+    /// Returns the count of the given item within the window ending at the current time.
+    pub fn count_of<Q>(&self, item: &Q) -> usize
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.counter.map.get(item).cloned().unwrap_or(0)
+    }
+
+    /// The counts within the window ending at the current time
+    pub fn counts(&self) -> &Counter<T, usize, S> {
+        &self.counter
+    }
+
+    /// Forget every count, without changing the current time
+    pub fn clear(&mut self) {
+        self.events.clear();
+        self.counter.map.clear();
+    }
+}
+
+/// A counter whose counts decay exponentially over time, halving every `half_life` units
+/// of time, so that items which were frequent recently outweigh items which were frequent
+/// long ago.
+///
+/// Like [`WindowedCounter`](struct.WindowedCounter.html), it measures time with plain `u64`s.
+/// Counts are decayed lazily when they are read or updated; [`tick`](#method.tick) decays
+/// them all at once and forgets the ones which have become negligible, so stale items don't
+/// accumulate.
+///
+/// ```rust
+/// # use counter::rolling::DecayingCounter;
+/// let mut counter = DecayingCounter::<_>::new(10.0);
+/// counter.add_count("old", 8.0, 0);
+/// counter.add_count("new", 3.0, 20);
+/// assert_eq!(counter.count_of(&"old", 20), 2.0);
+/// assert_eq!(counter.most_common(20)[0], ("new", 3.0));
+/// ```
+#[cfg(feature = "std")]
+#[derive(Clone, Debug)]
+pub struct DecayingCounter<T: Hash + Eq, S = RandomState> {
+    half_life: f64,
+    min_count: f64,
+    now: u64,
+    map: HashMap<T, (f64, u64), S>,
+}
+
+#[cfg(feature = "std")]
+impl<T> DecayingCounter<T>
+where
+    T: Hash + Eq,
+{
+    /// Create a new, empty `DecayingCounter` whose counts halve every `half_life` units of time
+    ///
+    /// # Panics
+    ///
+    /// Panics if `half_life` is not positive.
+    pub fn new(half_life: f64) -> DecayingCounter<T> {
+        DecayingCounter::with_hasher(half_life, RandomState::default())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T, S> DecayingCounter<T, S>
+where
+    T: Hash + Eq,
+    S: BuildHasher,
+{
+    /// Create a new, empty `DecayingCounter` whose counts halve every `half_life` units of
+    /// time, and which will use the given hasher to hash the items
+    ///
+    /// # Panics
+    ///
+    /// Panics if `half_life` is not positive.
+    pub fn with_hasher(half_life: f64, hash_builder: S) -> DecayingCounter<T, S> {
+        assert!(half_life > 0.0, "a DecayingCounter needs a positive half-life");
+        DecayingCounter {
+            half_life,
+            min_count: 0.01,
+            now: 0,
+            map: HashMap::with_hasher(hash_builder),
+        }
+    }
+
+    /// The time it takes for a count to halve
+    pub fn half_life(&self) -> f64 {
+        self.half_life
+    }
+
+    /// Set the count below which [`tick`](#method.tick) forgets an item. It defaults to `0.01`.
+    pub fn set_min_count(&mut self, min_count: f64) {
+        self.min_count = min_count;
+    }
+
+    /// The number of items with a count
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if no item has a count.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    fn decayed(&self, count: f64, since: u64, now: u64) -> f64 {
+        let elapsed = now.saturating_sub(since) as f64;
+        count * (-elapsed / self.half_life).exp2()
+    }
+
+    /// Add `count` to the count of the given item at time `now`
+    ///
+    /// A `now` earlier than the latest time seen is treated as the latest time.
+    pub fn add_count(&mut self, item: T, count: f64, now: u64) {
+        self.now = self.now.max(now);
+        let now = self.now;
+        let decayed = match self.map.get(&item) {
+            Some(&(old, since)) => self.decayed(old, since, now),
+            None => 0.0,
+        };
+        self.map.insert(item, (decayed + count, now));
+    }
+
+    /// Add one to the count of the given item at time `now`
+    pub fn increment(&mut self, item: T, now: u64) {
+        self.add_count(item, 1.0, now);
+    }
+
+    /// Returns the count of the given item, decayed to time `now`.
+    pub fn count_of<Q>(&self, item: &Q, now: u64) -> f64
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map
+            .get(item)
+            .map_or(0.0, |&(count, since)| self.decayed(count, since, now))
+    }
+
+    /// Decay every count to time `now`, and forget the items whose counts have fallen below
+    /// the minimum count
+    pub fn tick(&mut self, now: u64) {
+        self.now = self.now.max(now);
+        let now = self.now;
+        let half_life = self.half_life;
+        let min_count = self.min_count;
+        self.map.retain(|_, entry| {
+            let elapsed = now.saturating_sub(entry.1) as f64;
+            *entry = (entry.0 * (-elapsed / half_life).exp2(), now);
+            entry.0 >= min_count
+        });
+    }
+
+    /// Create a vector of `(item, count)` pairs, with the counts decayed to time `now`,
+    /// sorted from the highest count to the lowest.
+    ///
+    /// Note that the ordering of duplicates is unstable.
+    pub fn most_common(&self, now: u64) -> Vec<(T, f64)>
+    where
+        T: Clone,
+    {
+        use core::cmp::Ordering;
+
+        let mut items = self
+            .map
+            .iter()
+            .map(|(item, &(count, since))| (item.clone(), self.decayed(count, since, now)))
+            .collect::<Vec<_>>();
+        items.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap_or(Ordering::Equal));
+        items
+    }
+
+    /// Forget every count
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window_expiry() {
+        let mut counter = WindowedCounter::<_>::new(10);
+        counter.add_count('a', 2, 0);
+        counter.increment('b', 5);
+        counter.increment('a', 9);
+        assert!(counter.counts().map == hashmap! { 'a' => 3, 'b' => 1 });
+
+        counter.advance(10);
+        assert!(counter.counts().map == hashmap! { 'a' => 1, 'b' => 1 });
+        counter.advance(15);
+        assert!(counter.counts().map == hashmap! { 'a' => 1 });
+        assert_eq!(counter.count_of(&'b'), 0);
+        counter.advance(100);
+        assert!(counter.counts().is_empty());
+    }
+
+    #[test]
+    fn test_time_does_not_go_backwards() {
+        let mut counter = WindowedCounter::<_>::new(10);
+        counter.increment('a', 20);
+        counter.increment('b', 5);
+        assert_eq!(counter.now(), 20);
+        counter.advance(29);
+        assert!(counter.counts().map == hashmap! { 'a' => 1, 'b' => 1 });
+        counter.advance(30);
+        assert!(counter.counts().is_empty());
+    }
+
+    #[test]
+    fn test_last_n_events() {
+        let mut counter = WindowedCounter::<_>::new(3);
+        for (sequence, c) in "aabcb".chars().enumerate() {
+            counter.increment(c, sequence as u64);
+        }
+        assert!(counter.counts().map == hashmap! { 'b' => 2, 'c' => 1 });
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_decay() {
+        let mut counter = DecayingCounter::<_>::new(4.0);
+        counter.add_count('a', 16.0, 0);
+        assert_eq!(counter.count_of(&'a', 4), 8.0);
+        assert_eq!(counter.count_of(&'a', 8), 4.0);
+        counter.increment('a', 8);
+        assert_eq!(counter.count_of(&'a', 8), 5.0);
+        assert_eq!(counter.count_of(&'b', 8), 0.0);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_tick_prunes() {
+        let mut counter = DecayingCounter::<_>::new(1.0);
+        counter.add_count('a', 1.0, 0);
+        counter.add_count('b', 1024.0, 0);
+        counter.set_min_count(0.5);
+        counter.tick(2);
+        assert_eq!(counter.len(), 1);
+        assert_eq!(counter.count_of(&'b', 2), 256.0);
+        assert_eq!(counter.most_common(3), vec![('b', 128.0)]);
+    }
+}
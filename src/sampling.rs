@@ -0,0 +1,272 @@
+//! Random sampling from counters, enabled by the `rand` feature.
+
+use alloc::vec::{self, Vec};
+use core::hash::{BuildHasher, Hash};
+use core::ops::SubAssign;
+
+use num_traits::{One, ToPrimitive, Zero};
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use distribution::AliasTable;
+use Counter;
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: ToPrimitive,
+    S: BuildHasher,
+{
+    /// Pick a random item, with a probability proportional to its count, or `None` if no count
+    /// is positive
+    ///
+    /// Each call takes `O(len)` time. To draw many items from the same counter, create its
+    /// [`distribution`](#method.distribution) once and call
+    /// [`FrequencyDistribution::sample`](struct.FrequencyDistribution.html#method.sample),
+    /// which takes constant time. This method requires the `rand` feature.
+    ///
+    /// ```rust
+    /// # extern crate counter;
+    /// # extern crate rand;
+    /// # use counter::Counter;
+    /// # fn main() {
+    /// let next_words = Counter::<_>::init(vec!["cat", "cat", "dog"]);
+    /// let word = next_words.sample(&mut rand::thread_rng()).unwrap();
+    /// assert!(*word == "cat" || *word == "dog");
+    /// # }
+    /// ```
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<&T> {
+        let total = self.positive_counts().sum::<f64>();
+        if total <= 0.0 {
+            return None;
+        }
+        let mut remaining = rng.gen::<f64>() * total;
+        let mut last = None;
+        for item in self.map.keys() {
+            let count = self.positive_count_of(item);
+            if count > 0.0 {
+                if remaining < count {
+                    return Some(item);
+                }
+                remaining -= count;
+                last = Some(item);
+            }
+        }
+        // only reached through rounding error
+        last
+    }
+
+    /// Pick `n` random items with replacement, each with a probability proportional to its
+    /// count, or none if no count is positive
+    ///
+    /// This builds an alias table once, so each draw after that takes constant time. This
+    /// method requires the `rand` feature.
+    ///
+    /// ```rust
+    /// # extern crate counter;
+    /// # extern crate rand;
+    /// # use counter::Counter;
+    /// # fn main() {
+    /// let counter = "ab".chars().collect::<Counter<_>>();
+    /// let samples = counter.sample_n(&mut rand::thread_rng(), 5);
+    /// assert_eq!(samples.len(), 5);
+    /// # }
+    /// ```
+    pub fn sample_n<R: Rng + ?Sized>(&self, rng: &mut R, n: usize) -> Vec<&T> {
+        let (items, weights): (Vec<_>, Vec<_>) = self
+            .map
+            .keys()
+            .map(|item| (item, self.positive_count_of(item)))
+            .filter(|&(_, count)| count > 0.0)
+            .unzip();
+        if items.is_empty() {
+            return Vec::new();
+        }
+        let alias = AliasTable::new(&weights);
+        (0..n)
+            .filter_map(|_| alias.sample(rng))
+            .map(|i| items[i])
+            .collect()
+    }
+
+    /// Pick `n` random items without replacement, treating the counter as a multiset which
+    /// holds each item as many times as its count
+    ///
+    /// Every item which is picked is taken out of a working copy of the counts, so an item is
+    /// never picked more times than its count. If there are fewer than `n` items in all, they
+    /// are all picked, in a random order. Counts are rounded down to whole numbers, and counts
+    /// which aren't positive are skipped. This method requires the `rand` feature.
+    ///
+    /// ```rust
+    /// # extern crate counter;
+    /// # extern crate rand;
+    /// # use counter::Counter;
+    /// # fn main() {
+    /// let urn = "rrrb".chars().collect::<Counter<_>>();
+    /// let drawn = urn.sample_without_replacement(&mut rand::thread_rng(), 10);
+    /// assert_eq!(drawn.into_iter().cloned().collect::<Counter<_>>(), urn);
+    /// # }
+    /// ```
+    pub fn sample_without_replacement<R: Rng + ?Sized>(&self, rng: &mut R, n: usize) -> Vec<&T> {
+        let mut remaining = self
+            .map
+            .iter()
+            .filter_map(|(item, count)| count.to_u64().map(|count| (item, count)))
+            .filter(|&(_, count)| count > 0)
+            .collect::<Vec<_>>();
+        let mut total = remaining.iter().map(|&(_, count)| count).sum::<u64>();
+
+        let mut samples = Vec::new();
+        while samples.len() < n && total > 0 {
+            let mut target = rng.gen_range(0..total);
+            let i = remaining
+                .iter()
+                .position(|&(_, count)| {
+                    if target < count {
+                        true
+                    } else {
+                        target -= count;
+                        false
+                    }
+                })
+                .expect("the target is less than the total");
+            samples.push(remaining[i].0);
+            remaining[i].1 -= 1;
+            total -= 1;
+            if remaining[i].1 == 0 {
+                remaining.swap_remove(i);
+            }
+        }
+        samples
+    }
+
+    /// Pick one of the elements of the multiset uniformly at random, so that each item is
+    /// picked with a probability proportional to its count, or `None` if there are none
+    ///
+    /// Unlike [`sample`](#method.sample), this counts the elements exactly, with integers:
+    /// counts are rounded down to whole numbers, and counts which aren't positive are skipped.
+    /// This method requires the `rand` feature.
+    ///
+    /// ```rust
+    /// # extern crate counter;
+    /// # extern crate rand;
+    /// # use counter::Counter;
+    /// # fn main() {
+    /// let counter = "aab".chars().collect::<Counter<_>>();
+    /// let item = counter.choose(&mut rand::thread_rng()).unwrap();
+    /// assert!(*item == 'a' || *item == 'b');
+    /// # }
+    /// ```
+    pub fn choose<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<&T> {
+        self.sample_without_replacement(rng, 1).pop()
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Clone + PartialOrd + SubAssign + Zero + One,
+    S: BuildHasher,
+{
+    /// Create an iterator over the [`elements`](#method.elements), each item repeated as many
+    /// times as its count, in a random order
+    ///
+    /// The elements are collected and shuffled up front, so this takes memory proportional to
+    /// the total of the counts. This method requires the `rand` feature.
+    ///
+    /// ```rust
+    /// # extern crate counter;
+    /// # extern crate rand;
+    /// # use counter::Counter;
+    /// # fn main() {
+    /// let counter = "abbccc".chars().collect::<Counter<_>>();
+    /// let shuffled = counter.elements_shuffled(&mut rand::thread_rng());
+    /// assert_eq!(shuffled.cloned().collect::<Counter<_>>(), counter);
+    /// # }
+    /// ```
+    pub fn elements_shuffled<R: Rng + ?Sized>(&self, rng: &mut R) -> vec::IntoIter<&T> {
+        let mut elements = self.elements().collect::<Vec<_>>();
+        elements.shuffle(rng);
+        elements.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_sample() {
+        let counter = Counter::<_, i32>::from_pairs(vec![('a', 1), ('b', 3), ('c', -5)]);
+        let mut rng = StdRng::seed_from_u64(11);
+        let samples = (0..10_000)
+            .map(|_| *counter.sample(&mut rng).unwrap())
+            .collect::<Counter<_>>();
+        assert_eq!(samples.len(), 2);
+        assert!((2200..2800).contains(&samples[&'a']));
+        assert!((7200..7800).contains(&samples[&'b']));
+
+        let empty = Counter::<_, i32>::from_pairs(vec![('a', -1)]);
+        assert_eq!(empty.sample(&mut rng), None);
+    }
+
+    #[test]
+    fn test_sample_n() {
+        let counter = Counter::<_, i32>::from_pairs(vec![('a', 1), ('b', 9), ('c', -5)]);
+        let mut rng = StdRng::seed_from_u64(3);
+        let samples = counter
+            .sample_n(&mut rng, 10_000)
+            .into_iter()
+            .collect::<Counter<_>>();
+        assert_eq!(samples.len(), 2);
+        assert!((800..1200).contains(&samples[&&'a']));
+
+        assert!(counter.sample_n(&mut rng, 0).is_empty());
+        assert!(Counter::<char>::new().sample_n(&mut rng, 3).is_empty());
+    }
+
+    #[test]
+    fn test_sample_without_replacement() {
+        let counter = Counter::<_, i32>::from_pairs(vec![('a', 2), ('b', 3), ('c', -5)]);
+        let mut rng = StdRng::seed_from_u64(5);
+        for n in 0..5 {
+            let samples = counter.sample_without_replacement(&mut rng, n);
+            assert_eq!(samples.len(), n);
+            let samples = samples.into_iter().collect::<Counter<_>>();
+            assert!(samples[&&'a'] <= 2 && samples[&&'b'] <= 3);
+        }
+        let all = counter.sample_without_replacement(&mut rng, 100);
+        assert_eq!(all.len(), 5);
+
+        // the first pick is still proportional to the counts
+        let firsts = (0..10_000)
+            .map(|_| *counter.sample_without_replacement(&mut rng, 1)[0])
+            .collect::<Counter<_>>();
+        assert!((3700..4300).contains(&firsts[&'a']));
+    }
+
+    #[test]
+    fn test_choose() {
+        let counter = Counter::<_, i32>::from_pairs(vec![('a', 1), ('b', 3), ('c', -5)]);
+        let mut rng = StdRng::seed_from_u64(13);
+        let picks = (0..10_000)
+            .map(|_| *counter.choose(&mut rng).unwrap())
+            .collect::<Counter<_>>();
+        assert_eq!(picks.len(), 2);
+        assert!((2200..2800).contains(&picks[&'a']));
+        assert_eq!(Counter::<char>::new().choose(&mut rng), None);
+    }
+
+    #[test]
+    fn test_elements_shuffled() {
+        let counter = Counter::<_>::init("mississippi".chars());
+        let mut rng = StdRng::seed_from_u64(17);
+        let first = counter.elements_shuffled(&mut rng).collect::<Vec<_>>();
+        let second = counter.elements_shuffled(&mut rng).collect::<Vec<_>>();
+        assert_eq!(first.len(), 11);
+        assert_ne!(first, second);
+        assert_eq!(first.into_iter().cloned().collect::<Counter<_>>(), counter);
+    }
+}
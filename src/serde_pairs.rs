@@ -0,0 +1,129 @@
+//! An alternative serde representation of a `Counter`, as a sequence of `[item, count]` pairs,
+//! enabled by the `serde` feature.
+//!
+//! The default representation is a map, which formats like JSON can only use when the items
+//! serialize as strings. This one works for any item type. Use it on a field with
+//! `#[serde(with = "counter::serde_pairs")]`:
+//!
+//! ```rust
+//! # extern crate counter;
+//! # #[macro_use]
+//! # extern crate serde_derive;
+//! # extern crate serde_json;
+//! # use counter::Counter;
+//! #[derive(Serialize, Deserialize)]
+//! struct Edges {
+//!     #[serde(with = "counter::serde_pairs")]
+//!     counts: Counter<(u32, u32)>,
+//! }
+//!
+//! # fn main() {
+//! let edges = Edges { counts: vec![(1, 2), (1, 2)].into_iter().collect() };
+//! let json = serde_json::to_string(&edges).unwrap();
+//! assert_eq!(json, r#"{"counts":[[[1,2],2]]}"#);
+//!
+//! let edges: Edges = serde_json::from_str(&json).unwrap();
+//! assert_eq!(edges.counts[&(1, 2)], 2);
+//! # }
+//! ```
+//!
+//! As with the map representation, the counts of duplicate items are summed when
+//! deserializing.
+
+use num_traits::Zero;
+use serde::de::{Deserialize, Deserializer, SeqAccess, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+use core::fmt;
+use core::hash::{BuildHasher, Hash};
+use core::marker::PhantomData;
+use core::ops::AddAssign;
+
+use Counter;
+
+/// Serialize a `Counter` as a sequence of `(item, count)` pairs
+pub fn serialize<T, N, S, Ser>(
+    counter: &Counter<T, N, S>,
+    serializer: Ser,
+) -> Result<Ser::Ok, Ser::Error>
+where
+    T: Serialize + Hash + Eq,
+    N: Serialize,
+    S: BuildHasher,
+    Ser: Serializer,
+{
+    serializer.collect_seq(counter.map.iter())
+}
+
+struct PairsVisitor<T, N, S> {
+    marker: PhantomData<(T, N, S)>,
+}
+
+impl<'de, T, N, S> Visitor<'de> for PairsVisitor<T, N, S>
+where
+    T: Deserialize<'de> + Hash + Eq,
+    N: Deserialize<'de> + AddAssign + Zero,
+    S: BuildHasher + Default,
+{
+    type Value = Counter<T, N, S>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a sequence of (item, count) pairs")
+    }
+
+    fn visit_seq<A>(self, mut access: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        // don't trust the size hint too far; it comes from the input
+        let capacity = access.size_hint().unwrap_or(0).min(4096);
+        let mut counter = Counter::with_capacity_and_hasher(capacity, S::default());
+        while let Some((item, count)) = access.next_element::<(T, N)>()? {
+            let entry = counter.map.entry(item).or_insert_with(N::zero);
+            *entry += count;
+        }
+        Ok(counter)
+    }
+}
+
+/// Deserialize a `Counter` from a sequence of `(item, count)` pairs, summing the counts of
+/// duplicate items
+pub fn deserialize<'de, T, N, S, D>(deserializer: D) -> Result<Counter<T, N, S>, D::Error>
+where
+    T: Deserialize<'de> + Hash + Eq,
+    N: Deserialize<'de> + AddAssign + Zero,
+    S: BuildHasher + Default,
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_seq(PairsVisitor {
+        marker: PhantomData,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate serde_json;
+
+    use super::*;
+    use std::string::{String, ToString};
+
+    #[test]
+    fn test_pairs_round_trip() {
+        let counter = vec![(1, 'a'), (2, 'b'), (1, 'a')]
+            .into_iter()
+            .collect::<Counter<_>>();
+        let json = serialize(&counter, serde_json::value::Serializer).unwrap();
+        assert_eq!(json.as_array().unwrap().len(), 2);
+
+        let back: Counter<(i32, char)> = deserialize(json).unwrap();
+        assert_eq!(back, counter);
+    }
+
+    #[test]
+    fn test_pairs_sum_duplicates() {
+        let json = serde_json::json!([["a", 1], ["b", 2], ["a", 3]]);
+        let counter: Counter<String> = deserialize(json).unwrap();
+        let expected = hashmap! { "a".to_string() => 4, "b".to_string() => 2 };
+        assert!(counter.map == expected);
+    }
+}
@@ -0,0 +1,115 @@
+//! `Serialize` and `Deserialize` implementations, enabled by the `serde` feature.
+//!
+//! A `Counter` is serialized as a map from items to counts. When deserializing,
+//! the counts of duplicate items are summed rather than rejected.
+
+use num_traits::Zero;
+use serde::de::{Deserialize, Deserializer, MapAccess, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+use core::fmt;
+use core::hash::{BuildHasher, Hash};
+use core::marker::PhantomData;
+use core::ops::AddAssign;
+
+use Counter;
+
+impl<T, N, S> Serialize for Counter<T, N, S>
+where
+    T: Serialize + Hash + Eq,
+    N: Serialize,
+    S: BuildHasher,
+{
+    fn serialize<Ser>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error>
+    where
+        Ser: Serializer,
+    {
+        serializer.collect_map(self.map.iter())
+    }
+}
+
+struct CounterVisitor<T, N, S> {
+    marker: PhantomData<(T, N, S)>,
+}
+
+impl<'de, T, N, S> Visitor<'de> for CounterVisitor<T, N, S>
+where
+    T: Deserialize<'de> + Hash + Eq,
+    N: Deserialize<'de> + AddAssign + Zero,
+    S: BuildHasher + Default,
+{
+    type Value = Counter<T, N, S>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map from items to counts")
+    }
+
+    fn visit_map<M>(self, mut access: M) -> Result<Self::Value, M::Error>
+    where
+        M: MapAccess<'de>,
+    {
+        // don't trust the size hint too far; it comes from the input
+        let capacity = access.size_hint().unwrap_or(0).min(4096);
+        let mut counter = Counter::with_capacity_and_hasher(capacity, S::default());
+        while let Some((item, count)) = access.next_entry::<T, N>()? {
+            let entry = counter.map.entry(item).or_insert_with(N::zero);
+            *entry += count;
+        }
+        Ok(counter)
+    }
+}
+
+impl<'de, T, N, S> Deserialize<'de> for Counter<T, N, S>
+where
+    T: Deserialize<'de> + Hash + Eq,
+    N: Deserialize<'de> + AddAssign + Zero,
+    S: BuildHasher + Default,
+{
+    /// Deserialize a counter from a map, summing the counts of duplicate items.
+    ///
+    /// ```rust
+    /// # extern crate counter;
+    /// # extern crate serde_json;
+    /// # use counter::Counter;
+    /// # fn main() {
+    /// let counter: Counter<String> = serde_json::from_str(r#"{"a": 1, "b": 2, "a": 3}"#).unwrap();
+    /// assert_eq!(counter["a"], 4);
+    /// assert_eq!(counter["b"], 2);
+    /// # }
+    /// ```
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(CounterVisitor {
+            marker: PhantomData,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate serde_json;
+
+    use Counter;
+    use std::string::{String, ToString};
+
+    #[test]
+    fn test_round_trip() {
+        let counter = "abbccc".chars().collect::<Counter<_>>();
+        let json = serde_json::to_string(&counter).unwrap();
+        let restored: Counter<char> = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, counter);
+    }
+
+    #[test]
+    fn test_duplicate_keys_are_merged() {
+        let counter: Counter<String, u32> =
+            serde_json::from_str(r#"{"a": 1, "b": 2, "a": 3}"#).unwrap();
+        let expected = hashmap!{
+            "a".to_string() => 4,
+            "b".to_string() => 2,
+        };
+        assert_eq!(counter.into_map(), expected);
+    }
+}
@@ -0,0 +1,931 @@
+//! Approximate counters which use a fixed amount of memory, however many items they see.
+//!
+//! [`DistinctEstimator`](struct.DistinctEstimator.html) requires the `std` feature.
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::cmp::Reverse;
+use core::fmt;
+use core::hash::{BuildHasher, Hash, Hasher};
+use core::marker::PhantomData;
+
+use Counter;
+use HashMap;
+use RandomState;
+
+/// A [Count-Min Sketch](https://en.wikipedia.org/wiki/Count%E2%80%93min_sketch), which
+/// estimates the counts of items in a stream too large to count exactly.
+///
+/// The sketch is a grid of `depth` rows of `width` counts. Each item is hashed to one count
+/// in every row, and its estimate is the smallest of those counts. Estimates are never too
+/// low; with probability at least `1 - 2^-depth`, an estimate is too high by no more than
+/// `2 * total / width`, where `total` is the sum of all the counts added to the sketch.
+///
+/// The items themselves are not stored, so `T` may be unsized.
+///
+/// ```rust
+/// # use counter::sketch::CountMinSketch;
+/// let mut sketch = CountMinSketch::<str>::new(1024, 4);
+/// for url in "/ /about / /blog / /about".split(' ') {
+///     sketch.update(url);
+/// }
+/// assert!(sketch.estimate("/") >= 3);
+/// assert!(sketch.estimate("/about") >= 2);
+/// assert_eq!(sketch.total(), 6);
+/// ```
+pub struct CountMinSketch<T: ?Sized, S = RandomState> {
+    width: usize,
+    depth: usize,
+    counts: Vec<usize>,
+    total: usize,
+    hash_builder: S,
+    marker: PhantomData<fn(&T)>,
+}
+
+impl<T> CountMinSketch<T>
+where
+    T: Hash + ?Sized,
+{
+    /// Create a new, empty `CountMinSketch` with `depth` rows of `width` counts
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` or `depth` is zero.
+    pub fn new(width: usize, depth: usize) -> CountMinSketch<T> {
+        CountMinSketch::with_hasher(width, depth, RandomState::default())
+    }
+}
+
+impl<T, S> CountMinSketch<T, S>
+where
+    T: Hash + ?Sized,
+    S: BuildHasher,
+{
+    /// Create a new, empty `CountMinSketch` with `depth` rows of `width` counts,
+    /// which will use the given hasher to hash the items
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` or `depth` is zero.
+    pub fn with_hasher(width: usize, depth: usize, hash_builder: S) -> CountMinSketch<T, S> {
+        assert!(
+            width > 0 && depth > 0,
+            "a CountMinSketch needs a nonzero width and depth"
+        );
+        CountMinSketch {
+            width,
+            depth,
+            counts: vec![0; width * depth],
+            total: 0,
+            hash_builder,
+            marker: PhantomData,
+        }
+    }
+
+    /// The number of counts in each row
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The number of rows, each of which hashes the items differently
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// The sum of all the counts added to this sketch
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    fn indices<'a>(&'a self, item: &'a T) -> impl Iterator<Item = usize> + 'a {
+        (0..self.depth).map(move |row| {
+            let mut hasher = self.hash_builder.build_hasher();
+            row.hash(&mut hasher);
+            item.hash(&mut hasher);
+            row * self.width + (hasher.finish() % self.width as u64) as usize
+        })
+    }
+
+    /// Add `count` to the count of the given item
+    pub fn add_count(&mut self, item: &T, count: usize) {
+        let indices = self.indices(item).collect::<Vec<_>>();
+        for index in indices {
+            self.counts[index] += count;
+        }
+        self.total += count;
+    }
+
+    /// Add one to the count of the given item
+    pub fn update(&mut self, item: &T) {
+        self.add_count(item, 1);
+    }
+
+    /// Returns the estimated count of the given item, which is never lower than its true count.
+    pub fn estimate(&self, item: &T) -> usize {
+        self.indices(item)
+            .map(|index| self.counts[index])
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// Add the counts from `other` to this sketch.
+    ///
+    /// Both sketches must hash their items the same way, so `other` should have been created
+    /// with an equal hasher, for instance by cloning this sketch while it was empty.
+    ///
+    /// ```rust
+    /// # use counter::sketch::CountMinSketch;
+    /// let empty = CountMinSketch::<str>::new(256, 4);
+    /// let mut a = empty.clone();
+    /// let mut b = empty;
+    /// a.add_count("x", 3);
+    /// b.add_count("x", 2);
+    /// a.merge(&b);
+    /// assert!(a.estimate("x") >= 5);
+    /// assert_eq!(a.total(), 5);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the two sketches have different widths or depths.
+    pub fn merge(&mut self, other: &CountMinSketch<T, S>) {
+        assert!(
+            self.width == other.width && self.depth == other.depth,
+            "cannot merge CountMinSketches of different dimensions"
+        );
+        for (count, &other_count) in self.counts.iter_mut().zip(other.counts.iter()) {
+            *count += other_count;
+        }
+        self.total += other.total;
+    }
+
+    /// Count exactly the items from the given iterable whose estimated count is at least
+    /// `threshold`.
+    ///
+    /// Since estimates are never too low, passing the stream which was fed to this sketch
+    /// a second time gives the exact counts of every item which occurred at least
+    /// `threshold` times, along with any lighter items the sketch overestimated.
+    ///
+    /// ```rust
+    /// # use counter::sketch::CountMinSketch;
+    /// let words = "a b a c a b".split(' ');
+    /// let mut sketch = CountMinSketch::<str>::new(1024, 4);
+    /// for word in words.clone() {
+    ///     sketch.update(word);
+    /// }
+    /// let heavy = sketch.count_heavy(words, 2);
+    /// assert_eq!(heavy[&"a"], 3);
+    /// assert_eq!(heavy[&"b"], 2);
+    /// ```
+    pub fn count_heavy<I>(&self, iterable: I, threshold: usize) -> Counter<I::Item, usize, S>
+    where
+        I: IntoIterator,
+        I::Item: Borrow<T> + Hash + Eq,
+        S: Clone,
+    {
+        let mut counter = Counter::with_hasher(self.hash_builder.clone());
+        for item in iterable {
+            let is_heavy = match counter.map.get_mut(&item) {
+                Some(count) => {
+                    *count += 1;
+                    continue;
+                }
+                None => self.estimate(item.borrow()) >= threshold,
+            };
+            if is_heavy {
+                counter.map.insert(item, 1);
+            }
+        }
+        counter
+    }
+
+    /// Reset every count in this sketch to zero
+    pub fn clear(&mut self) {
+        for count in self.counts.iter_mut() {
+            *count = 0;
+        }
+        self.total = 0;
+    }
+}
+
+impl<T, S> Clone for CountMinSketch<T, S>
+where
+    T: ?Sized,
+    S: Clone,
+{
+    fn clone(&self) -> CountMinSketch<T, S> {
+        CountMinSketch {
+            width: self.width,
+            depth: self.depth,
+            counts: self.counts.clone(),
+            total: self.total,
+            hash_builder: self.hash_builder.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T, S> fmt::Debug for CountMinSketch<T, S>
+where
+    T: ?Sized,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CountMinSketch")
+            .field("width", &self.width)
+            .field("depth", &self.depth)
+            .field("total", &self.total)
+            .finish()
+    }
+}
+
+/// The estimated count of an item tracked by [`HeavyHitters`](struct.HeavyHitters.html) or
+/// [`LossyCounter`](struct.LossyCounter.html).
+///
+/// The true count of the item is at least `count - error` and at most `count`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Estimate {
+    /// An upper bound on the count of the item
+    pub count: usize,
+    /// How much `count` may overestimate the count of the item
+    pub error: usize,
+}
+
+impl Estimate {
+    /// A lower bound on the count of the item
+    pub fn lower_bound(&self) -> usize {
+        self.count - self.error
+    }
+}
+
+/// Finds the most common items in a stream while tracking at most `capacity` items, using the
+/// [Space-Saving](https://www.cs.ucsb.edu/sites/default/files/documents/2005-23.pdf) algorithm.
+///
+/// When a new item arrives and the tracker is full, it replaces the item with the lowest
+/// count, inheriting that count as its error. Every item whose count is greater than
+/// `total / capacity` is guaranteed to be tracked.
+///
+/// ```rust
+/// # use counter::sketch::HeavyHitters;
+/// let mut hitters = HeavyHitters::new(3);
+/// for c in "abacabad".chars() {
+///     hitters.update(c);
+/// }
+/// let top = hitters.top(1);
+/// assert_eq!(top[0].0, 'a');
+/// assert!(top[0].1.lower_bound() <= 4 && 4 <= top[0].1.count);
+/// ```
+#[derive(Clone, Debug)]
+pub struct HeavyHitters<T: Hash + Eq, S = RandomState> {
+    map: HashMap<T, Estimate, S>,
+    capacity: usize,
+    total: usize,
+}
+
+impl<T> HeavyHitters<T>
+where
+    T: Hash + Eq,
+{
+    /// Create a new, empty `HeavyHitters` which tracks at most `capacity` items
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn new(capacity: usize) -> HeavyHitters<T> {
+        HeavyHitters::with_hasher(capacity, RandomState::default())
+    }
+}
+
+impl<T, S> HeavyHitters<T, S>
+where
+    T: Hash + Eq,
+    S: BuildHasher,
+{
+    /// Create a new, empty `HeavyHitters` which tracks at most `capacity` items,
+    /// and which will use the given hasher to hash the items
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn with_hasher(capacity: usize, hash_builder: S) -> HeavyHitters<T, S> {
+        assert!(capacity > 0, "HeavyHitters needs a nonzero capacity");
+        HeavyHitters {
+            map: HashMap::with_capacity_and_hasher(capacity, hash_builder),
+            capacity,
+            total: 0,
+        }
+    }
+
+    /// The greatest number of items which can be tracked at once
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The number of items being tracked
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if no items have been counted yet.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// The number of items which have been counted, including those no longer tracked
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// Add one to the count of the given item
+    ///
+    /// If the tracker is full and the item is not tracked, this takes time proportional
+    /// to the capacity.
+    pub fn update(&mut self, item: T) {
+        self.add_count(item, 1);
+    }
+
+    /// Add `count` to the count of the given item
+    pub fn add_count(&mut self, item: T, count: usize) {
+        if count == 0 {
+            return;
+        }
+        self.total += count;
+        if let Some(estimate) = self.map.get_mut(&item) {
+            estimate.count += count;
+            return;
+        }
+        let mut error = 0;
+        if self.map.len() == self.capacity {
+            let min = self
+                .map
+                .values()
+                .map(|estimate| estimate.count)
+                .min()
+                .unwrap_or(0);
+            let mut evicted = false;
+            self.map.retain(|_, estimate| {
+                if !evicted && estimate.count == min {
+                    evicted = true;
+                    false
+                } else {
+                    true
+                }
+            });
+            error = min;
+        }
+        self.map.insert(
+            item,
+            Estimate {
+                count: error + count,
+                error,
+            },
+        );
+    }
+
+    /// Add the counts of the elements from the given iterable
+    pub fn update_from<I>(&mut self, iterable: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        for item in iterable {
+            self.update(item);
+        }
+    }
+
+    /// Returns the estimated count of the given item, or `None` if it is not tracked.
+    ///
+    /// An untracked item occurred at most as many times as the lowest tracked count.
+    pub fn estimate<Q>(&self, item: &Q) -> Option<Estimate>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.get(item).cloned()
+    }
+
+    /// Returns the `k` tracked items with the highest counts, from highest to lowest,
+    /// along with their estimated counts.
+    ///
+    /// Note that the ordering of duplicates is unstable.
+    pub fn top(&self, k: usize) -> Vec<(T, Estimate)>
+    where
+        T: Clone,
+    {
+        let mut items = self
+            .map
+            .iter()
+            .map(|(item, &estimate)| (item.clone(), estimate))
+            .collect::<Vec<_>>();
+        items.sort_by_key(|(_, estimate)| Reverse(estimate.count));
+        items.truncate(k);
+        items
+    }
+
+    /// Returns the tracked items which are guaranteed to have a count of at least `threshold`,
+    /// with their lower bounds, as a `Counter`.
+    pub fn guaranteed(&self, threshold: usize) -> Counter<T, usize, S>
+    where
+        T: Clone,
+        S: Clone,
+    {
+        let mut counter = Counter::with_hasher(self.map.hasher().clone());
+        counter.map.extend(
+            self.map
+                .iter()
+                .filter(|(_, estimate)| estimate.lower_bound() >= threshold.max(1))
+                .map(|(item, estimate)| (item.clone(), estimate.lower_bound())),
+        );
+        counter
+    }
+}
+
+/// Finds the items whose frequency in a stream is above a given support, using the
+/// [Lossy Counting](https://www.vldb.org/conf/2002/S10P03.pdf) algorithm.
+///
+/// The stream is divided into buckets of `ceil(1 / epsilon)` items, and at the end of each
+/// bucket the items which can't have a frequency above `epsilon` are dropped. Each count
+/// underestimates the true count by at most `epsilon * total`, and the counter tracks at
+/// most `(1 / epsilon) * log(epsilon * total)` items.
+///
+/// ```rust
+/// # use counter::sketch::LossyCounter;
+/// let mut lossy = LossyCounter::new(0.01);
+/// lossy.update_from((0..10_000u32).map(|n| if n % 4 == 0 { 0 } else { n }));
+/// let frequent = lossy.frequent(0.2);
+/// assert_eq!(frequent.keys().collect::<Vec<_>>(), vec![&0]);
+/// assert!(frequent[&0] >= 2_400);
+/// ```
+#[derive(Clone, Debug)]
+pub struct LossyCounter<T: Hash + Eq, S = RandomState> {
+    map: HashMap<T, Estimate, S>,
+    epsilon: f64,
+    width: usize,
+    total: usize,
+}
+
+impl<T> LossyCounter<T>
+where
+    T: Hash + Eq,
+{
+    /// Create a new, empty `LossyCounter` whose counts are off by at most `epsilon` times the
+    /// number of items counted
+    ///
+    /// # Panics
+    ///
+    /// Panics if `epsilon` is not between 0 and 1.
+    pub fn new(epsilon: f64) -> LossyCounter<T> {
+        LossyCounter::with_hasher(epsilon, RandomState::default())
+    }
+}
+
+impl<T, S> LossyCounter<T, S>
+where
+    T: Hash + Eq,
+    S: BuildHasher,
+{
+    /// Create a new, empty `LossyCounter` whose counts are off by at most `epsilon` times the
+    /// number of items counted, and which will use the given hasher to hash the items
+    ///
+    /// # Panics
+    ///
+    /// Panics if `epsilon` is not between 0 and 1.
+    pub fn with_hasher(epsilon: f64, hash_builder: S) -> LossyCounter<T, S> {
+        assert!(
+            epsilon > 0.0 && epsilon < 1.0,
+            "the epsilon of a LossyCounter must be between 0 and 1"
+        );
+        let inverse = 1.0 / epsilon;
+        let mut width = inverse as usize;
+        if (width as f64) < inverse {
+            width += 1;
+        }
+        LossyCounter {
+            map: HashMap::with_hasher(hash_builder),
+            epsilon,
+            width,
+            total: 0,
+        }
+    }
+
+    /// The greatest fraction of the total by which a count may be too low
+    pub fn epsilon(&self) -> f64 {
+        self.epsilon
+    }
+
+    /// The number of items being tracked
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if no items are being tracked.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// The number of items which have been counted, including those no longer tracked
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// Add one to the count of the given item
+    pub fn update(&mut self, item: T) {
+        self.total += 1;
+        let bucket = self.total.div_ceil(self.width);
+        self.map
+            .entry(item)
+            .and_modify(|estimate| estimate.count += 1)
+            .or_insert(Estimate {
+                count: bucket,
+                error: bucket - 1,
+            });
+        if self.total.is_multiple_of(self.width) {
+            self.map.retain(|_, estimate| estimate.count > bucket);
+        }
+    }
+
+    /// Add the counts of the elements from the given iterable
+    pub fn update_from<I>(&mut self, iterable: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        for item in iterable {
+            self.update(item);
+        }
+    }
+
+    /// Returns the estimated count of the given item, or `None` if it is not tracked.
+    ///
+    /// An untracked item occurred at most `epsilon * total` times.
+    pub fn estimate<Q>(&self, item: &Q) -> Option<Estimate>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.get(item).cloned()
+    }
+
+    /// Returns the items which may make up at least `support` of the total, with their lower
+    /// bounds, as a `Counter`.
+    ///
+    /// Every item which makes up at least `support` of the total is included, and no
+    /// item which makes up less than `support - epsilon` of it.
+    pub fn frequent(&self, support: f64) -> Counter<T, usize, S>
+    where
+        T: Clone,
+        S: Clone,
+    {
+        let threshold = (support - self.epsilon) * self.total as f64;
+        let mut counter = Counter::with_hasher(self.map.hasher().clone());
+        counter.map.extend(
+            self.map
+                .iter()
+                .filter(|(_, estimate)| {
+                    estimate.lower_bound() > 0 && estimate.lower_bound() as f64 >= threshold
+                })
+                .map(|(item, estimate)| (item.clone(), estimate.lower_bound())),
+        );
+        counter
+    }
+}
+
+/// Estimates the number of distinct items in a stream with the
+/// [HyperLogLog](https://en.wikipedia.org/wiki/HyperLogLog) algorithm.
+///
+/// It keeps `2^precision` one-byte registers, and its estimates have a relative standard
+/// error of about `1.04 / sqrt(2^precision)`, so a precision of 12 uses 4 KiB for an error
+/// of about 1.6%.
+///
+/// ```rust
+/// # use counter::sketch::DistinctEstimator;
+/// let mut distinct = DistinctEstimator::<u32>::new(12);
+/// distinct.update(0..10_000);
+/// distinct.update(5_000..15_000);
+/// let estimate = distinct.estimate();
+/// assert!(13_500 < estimate && estimate < 16_500);
+/// ```
+#[cfg(feature = "std")]
+pub struct DistinctEstimator<T: ?Sized, S = RandomState> {
+    precision: u8,
+    registers: Vec<u8>,
+    hash_builder: S,
+    marker: PhantomData<fn(&T)>,
+}
+
+#[cfg(feature = "std")]
+impl<T> DistinctEstimator<T>
+where
+    T: Hash + ?Sized,
+{
+    /// Create a new, empty `DistinctEstimator` with `2^precision` registers
+    ///
+    /// # Panics
+    ///
+    /// Panics if `precision` is not between 4 and 18.
+    pub fn new(precision: u8) -> DistinctEstimator<T> {
+        DistinctEstimator::with_hasher(precision, RandomState::default())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T, S> DistinctEstimator<T, S>
+where
+    T: Hash + ?Sized,
+    S: BuildHasher,
+{
+    /// Create a new, empty `DistinctEstimator` with `2^precision` registers,
+    /// which will use the given hasher to hash the items
+    ///
+    /// # Panics
+    ///
+    /// Panics if `precision` is not between 4 and 18.
+    pub fn with_hasher(precision: u8, hash_builder: S) -> DistinctEstimator<T, S> {
+        assert!(
+            (4..=18).contains(&precision),
+            "the precision of a DistinctEstimator must be between 4 and 18"
+        );
+        DistinctEstimator {
+            precision,
+            registers: vec![0; 1 << precision],
+            hash_builder,
+            marker: PhantomData,
+        }
+    }
+
+    /// The number of bits of each hash which select a register
+    pub fn precision(&self) -> u8 {
+        self.precision
+    }
+
+    /// Record one occurrence of the given item
+    pub fn insert(&mut self, item: &T) {
+        let hash = self.hash_builder.hash_one(item);
+        let index = (hash >> (64 - self.precision)) as usize;
+        // The guard bit keeps the rank within the bits which weren't used for the index.
+        let rest = (hash << self.precision) | (1 << (self.precision - 1));
+        let rank = rest.leading_zeros() as u8 + 1;
+        if rank > self.registers[index] {
+            self.registers[index] = rank;
+        }
+    }
+
+    /// Record the elements from the given iterable
+    pub fn update<I>(&mut self, iterable: I)
+    where
+        I: IntoIterator,
+        I::Item: Borrow<T>,
+    {
+        for item in iterable {
+            self.insert(item.borrow());
+        }
+    }
+
+    /// Returns the estimated number of distinct items which have been recorded.
+    pub fn estimate(&self) -> usize {
+        let m = self.registers.len() as f64;
+        let alpha = match self.registers.len() {
+            16 => 0.673,
+            32 => 0.697,
+            64 => 0.709,
+            _ => 0.7213 / (1.0 + 1.079 / m),
+        };
+        let sum = self
+            .registers
+            .iter()
+            .map(|&rank| 1.0 / (1u64 << rank) as f64)
+            .sum::<f64>();
+        let raw = alpha * m * m / sum;
+        let zeros = self.registers.iter().filter(|&&rank| rank == 0).count();
+        let estimate = if raw <= 2.5 * m && zeros > 0 {
+            // Linear counting is more accurate while many registers are still empty.
+            m * (m / zeros as f64).ln()
+        } else {
+            raw
+        };
+        estimate.round() as usize
+    }
+
+    /// Record every item which was recorded by `other`.
+    ///
+    /// As with [`CountMinSketch::merge`](struct.CountMinSketch.html#method.merge), both
+    /// estimators must hash their items the same way.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the two estimators have different precisions.
+    pub fn merge(&mut self, other: &DistinctEstimator<T, S>) {
+        assert!(
+            self.precision == other.precision,
+            "cannot merge DistinctEstimators of different precisions"
+        );
+        for (rank, &other_rank) in self.registers.iter_mut().zip(other.registers.iter()) {
+            if other_rank > *rank {
+                *rank = other_rank;
+            }
+        }
+    }
+
+    /// Forget every item which has been recorded
+    pub fn clear(&mut self) {
+        for rank in self.registers.iter_mut() {
+            *rank = 0;
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T, S> Clone for DistinctEstimator<T, S>
+where
+    T: ?Sized,
+    S: Clone,
+{
+    fn clone(&self) -> DistinctEstimator<T, S> {
+        DistinctEstimator {
+            precision: self.precision,
+            registers: self.registers.clone(),
+            hash_builder: self.hash_builder.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T, S> fmt::Debug for DistinctEstimator<T, S>
+where
+    T: ?Sized,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DistinctEstimator")
+            .field("precision", &self.precision)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_never_too_low() {
+        let mut sketch = CountMinSketch::<u32>::new(16, 3);
+        let counter = (0..1000u32).map(|n| n % 37).collect::<Counter<_>>();
+        for (item, &count) in counter.iter() {
+            sketch.add_count(item, count);
+        }
+        assert_eq!(sketch.total(), 1000);
+        for (item, &count) in counter.iter() {
+            assert!(sketch.estimate(item) >= count);
+        }
+    }
+
+    #[test]
+    fn test_estimate_exact_when_wide() {
+        let mut sketch = CountMinSketch::<str>::new(1 << 16, 4);
+        sketch.add_count("a", 5);
+        sketch.update("b");
+        assert_eq!(sketch.estimate("a"), 5);
+        assert_eq!(sketch.estimate("b"), 1);
+        assert_eq!(sketch.estimate("c"), 0);
+
+        sketch.clear();
+        assert_eq!(sketch.estimate("a"), 0);
+        assert_eq!(sketch.total(), 0);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut a = CountMinSketch::<char>::new(1 << 12, 4);
+        let mut b = a.clone();
+        for c in "abbccc".chars() {
+            a.update(&c);
+            b.update(&c);
+        }
+        a.merge(&b);
+        assert_eq!(a.total(), 12);
+        assert_eq!(a.estimate(&'c'), 6);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_merge_different_dimensions() {
+        let mut a = CountMinSketch::<char>::new(8, 2);
+        let b = CountMinSketch::<char>::new(8, 3);
+        a.merge(&b);
+    }
+
+    #[test]
+    fn test_count_heavy() {
+        let stream = "abbcccdddd".chars();
+        let mut sketch = CountMinSketch::<char>::new(1 << 12, 4);
+        for c in stream.clone() {
+            sketch.update(&c);
+        }
+        let heavy = sketch.count_heavy(stream, 3);
+        let expected = hashmap! { 'c' => 3, 'd' => 4 };
+        assert!(heavy.map == expected);
+    }
+
+    #[test]
+    fn test_heavy_hitters_exact_within_capacity() {
+        let mut hitters = HeavyHitters::new(4);
+        hitters.update_from("abbccc".chars());
+        assert_eq!(hitters.len(), 3);
+        assert_eq!(hitters.total(), 6);
+        assert_eq!(hitters.estimate(&'c'), Some(Estimate { count: 3, error: 0 }));
+        assert_eq!(hitters.estimate(&'d'), None);
+        let top = hitters.top(2);
+        assert_eq!(top[0].0, 'c');
+        assert_eq!(top[1].0, 'b');
+    }
+
+    #[test]
+    fn test_heavy_hitters_eviction() {
+        let mut hitters = HeavyHitters::new(2);
+        hitters.update_from("aaabc".chars());
+        assert_eq!(hitters.len(), 2);
+        assert_eq!(hitters.estimate(&'a'), Some(Estimate { count: 3, error: 0 }));
+        assert_eq!(hitters.estimate(&'b'), None);
+        assert_eq!(hitters.estimate(&'c'), Some(Estimate { count: 2, error: 1 }));
+    }
+
+    #[test]
+    fn test_heavy_hitters_bounds() {
+        let stream = (0..10_000u32).map(|n| if n % 3 == 0 { 0 } else { n % 97 });
+        let exact = stream.clone().collect::<Counter<_>>();
+        let mut hitters = HeavyHitters::new(20);
+        hitters.update_from(stream);
+
+        let estimate = hitters.estimate(&0).unwrap();
+        assert!(estimate.lower_bound() <= exact[&0] && exact[&0] <= estimate.count);
+        for (item, estimate) in hitters.top(20) {
+            assert!(estimate.lower_bound() <= exact[&item]);
+            assert!(exact[&item] <= estimate.count);
+        }
+        let expected = hashmap! { 0 => estimate.lower_bound() };
+        assert!(hitters.guaranteed(1000).map == expected);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_distinct_small() {
+        let mut distinct = DistinctEstimator::<str>::new(16);
+        assert_eq!(distinct.estimate(), 0);
+        distinct.update("a b c a b a".split(' '));
+        assert_eq!(distinct.estimate(), 3);
+        distinct.clear();
+        assert_eq!(distinct.estimate(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_distinct_large() {
+        let mut distinct = DistinctEstimator::<u64>::new(14);
+        distinct.update(0..100_000u64);
+        let estimate = distinct.estimate() as f64;
+        assert!((estimate - 100_000.0).abs() < 5_000.0);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_distinct_merge() {
+        let mut a = DistinctEstimator::<u64>::new(12);
+        let mut b = a.clone();
+        a.update(0..20_000u64);
+        b.update(10_000..30_000u64);
+        a.merge(&b);
+        let estimate = a.estimate() as f64;
+        assert!((estimate - 30_000.0).abs() < 3_000.0);
+    }
+
+    #[test]
+    fn test_lossy_counter_bounds() {
+        let stream = (0..20_000u32).map(|n| match n % 10 {
+            0..=2 => 0,
+            3 => 1,
+            _ => n,
+        });
+        let exact = stream.clone().collect::<Counter<_>>();
+        let mut lossy = LossyCounter::new(0.005);
+        lossy.update_from(stream);
+        assert_eq!(lossy.total(), 20_000);
+        assert!(lossy.len() < 2_000);
+
+        for item in &[0, 1] {
+            let estimate = lossy.estimate(item).unwrap();
+            assert!(estimate.lower_bound() <= exact[item] && exact[item] <= estimate.count);
+            assert!(exact[item] - estimate.lower_bound() <= 100);
+        }
+        let frequent = lossy.frequent(0.05);
+        let mut keys = frequent.keys().cloned().collect::<Vec<_>>();
+        keys.sort();
+        assert_eq!(keys, vec![0, 1]);
+    }
+
+    #[test]
+    fn test_lossy_counter_prunes() {
+        let mut lossy = LossyCounter::new(0.5);
+        lossy.update_from("ab".chars());
+        assert!(lossy.is_empty());
+        lossy.update_from("cc".chars());
+        assert_eq!(lossy.estimate(&'c'), Some(Estimate { count: 3, error: 1 }));
+    }
+}
@@ -0,0 +1,652 @@
+//! Statistics which compare counters or summarize them.
+
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use core::cmp::Ordering;
+use core::hash::{BuildHasher, Hash};
+use core::ops::Add;
+
+use num_traits::{ToPrimitive, Zero};
+
+use Counter;
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: ToPrimitive,
+    S: BuildHasher,
+{
+    /// The number of items with a positive count in both this counter and `other`, and in
+    /// either of them
+    fn shared_and_all_keys(&self, other: &Counter<T, N, S>) -> (usize, usize) {
+        let len = self.positive_counts().count();
+        let other_len = other.positive_counts().count();
+        let shared = self
+            .map
+            .keys()
+            .filter(|item| self.positive_count_of(item) > 0.0)
+            .filter(|item| other.positive_count_of(item) > 0.0)
+            .count();
+        (shared, len + other_len - shared)
+    }
+
+    /// The cosine of the angle between this counter and `other`, treating each as a vector
+    /// with a dimension for each item
+    ///
+    /// This is one for counters whose counts are proportional, and zero for counters with no
+    /// items in common or an empty counter. Counts which aren't positive are left out. This
+    /// method requires the `std` feature.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let a = Counter::<_>::init("the cat sat".split(' '));
+    /// let b = Counter::<_>::init("the cat sat the cat sat".split(' '));
+    /// let c = Counter::<_>::init("a dog ran".split(' '));
+    /// assert!((a.cosine_similarity(&b) - 1.0).abs() < 1e-12);
+    /// assert_eq!(a.cosine_similarity(&c), 0.0);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn cosine_similarity(&self, other: &Counter<T, N, S>) -> f64 {
+        let dot = self
+            .map
+            .keys()
+            .map(|item| self.positive_count_of(item) * other.positive_count_of(item))
+            .sum::<f64>();
+        let norm = self.positive_counts().map(|count| count * count).sum::<f64>();
+        let other_norm = other.positive_counts().map(|count| count * count).sum::<f64>();
+        if norm == 0.0 || other_norm == 0.0 {
+            0.0
+        } else {
+            dot / (norm.sqrt() * other_norm.sqrt())
+        }
+    }
+
+    /// The cosine similarity of the sets of items in this counter and `other`, ignoring how
+    /// often each was counted
+    ///
+    /// This method requires the `std` feature.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let a = "aaab".chars().collect::<Counter<_>>();
+    /// let b = "bc".chars().collect::<Counter<_>>();
+    /// assert!((a.cosine_similarity_of_keys(&b) - 0.5).abs() < 1e-12);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn cosine_similarity_of_keys(&self, other: &Counter<T, N, S>) -> f64 {
+        let (shared, all) = self.shared_and_all_keys(other);
+        let len = self.positive_counts().count();
+        let other_len = all + shared - len;
+        if len == 0 || other_len == 0 {
+            0.0
+        } else {
+            shared as f64 / ((len * other_len) as f64).sqrt()
+        }
+    }
+
+    /// The weighted Jaccard index of this counter and `other`: the total of the smaller count
+    /// of each item divided by the total of the larger count
+    ///
+    /// This is the size of the multisets' intersection divided by the size of their union.
+    /// Counts which aren't positive are left out, and two empty counters have an index of one.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let a = "aab".chars().collect::<Counter<_>>();
+    /// let b = "abbc".chars().collect::<Counter<_>>();
+    /// // the intersection is "ab" and the union is "aabbc"
+    /// assert_eq!(a.jaccard_index(&b), 0.4);
+    /// ```
+    pub fn jaccard_index(&self, other: &Counter<T, N, S>) -> f64 {
+        let mut intersection = 0.0;
+        let mut union = 0.0;
+        for item in self.map.keys() {
+            let count = self.positive_count_of(item);
+            let other_count = other.positive_count_of(item);
+            intersection += count.min(other_count);
+            union += count.max(other_count);
+        }
+        for item in other.map.keys().filter(|item| !self.map.contains_key(*item)) {
+            union += other.positive_count_of(item);
+        }
+        if union == 0.0 {
+            1.0
+        } else {
+            intersection / union
+        }
+    }
+
+    /// The Jaccard index of the sets of items in this counter and `other`, ignoring how often
+    /// each was counted: the number of items in both divided by the number in either
+    ///
+    /// Two empty counters have an index of one.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let a = "aab".chars().collect::<Counter<_>>();
+    /// let b = "abbc".chars().collect::<Counter<_>>();
+    /// assert_eq!(a.jaccard_index_of_keys(&b), 2.0 / 3.0);
+    /// ```
+    pub fn jaccard_index_of_keys(&self, other: &Counter<T, N, S>) -> f64 {
+        match self.shared_and_all_keys(other) {
+            (_, 0) => 1.0,
+            (shared, all) => shared as f64 / all as f64,
+        }
+    }
+
+    /// Calls `f` with the observed and expected counts in this counter and in `other` of each
+    /// item counted in either, treating them as the two rows of a contingency table
+    fn for_each_cell<F>(&self, other: &Counter<T, N, S>, mut f: F) -> usize
+    where
+        F: FnMut(f64, f64),
+    {
+        let total = self.positive_counts().sum::<f64>();
+        let other_total = other.positive_counts().sum::<f64>();
+        let grand_total = total + other_total;
+        let mut columns = 0;
+        let mut cell = |count: f64, other_count: f64| {
+            let column_total = count + other_count;
+            if column_total > 0.0 {
+                f(count, total * column_total / grand_total);
+                f(other_count, other_total * column_total / grand_total);
+                columns += 1;
+            }
+        };
+        for item in self.map.keys() {
+            cell(self.positive_count_of(item), other.positive_count_of(item));
+        }
+        for item in other.map.keys().filter(|item| !self.map.contains_key(*item)) {
+            cell(0.0, other.positive_count_of(item));
+        }
+        columns
+    }
+
+    /// Pearson's chi-squared test of whether this counter and `other` were drawn from the same
+    /// distribution
+    ///
+    /// The counters are the two rows of a contingency table with a column for each item
+    /// counted in either, so the test has one degree of freedom fewer than there are items.
+    /// Counts which aren't positive are left out. Look the statistic up in a chi-squared
+    /// distribution to get the p-value.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let control = Counter::<_>::from_pairs(vec![("click", 30), ("skip", 70)]);
+    /// let variant = Counter::<_>::from_pairs(vec![("click", 45), ("skip", 55)]);
+    /// let test = control.chi_square(&variant);
+    /// assert_eq!(test.degrees_of_freedom, 1);
+    /// assert!((test.statistic - 4.8).abs() < 1e-12);
+    /// ```
+    pub fn chi_square(&self, other: &Counter<T, N, S>) -> TestStatistic {
+        let mut statistic = 0.0;
+        let columns = self.for_each_cell(other, |observed, expected| {
+            if expected > 0.0 {
+                statistic += (observed - expected) * (observed - expected) / expected;
+            }
+        });
+        TestStatistic {
+            statistic,
+            degrees_of_freedom: columns.saturating_sub(1),
+        }
+    }
+
+    /// The G-test (log-likelihood ratio test) of whether this counter and `other` were drawn
+    /// from the same distribution
+    ///
+    /// This is set up like [`chi_square`](#method.chi_square), and gives close to the same
+    /// statistic for large counts. This method requires the `std` feature.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let control = Counter::<_>::from_pairs(vec![("click", 30), ("skip", 70)]);
+    /// let variant = Counter::<_>::from_pairs(vec![("click", 45), ("skip", 55)]);
+    /// let test = control.g_test(&variant);
+    /// assert_eq!(test.degrees_of_freedom, 1);
+    /// assert!((test.statistic - 4.83).abs() < 0.01);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn g_test(&self, other: &Counter<T, N, S>) -> TestStatistic {
+        let mut statistic = 0.0;
+        let columns = self.for_each_cell(other, |observed, expected| {
+            if observed > 0.0 {
+                statistic += 2.0 * observed * (observed / expected).ln();
+            }
+        });
+        TestStatistic {
+            statistic,
+            degrees_of_freedom: columns.saturating_sub(1),
+        }
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: ToPrimitive,
+    S: BuildHasher,
+{
+    /// The mean of the values which `value` gives the items, weighted by their counts, or
+    /// `None` if there are no positive counts
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let latencies = Counter::<_>::from_pairs(vec![("fast", 8), ("slow", 2)]);
+    /// let mean = latencies.weighted_mean_by(|&speed| if speed == "fast" { 10.0 } else { 100.0 });
+    /// assert_eq!(mean, Some(28.0));
+    /// ```
+    pub fn weighted_mean_by<F>(&self, mut value: F) -> Option<f64>
+    where
+        F: FnMut(&T) -> f64,
+    {
+        let mut total = 0.0;
+        let mut sum = 0.0;
+        for item in self.map.keys() {
+            let weight = self.positive_count_of(item);
+            if weight > 0.0 {
+                total += weight;
+                sum += weight * value(item);
+            }
+        }
+        if total > 0.0 {
+            Some(sum / total)
+        } else {
+            None
+        }
+    }
+
+    /// The variance of the values which `value` gives the items, weighted by their counts, or
+    /// `None` if there are no positive counts
+    ///
+    /// This is the population variance: the weighted mean of the squared differences from
+    /// the [weighted mean](#method.weighted_mean_by).
+    pub fn weighted_variance_by<F>(&self, mut value: F) -> Option<f64>
+    where
+        F: FnMut(&T) -> f64,
+    {
+        let mean = self.weighted_mean_by(&mut value)?;
+        self.weighted_mean_by(|item| (value(item) - mean) * (value(item) - mean))
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq + ToPrimitive,
+    N: ToPrimitive,
+    S: BuildHasher,
+{
+    /// The mean of the items, weighted by their counts, or `None` if there are no positive
+    /// counts
+    ///
+    /// Items which can't be represented as an `f64` are treated as zero.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let rolls = Counter::<u8>::init(vec![1, 2, 2, 6, 6, 6]);
+    /// assert_eq!(rolls.weighted_mean(), Some(23.0 / 6.0));
+    /// ```
+    pub fn weighted_mean(&self) -> Option<f64> {
+        self.weighted_mean_by(|item| item.to_f64().unwrap_or(0.0))
+    }
+
+    /// The population variance of the items, weighted by their counts, or `None` if there are
+    /// no positive counts
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let rolls = Counter::<u8>::init(vec![2, 4, 4, 4, 5, 5, 7, 9]);
+    /// assert_eq!(rolls.weighted_variance(), Some(4.0));
+    /// ```
+    pub fn weighted_variance(&self) -> Option<f64> {
+        self.weighted_variance_by(|item| item.to_f64().unwrap_or(0.0))
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: ToPrimitive,
+    S: BuildHasher,
+{
+    /// An estimate of the exponent `s` of Zipf's law, under which the count of the item of rank
+    /// `r` is proportional to `1 / r^s`, or `None` if fewer than two counts are positive
+    ///
+    /// This fits a line to the logarithms of the ranks and the counts by least squares and
+    /// negates its slope. Counts which aren't positive are left out. This method requires the
+    /// `std` feature.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = Counter::<_>::from_pairs(vec![("the", 60), ("of", 30), ("and", 20)]);
+    /// let exponent = counter.fit_zipf().unwrap();
+    /// assert!((exponent - 1.0).abs() < 1e-9);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn fit_zipf(&self) -> Option<f64> {
+        let mut counts = self.positive_counts().collect::<Vec<_>>();
+        if counts.len() < 2 {
+            return None;
+        }
+        counts.sort_by(|a, b| b.partial_cmp(a).unwrap_or(Ordering::Equal));
+
+        let len = counts.len() as f64;
+        let points = counts
+            .iter()
+            .enumerate()
+            .map(|(i, count)| (((i + 1) as f64).ln(), count.ln()))
+            .collect::<Vec<_>>();
+        let mean_x = points.iter().map(|&(x, _)| x).sum::<f64>() / len;
+        let mean_y = points.iter().map(|&(_, y)| y).sum::<f64>() / len;
+        let (covariance, variance) = points.iter().fold((0.0, 0.0), |(cov, var), &(x, y)| {
+            let dx = x - mean_x;
+            (cov + dx * (y - mean_y), var + dx * dx)
+        });
+        Some(-covariance / variance)
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Clone + Ord,
+    S: BuildHasher,
+{
+    /// The counts from the highest to the lowest, each with its rank, starting at 1
+    ///
+    /// This is the data which Zipf's law is about; see [`fit_zipf`](#method.fit_zipf). Tied
+    /// counts get consecutive ranks.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = "abbcccc".chars().collect::<Counter<_>>();
+    /// assert_eq!(counter.rank_frequency(), vec![(1, 4), (2, 2), (3, 1)]);
+    /// ```
+    pub fn rank_frequency(&self) -> Vec<(usize, N)> {
+        let mut counts = self.map.values().cloned().collect::<Vec<_>>();
+        counts.sort_by(|a, b| b.cmp(a));
+        counts
+            .into_iter()
+            .enumerate()
+            .map(|(i, count)| (i + 1, count))
+            .collect()
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Ord + Zero,
+    S: BuildHasher,
+{
+    /// All of the items tied for the highest count, in an arbitrary order
+    ///
+    /// Items whose counts aren't positive are never part of the mode, so it is empty if no
+    /// count is positive.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = "abbcc".chars().collect::<Counter<_>>();
+    /// let mut mode = counter.mode();
+    /// mode.sort();
+    /// assert_eq!(mode, vec![&'b', &'c']);
+    /// ```
+    pub fn mode(&self) -> Vec<&T> {
+        let zero = N::zero();
+        let max = match self.map.values().filter(|&count| *count > zero).max() {
+            Some(max) => max,
+            None => return Vec::new(),
+        };
+        self.map
+            .iter()
+            .filter(|&(_, count)| count == max)
+            .map(|(item, _)| item)
+            .collect()
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq + Ord,
+    N: Clone + Ord + Zero + Add<Output = N>,
+    S: BuildHasher,
+{
+    /// The items in ascending order, each with the total of its count and the counts of the
+    /// items before it
+    ///
+    /// Items whose counts aren't positive are skipped, so the last total is the total of the
+    /// positive counts.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let sizes = Counter::<u32>::init(vec![512, 64, 64, 4096, 512, 64]);
+    /// assert_eq!(sizes.cumulative(), vec![(&64, 3), (&512, 5), (&4096, 6)]);
+    /// ```
+    pub fn cumulative(&self) -> Vec<(&T, N)> {
+        let zero = N::zero();
+        let mut entries = self
+            .map
+            .iter()
+            .filter(|&(_, count)| *count > zero)
+            .collect::<Vec<_>>();
+        entries.sort_by_key(|&(item, _)| item);
+
+        let mut cumulative = N::zero();
+        entries
+            .into_iter()
+            .map(|(item, count)| {
+                cumulative = cumulative.clone() + count.clone();
+                (item, cumulative.clone())
+            })
+            .collect()
+    }
+
+    /// The median of the items, each repeated as many times as its count, or `None` if no
+    /// count is positive
+    ///
+    /// When the total is even this is the lower of the two middle items. The counter is never
+    /// expanded: the items are sorted and the counts are added up until half of the total is
+    /// reached. Items whose counts aren't positive are skipped.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let rolls = Counter::<u8>::init(vec![1, 1, 1, 2, 6, 6]);
+    /// assert_eq!(rolls.median_by_multiplicity(), Some(&1));
+    /// let rolls = Counter::<u8>::init(vec![1, 1, 2, 6, 6]);
+    /// assert_eq!(rolls.median_by_multiplicity(), Some(&2));
+    /// ```
+    pub fn median_by_multiplicity(&self) -> Option<&T> {
+        let cumulative = self.cumulative();
+        let total = cumulative.last()?.1.clone();
+        cumulative
+            .into_iter()
+            .find(|(_, cumulative)| cumulative.clone() + cumulative.clone() >= total)
+            .map(|(item, _)| item)
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq + Ord,
+    N: Clone + Ord + Zero + Add<Output = N> + ToPrimitive,
+    S: BuildHasher,
+{
+    /// The item at the `p`th percentile of the items, each repeated as many times as its count,
+    /// or `None` if no count is positive
+    ///
+    /// This is the nearest-rank percentile: the least item for which at least `p` percent of
+    /// the total is at or below it. Items whose counts aren't positive are skipped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `p` isn't between 0 and 100.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mut latencies = Counter::<u32>::new();
+    /// latencies.add_count(10, 90);
+    /// latencies.add_count(250, 9);
+    /// latencies.add_count(1200, 1);
+    /// assert_eq!(latencies.percentile(50.0), Some(&10));
+    /// assert_eq!(latencies.percentile(99.0), Some(&250));
+    /// assert_eq!(latencies.percentile(100.0), Some(&1200));
+    /// ```
+    pub fn percentile(&self, p: f64) -> Option<&T> {
+        assert!(
+            (0.0..=100.0).contains(&p),
+            "percentile must be between 0 and 100"
+        );
+        let cumulative = self.cumulative();
+        let total = cumulative.last()?.1.to_f64()?;
+        let rank = p / 100.0 * total;
+        cumulative
+            .iter()
+            .find(|(_, cumulative)| matches!(cumulative.to_f64(), Some(c) if c >= rank))
+            .or_else(|| cumulative.last())
+            .map(|&(item, _)| item)
+    }
+}
+
+/// The result of a statistical test comparing two counters.
+///
+/// This `struct` is created by the [`chi_square`](struct.Counter.html#method.chi_square) and
+/// [`g_test`](struct.Counter.html#method.g_test) methods on [`Counter`](struct.Counter.html).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TestStatistic {
+    /// The value of the test statistic
+    pub statistic: f64,
+    /// The number of degrees of freedom of the distribution to compare the statistic to
+    pub degrees_of_freedom: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_cosine_similarity() {
+        let a = Counter::<_, i32>::from_pairs(vec![('x', 1), ('y', 2), ('z', -5)]);
+        let b = Counter::<_, i32>::from_pairs(vec![('x', 2), ('w', 2)]);
+        let expected = 2.0 / (5f64.sqrt() * 8f64.sqrt());
+        assert!((a.cosine_similarity(&b) - expected).abs() < 1e-12);
+        assert!((b.cosine_similarity(&a) - expected).abs() < 1e-12);
+        assert_eq!(a.cosine_similarity(&Counter::new()), 0.0);
+
+        assert!((a.cosine_similarity_of_keys(&b) - 0.5).abs() < 1e-12);
+        assert_eq!(Counter::<char>::new().cosine_similarity_of_keys(&Counter::new()), 0.0);
+    }
+
+    #[test]
+    fn test_jaccard_index() {
+        let a = Counter::<_, i32>::from_pairs(vec![('x', 3), ('y', 1), ('z', -5)]);
+        let mut b = Counter::<_, i32>::from_pairs(vec![('x', 1), ('w', 2)]);
+        b.insert('y', 0);
+        assert_eq!(a.jaccard_index(&b), 1.0 / 6.0);
+        assert_eq!(b.jaccard_index(&a), 1.0 / 6.0);
+        assert_eq!(a.jaccard_index_of_keys(&b), 1.0 / 3.0);
+
+        let empty = Counter::<char>::new();
+        assert_eq!(empty.jaccard_index(&empty), 1.0);
+        assert_eq!(empty.jaccard_index_of_keys(&empty), 1.0);
+        assert_eq!(a.jaccard_index_of_keys(&Counter::new()), 0.0);
+    }
+
+    #[test]
+    fn test_weighted_mean_and_variance() {
+        let mut counter = Counter::<i32, i32>::from_pairs(vec![(-1, 3), (3, 1), (100, -2)]);
+        counter.insert(50, 0);
+        assert_eq!(counter.weighted_mean(), Some(0.0));
+        assert_eq!(counter.weighted_variance(), Some(3.0));
+        assert_eq!(counter.weighted_mean_by(|&item| f64::from(item * 2)), Some(0.0));
+        assert_eq!(counter.weighted_variance_by(|&item| f64::from(item * 2)), Some(12.0));
+
+        assert_eq!(Counter::<u8>::new().weighted_mean(), None);
+        assert_eq!(Counter::<u8>::new().weighted_variance(), None);
+    }
+
+    #[test]
+    fn test_mode_and_median() {
+        let mut counter = Counter::<_, i32>::from_pairs(vec![(1, 2), (5, 2), (9, 1), (0, -4)]);
+        let mut mode = counter.mode();
+        mode.sort();
+        assert_eq!(mode, vec![&1, &5]);
+        assert_eq!(counter.median_by_multiplicity(), Some(&5));
+
+        counter.insert(9, 3);
+        assert_eq!(counter.mode(), vec![&9]);
+        assert_eq!(counter.median_by_multiplicity(), Some(&5));
+
+        counter.insert(9, 4);
+        assert_eq!(counter.median_by_multiplicity(), Some(&5));
+        counter.insert(9, 5);
+        assert_eq!(counter.median_by_multiplicity(), Some(&9));
+
+        let empty = Counter::<u8, i32>::from_pairs(vec![(1, 0), (2, -1)]);
+        assert!(empty.mode().is_empty());
+        assert_eq!(empty.median_by_multiplicity(), None);
+    }
+
+    #[test]
+    fn test_cumulative_and_percentile() {
+        let counter = Counter::<_, i32>::from_pairs(vec![('c', 5), ('a', 3), ('b', 2), ('z', -1)]);
+        assert_eq!(counter.cumulative(), vec![(&'a', 3), (&'b', 5), (&'c', 10)]);
+        assert_eq!(counter.percentile(0.0), Some(&'a'));
+        assert_eq!(counter.percentile(30.0), Some(&'a'));
+        assert_eq!(counter.percentile(31.0), Some(&'b'));
+        assert_eq!(counter.percentile(50.0), Some(&'b'));
+        assert_eq!(counter.percentile(100.0), Some(&'c'));
+
+        let empty = Counter::<char, i32>::new();
+        assert!(empty.cumulative().is_empty());
+        assert_eq!(empty.percentile(50.0), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_percentile_out_of_range() {
+        Counter::<_>::init(vec![1]).percentile(101.0);
+    }
+
+    #[test]
+    fn test_rank_frequency() {
+        let counter = Counter::<_>::init("aaabbbcd".chars());
+        assert_eq!(counter.rank_frequency(), vec![(1, 3), (2, 3), (3, 1), (4, 1)]);
+        assert!(Counter::<char>::new().rank_frequency().is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_fit_zipf() {
+        let counter =
+            Counter::<_, i32>::from_pairs(vec![(1, 400), (2, 100), (3, 44), (4, 25), (5, 0)]);
+        assert!((counter.fit_zipf().unwrap() - 2.0).abs() < 0.01);
+        assert_eq!(Counter::<_>::init(vec!['a']).fit_zipf(), None);
+    }
+
+    #[test]
+    fn test_chi_square() {
+        // expected counts: 10 and 20 for each of x and y, 10 and 20 for z
+        let a = Counter::<_, u32>::from_pairs(vec![('x', 15), ('y', 5), ('z', 10)]);
+        let b = Counter::<_, u32>::from_pairs(vec![('x', 15), ('y', 25), ('z', 20)]);
+        let chi = a.chi_square(&b);
+        assert_eq!(chi.degrees_of_freedom, 2);
+        assert!((chi.statistic - 7.5).abs() < 1e-12);
+
+        let empty = Counter::<char, u32>::new();
+        assert_eq!(
+            empty.chi_square(&empty),
+            TestStatistic { statistic: 0.0, degrees_of_freedom: 0 }
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_g_test() {
+        let a = Counter::<_, u32>::from_pairs(vec![('x', 15), ('y', 5), ('z', 10)]);
+        let b = Counter::<_, u32>::from_pairs(vec![('x', 15), ('y', 25), ('z', 20)]);
+        let g = a.g_test(&b);
+        assert_eq!(g.degrees_of_freedom, 2);
+        let expected = 2.0
+            * (15.0 * 1.5f64.ln() + 5.0 * 0.5f64.ln() + 15.0 * 0.75f64.ln() + 25.0 * 1.25f64.ln());
+        assert!((g.statistic - expected).abs() < 1e-12);
+    }
+}
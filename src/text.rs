@@ -0,0 +1,254 @@
+use alloc::string::String;
+use core::ops::AddAssign;
+
+use num_traits::{One, Zero};
+
+#[cfg(feature = "unicode-normalization")]
+use unicode_normalization::UnicodeNormalization;
+#[cfg(feature = "unicode-segmentation")]
+use unicode_segmentation::UnicodeSegmentation;
+
+use Counter;
+
+#[cfg(feature = "unicode-segmentation")]
+fn split_words(text: &str) -> impl Iterator<Item = &str> {
+    text.unicode_words()
+}
+
+#[cfg(not(feature = "unicode-segmentation"))]
+fn split_words(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .map(|word| word.trim_matches('\''))
+}
+
+/// A Unicode normalization form to apply to text before counting it, with
+/// [`Counter::init_normalized`](struct.Counter.html#method.init_normalized).
+///
+/// This type requires the `unicode-normalization` feature.
+#[cfg(feature = "unicode-normalization")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Normalization {
+    /// Canonical composition: equivalent sequences such as a precomposed "é" and an "e"
+    /// followed by a combining accent are counted together
+    Nfc,
+    /// Compatibility composition: like `Nfc`, but compatibility variants such as "ﬁ" and "fi",
+    /// or full-width and ordinary letters, are counted together too
+    Nfkc,
+    /// Like `Nfc`, but also case-folded, so that "É" and "é" are counted together
+    NfcCaseFold,
+    /// Like `Nfkc`, but also case-folded
+    NfkcCaseFold,
+}
+
+#[cfg(feature = "unicode-normalization")]
+impl Normalization {
+    /// Normalize `text` into this form
+    ///
+    /// Case folding is done with `str::to_lowercase`, after which the text is normalized again.
+    pub fn apply(self, text: &str) -> String {
+        match self {
+            Normalization::Nfc => text.nfc().collect(),
+            Normalization::Nfkc => text.nfkc().collect(),
+            Normalization::NfcCaseFold => text.to_lowercase().nfc().collect(),
+            Normalization::NfkcCaseFold => {
+                text.nfkc().collect::<String>().to_lowercase().nfkc().collect()
+            }
+        }
+    }
+}
+
+impl<N> Counter<String, N>
+where
+    N: PartialOrd + AddAssign + Zero + One,
+{
+    /// Create a new `Counter` of the words of `text`, lowercased
+    ///
+    /// Punctuation and whitespace between the words is skipped. With the
+    /// `unicode-segmentation` feature, the words are found with the Unicode word boundary
+    /// rules. Without it, the text is split at every character which is neither alphanumeric
+    /// nor an apostrophe, and apostrophes at the start or end of a word are dropped, so that
+    /// for example "3.5" counts as the two words "3" and "5".
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let words = Counter::<_>::words("The cat's hat. The CAT!");
+    /// assert_eq!(words[&"the".to_string()], 2);
+    /// assert_eq!(words[&"cat".to_string()], 1);
+    /// assert_eq!(words[&"cat's".to_string()], 1);
+    /// ```
+    pub fn words(text: &str) -> Counter<String, N> {
+        Counter::words_with(text, split_words)
+    }
+
+    /// Create a new `Counter` of the words of `text`, lowercased, using `tokenizer` to
+    /// split the text into words
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let words = Counter::<_>::words_with("a-b A c", |text| {
+    ///     text.split(|c| c == ' ' || c == '-')
+    /// });
+    /// assert_eq!(words[&"a".to_string()], 2);
+    /// assert_eq!(words.len(), 3);
+    /// ```
+    pub fn words_with<'a, F, I>(text: &'a str, tokenizer: F) -> Counter<String, N>
+    where
+        F: FnOnce(&'a str) -> I,
+        I: IntoIterator<Item = &'a str>,
+    {
+        Counter::init(
+            tokenizer(text)
+                .into_iter()
+                .filter(|word| !word.is_empty())
+                .map(str::to_lowercase),
+        )
+    }
+
+    /// Create a new `Counter` of the strings from the given iterable, each normalized with
+    /// `normalization` before it is counted
+    ///
+    /// This method requires the `unicode-normalization` feature.
+    ///
+    /// ```rust
+    /// # use counter::{Counter, Normalization};
+    /// let tokens = vec!["caf\u{e9}", "cafe\u{301}", "CAF\u{c9}"];
+    /// let counter = Counter::<_>::init_normalized(tokens.iter(), Normalization::Nfc);
+    /// assert_eq!(counter[&"caf\u{e9}".to_string()], 2);
+    ///
+    /// let counter = Counter::<_>::init_normalized(tokens.iter(), Normalization::NfcCaseFold);
+    /// assert_eq!(counter[&"caf\u{e9}".to_string()], 3);
+    /// ```
+    #[cfg(feature = "unicode-normalization")]
+    pub fn init_normalized<I>(iterable: I, normalization: Normalization) -> Counter<String, N>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let mut counter = Counter::new();
+        counter.update_normalized(iterable, normalization);
+        counter
+    }
+
+    /// Add the counts of the strings from the given iterable, each normalized with
+    /// `normalization` before it is counted
+    ///
+    /// This method requires the `unicode-normalization` feature.
+    #[cfg(feature = "unicode-normalization")]
+    pub fn update_normalized<I>(&mut self, iterable: I, normalization: Normalization)
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        self.update(
+            iterable
+                .into_iter()
+                .map(|text| normalization.apply(text.as_ref())),
+        );
+    }
+}
+
+#[cfg(feature = "unicode-segmentation")]
+impl<'a, N> Counter<&'a str, N>
+where
+    N: PartialOrd + AddAssign + Zero + One,
+{
+    /// Create a new `Counter` of the extended grapheme clusters of `text`, as slices of `text`
+    ///
+    /// Unlike counting `char`s, this counts an emoji sequence or a letter with combining
+    /// marks as the single character a reader sees. This method requires the
+    /// `unicode-segmentation` feature.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// // "e" followed by a combining acute accent, and a family emoji made of four people
+    /// let text = concat!(
+    ///     "e\u{301}e\u{301}",
+    ///     "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}\u{200D}\u{1F466}",
+    /// );
+    /// let graphemes = Counter::<_>::graphemes(text);
+    /// assert_eq!(graphemes.len(), 2);
+    /// assert_eq!(graphemes[&"e\u{301}"], 2);
+    /// assert_eq!(text.chars().count(), 11);
+    /// ```
+    pub fn graphemes(text: &'a str) -> Counter<&'a str, N> {
+        Counter::init(text.graphemes(true))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::string::ToString;
+
+    #[test]
+    fn test_words_with() {
+        let counter = Counter::<_>::words_with("Hello  hello\tWORLD", str::split_whitespace);
+        let expected = hashmap! { "hello".to_string() => 2, "world".to_string() => 1 };
+        assert!(counter.map == expected);
+
+        let counter = Counter::<String>::words_with(",,a,,", |text| text.split(','));
+        assert!(counter.map == hashmap! { "a".to_string() => 1 });
+    }
+
+    #[test]
+    #[cfg(feature = "unicode-segmentation")]
+    fn test_words() {
+        let counter = Counter::<_>::words("Ünïcödé words, ünïcödé WORDS; 3.5 and done.");
+        let expected = hashmap! {
+            "ünïcödé".to_string() => 2,
+            "words".to_string() => 2,
+            "3.5".to_string() => 1,
+            "and".to_string() => 1,
+            "done".to_string() => 1,
+        };
+        assert!(counter.map == expected);
+    }
+
+    #[test]
+    #[cfg(not(feature = "unicode-segmentation"))]
+    fn test_words_fallback() {
+        let counter = Counter::<_>::words("Ünïcödé words, ünïcödé WORDS; 'quoted' 3.5 don't.");
+        let expected = hashmap! {
+            "ünïcödé".to_string() => 2,
+            "words".to_string() => 2,
+            "quoted".to_string() => 1,
+            "3".to_string() => 1,
+            "5".to_string() => 1,
+            "don't".to_string() => 1,
+        };
+        assert!(counter.map == expected);
+    }
+
+    #[test]
+    #[cfg(feature = "unicode-segmentation")]
+    fn test_graphemes() {
+        let counter = Counter::<_>::graphemes("a\u{308}a\u{308}a\r\n\u{1F1EB}\u{1F1F7}");
+        let expected = hashmap! {
+            "a\u{308}" => 2,
+            "a" => 1,
+            "\r\n" => 1,
+            "\u{1F1EB}\u{1F1F7}" => 1,
+        };
+        assert!(counter.map == expected);
+    }
+
+    #[test]
+    #[cfg(feature = "unicode-normalization")]
+    fn test_normalized() {
+        let tokens = ["\u{fb01}ne", "fine", "FINE", "\u{ff26}ine"];
+        let counter = Counter::<_>::init_normalized(&tokens, Normalization::Nfc);
+        assert_eq!(counter.len(), 4);
+        let counter = Counter::<_>::init_normalized(&tokens, Normalization::Nfkc);
+        let expected = hashmap! {
+            "fine".to_string() => 2,
+            "Fine".to_string() => 1,
+            "FINE".to_string() => 1,
+        };
+        assert!(counter.map == expected);
+        let mut counter = Counter::<_>::init_normalized(&tokens, Normalization::NfkcCaseFold);
+        assert!(counter.map == hashmap! { "fine".to_string() => 4 });
+
+        counter.update_normalized(vec!["Fine"], Normalization::NfcCaseFold);
+        assert_eq!(counter[&"fine".to_string()], 5);
+    }
+}